@@ -36,36 +36,208 @@
 //!
 //! The specific values in example above applies to Linux, but the same logic applies to Windows.
 
+use crate::chords::*;
 use crate::custom_action::*;
 use crate::keys::*;
 use crate::layers::*;
+use crate::overrides::*;
+use crate::sequences::*;
+use crate::socd::*;
+use crate::zippy::*;
 
 use anyhow::{anyhow, bail, Result};
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 
 use kanata_keyberon::action::*;
 use kanata_keyberon::key_code::*;
 use kanata_keyberon::layout::*;
 
 pub type KanataAction = Action<CustomAction>;
-pub type KanataLayout = Layout<256, 1, MAX_LAYERS, CustomAction>;
+pub type KanataLayout = Layout<KEYS_IN_ROW, LAYOUT_ROWS, MAX_LAYERS, CustomAction>;
 
 pub struct Cfg {
     pub mapped_keys: MappedKeys,
     pub key_outputs: KeyOutputs,
     pub items: HashMap<String, String>,
     pub layout: KanataLayout,
+    /// Names of the layers, indexed like the layers of the layout.
+    pub layer_names: Vec<String>,
+    pub chords: Chords,
+    pub sequences: Sequences,
+    pub overrides: Overrides,
+    pub socd: Socd,
+    pub zippy: Zippy,
+    pub key_repeat: KeyRepeat,
+    pub debounce: Debounce,
+    /// Milliseconds that a key must be held before its press is handled. Zero handles presses
+    /// immediately.
+    pub slow_keys: u16,
+    /// Whether bounce-keys is initially on. The bounce-keys-toggle action turns it on or off.
+    pub bounce_keys: bool,
+    /// Milliseconds after the release of a key during which another press of the key is
+    /// discarded while bounce-keys is on.
+    pub bounce_keys_time: u16,
+    pub mouse_move: MouseMove,
+    pub mouse_wheel: MouseWheel,
+    /// Whether the direction of the mouse wheel actions and of the wheel events that pass through
+    /// kanata is reversed, e.g. for natural scrolling.
+    pub reverse_scroll: bool,
+    /// Built-in layout that text from commands and the hex digits of unicode on Linux are typed
+    /// with. It is configured to match the layout of the OS, which is not queried.
+    pub text_layout: TextLayout,
+    /// Whether kanata keeps running if no input device is found at startup, in which case the
+    /// devices are read once they are plugged in.
+    #[cfg(target_os = "linux")]
+    pub continue_if_no_devs: bool,
+    #[cfg(target_os = "linux")]
+    pub device_keys: Vec<DeviceKeys>,
+    /// Name and IDs of the virtual output devices.
+    #[cfg(target_os = "linux")]
+    pub uinput: crate::oskbd::UinputDevice,
+    #[cfg(target_os = "linux")]
+    pub event_policies: EventPolicies,
+    /// How unicode characters are entered.
+    pub unicode_method: crate::oskbd::UnicodeMethod,
+    /// Port of the TCP server that clients control kanata through.
+    pub tcp_server_port: Option<u16>,
+    /// Hardware ID patterns of the keyboards that are read through the Interception driver
+    /// instead of the low level keyboard hook, where no patterns select all keyboards.
+    #[cfg(target_os = "windows")]
+    pub interception: Option<Vec<String>>,
+    #[cfg(target_os = "windows")]
+    pub altgr: AltGrBehaviour,
+    /// Whether the keyboard hook and the output identify keys by their scancodes.
+    #[cfg(target_os = "windows")]
+    pub scancodes: bool,
+    #[cfg(target_os = "windows")]
+    pub injected_events: crate::oskbd::InjectedEvents,
+}
+
+/// How the fake lctl events that Windows sends for AltGr on international layouts are handled.
+#[cfg(target_os = "windows")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AltGrBehaviour {
+    /// The lctl events are handled like those of the key.
+    DoNothing,
+    /// The lctl press that comes right before the ralt press is released again.
+    CancelLctlPress,
+    /// lctl is released when ralt is released, in case the release of lctl is missing.
+    AddLctlRelease,
+}
+
+/// What happens to input events of a type other than keys.
+#[cfg(target_os = "linux")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EventPolicy {
+    /// The events are written to the virtual devices unchanged.
+    Forward,
+    /// The events are discarded.
+    Drop,
+    /// Absolute X and Y positions are turned into relative pointer movement, e.g. to use the
+    /// touchpad of a keyboard like a mouse. Other absolute axes are discarded.
+    Relative,
+}
+
+/// What happens to the events of the input devices that are not key events, per event type.
+/// Mouse wheel directions that are mapped in defsrc are handled as keys regardless.
+#[cfg(target_os = "linux")]
+#[derive(Debug, Clone, Copy)]
+pub struct EventPolicies {
+    pub rel: EventPolicy,
+    pub abs: EventPolicy,
+    pub msc: EventPolicy,
+    pub sw: EventPolicy,
+}
+
+/// Keys of input devices that are treated as other keys, e.g. to map the number keys of a macro pad
+/// separately from the number keys of the keyboard.
+#[cfg(target_os = "linux")]
+pub struct DeviceKeys {
+    /// Paths or name patterns of the devices, like the items of linux-dev-exclude.
+    pub devices: Vec<String>,
+    /// Keys of the devices and the keys that they are treated as.
+    pub keys: HashMap<OsCode, OsCode>,
+}
+
+/// Speed of the mouse pointer movement of the movemouse actions. The speed ramps up linearly from
+/// the start speed to the max speed while the pointer moves.
+#[derive(Debug, Clone)]
+pub struct MouseMove {
+    /// Pixels per second when the pointer starts moving.
+    pub start_speed: u16,
+    /// Pixels per second once the ramp time has passed.
+    pub max_speed: u16,
+    /// Milliseconds from the start speed to the max speed.
+    pub ramp_time: u16,
+}
+
+/// Scrolling of the held mwheel actions.
+#[derive(Debug, Clone)]
+pub struct MouseWheel {
+    /// Milliseconds between two scrolls while a mwheel action is held. Zero scrolls one notch per
+    /// press instead.
+    pub interval: u16,
+    /// Distance of a scroll while a mwheel action is held, in units of 1/120 of a notch.
+    pub distance: u16,
+}
+
+/// How a held key is repeated.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RepeatMode {
+    /// The repeat events of the OS are forwarded for the output of the key.
+    Os,
+    /// Kanata repeats the output of the key itself, independent of the OS settings.
+    Kanata,
+    /// The key is not repeated.
+    None,
+}
+
+/// How held keys are repeated.
+#[derive(Debug, Clone)]
+pub struct KeyRepeat {
+    /// Milliseconds between the press of a key and its first repeat by kanata.
+    pub delay: u16,
+    /// Milliseconds between two repeats by kanata.
+    pub interval: u16,
+    default_mode: RepeatMode,
+    /// Repeat modes of the physical keys listed in defrepeat.
+    modes: HashMap<u8, RepeatMode>,
+}
+
+impl KeyRepeat {
+    /// Returns the repeat mode of the physical key at `coord`.
+    pub fn mode(&self, coord: u8) -> RepeatMode {
+        self.modes.get(&coord).copied().unwrap_or(self.default_mode)
+    }
+
+    /// Returns whether all keys are repeated by the OS.
+    #[cfg(target_os = "linux")]
+    pub fn is_os_only(&self) -> bool {
+        self.default_mode == RepeatMode::Os && self.modes.values().all(|m| *m == RepeatMode::Os)
+    }
+}
+
+/// Debounce times of the physical keys. A press of a key is discarded if it arrives within the
+/// debounce time of the previous release of the key, which filters the chatter of worn switches.
+#[derive(Debug, Clone, Default)]
+pub struct Debounce {
+    /// Debounce time in milliseconds of keys that are not listed in defdebounce. Zero disables
+    /// debouncing.
+    default_time: u16,
+    /// Debounce times of the physical keys listed in defdebounce.
+    times: HashMap<u8, u16>,
+}
+
+impl Debounce {
+    /// Returns the debounce time in milliseconds of the physical key at `coord`.
+    pub fn time(&self, coord: u8) -> u16 {
+        self.times.get(&coord).copied().unwrap_or(self.default_time)
+    }
 }
 
 impl Cfg {
     pub fn new_from_file(p: &std::path::Path) -> Result<Self> {
-        let (items, mapped_keys, key_outputs, layout) = parse_cfg(p)?;
-        Ok(Self {
-            items,
-            mapped_keys,
-            key_outputs,
-            layout,
-        })
+        parse_cfg(p)
     }
 }
 
@@ -113,22 +285,113 @@ fn parse_f13_f24() {
     parse_cfg(&std::path::PathBuf::from("./cfg_samples/f13_f24.kbd")).unwrap();
 }
 
-/// Parse a configuration file.
-fn parse_cfg(
-    p: &std::path::Path,
-) -> Result<(
-    HashMap<String, String>,
-    MappedKeys,
-    KeyOutputs,
-    KanataLayout,
-)> {
-    let cfg = std::fs::read_to_string(p)?;
+#[test]
+fn parse_collects_all_errors() {
+    let text = "(defcfg tap-time x hold-time y)
+        (defsrc a b)
+        (defalias p (bad) q (worse))
+        (deflayer base @p (nope))";
+    let mut errs = CfgErrors::default();
+    parse_cfg_str(text, &mut errs).unwrap();
+    let msgs = errs.0.iter().map(|e| e.to_string()).collect::<Vec<_>>();
+    assert_eq!(msgs.len(), 5, "{:?}", msgs);
+    assert!(msgs[0].contains("tap-time"));
+    assert!(msgs[1].contains("hold-time"));
+}
+
+/// Parse actions written one after another, e.g. `(one-shot lsft) (one-shot lctl)`.
+#[cfg(test)]
+fn parse_test_actions(text: &str, s: &mut ParsedState) -> Vec<&'static KanataAction> {
+    let roots = parse_root_exprs(&format!("({})", text)).unwrap();
+    roots[0]
+        .iter()
+        .map(|ac| parse_action(ac, s).unwrap())
+        .collect()
+}
+
+#[test]
+fn nested_one_shots_have_the_coords_of_their_keys() {
+    let mut s = ParsedState::default();
+    let actions = parse_test_actions(
+        "(tap-hold 200 200 (one-shot lsft stack) lsft) (tap-hold 200 200 (one-shot lctl stack) lctl)",
+        &mut s,
+    );
+    let one_shot = |ac, coord| match set_action_coord(ac, coord) {
+        Action::HoldTap {
+            tap: Action::Custom(CustomAction::OneShot { coord, cfg, .. }),
+            ..
+        } => (*coord, cfg.stack),
+        _ => panic!("expected a tap-hold with a one-shot tap action"),
+    };
+    // Different coordinates let both one-shots be active at the same time, so they stack.
+    assert_eq!(one_shot(actions[0], (0, 42)), ((0, 42), true));
+    assert_eq!(one_shot(actions[1], (0, 29)), ((0, 29), true));
+}
+
+#[test]
+fn nested_tap_dances_have_their_own_coords() {
+    let mut s = ParsedState::default();
+    let actions = parse_test_actions(
+        "(tap-hold 200 200 (tap-dance 200 (a b)) lsft) (tap-dance 200 ((one-shot lsft) (one-shot lctl)))",
+        &mut s,
+    );
+    match set_action_coord(actions[0], (0, 30)) {
+        Action::HoldTap {
+            tap: Action::Custom(CustomAction::TapDance { coord, .. }),
+            ..
+        } => assert_eq!(*coord, (0, 30)),
+        _ => panic!("expected a tap-hold with a tap-dance tap action"),
+    }
+    // The one-shots are actions of the virtual keys of the second tap-dance.
+    let coords: Vec<KeyCoord> = virtual_key_actions(&s)
+        .iter()
+        .filter_map(|ac| match ac {
+            Action::Custom(CustomAction::OneShot { coord, .. }) => Some(*coord),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(coords.len(), 2);
+    assert_ne!(coords[0], coords[1]);
+    assert!(coords.iter().all(|(row, _)| *row == VIRTUAL_ROW));
+}
+
+#[test]
+fn dev_list_is_split_on_unescaped_colons() {
+    assert_eq!(
+        parse_dev_list(r"/dev/input/event1:My Keyboard\: Extra:"),
+        ["/dev/input/event1", "My Keyboard: Extra"]
+    );
+    assert_eq!(parse_dev_list(r"a\\b\"), [r"a\\b\"]);
+    assert_eq!(parse_dev_list("::"), Vec::<String>::new());
+}
 
-    let root_expr_strs = get_root_exprs(&cfg)?;
-    let mut root_exprs = Vec::new();
-    for expr in root_expr_strs {
-        root_exprs.push(parse_expr(&expr)?);
+/// Parse a configuration file. The error lists every error found in the file, each prefixed with
+/// the path of the file.
+fn parse_cfg(p: &std::path::Path) -> Result<Cfg> {
+    let cfg = std::fs::read_to_string(p).map_err(|e| anyhow!("{}: {}", p.display(), e))?;
+    let mut errs = CfgErrors::default();
+    match parse_cfg_str(&cfg, &mut errs) {
+        Ok(parsed) if errs.0.is_empty() => return Ok(parsed),
+        Ok(_) => {}
+        Err(e) => errs.0.push(e),
     }
+    let msgs = errs
+        .0
+        .iter()
+        .map(|e| match e.downcast_ref::<ParseError>() {
+            Some(pe) => format_parse_error(pe, p, &cfg),
+            None => format!("{}: {:#}", p.display(), e),
+        })
+        .collect::<Vec<_>>();
+    bail!(msgs.join("\n"))
+}
+
+/// Parse the content of a configuration file. Errors that parsing can continue after are added to
+/// `errs`, and the returned error is one that it can't continue after.
+fn parse_cfg_str(text: &str, errs: &mut CfgErrors) -> Result<Cfg> {
+    let root_exprs = parse_root_exprs(text)?;
+    let mut root_exprs = filter_platform_root_exprs(root_exprs)?;
+    substitute_vars(&mut root_exprs)?;
 
     let cfg_expr = root_exprs
         .iter()
@@ -142,7 +405,7 @@ fn parse_cfg(
     {
         bail!("Only one defcfg is allowed in the configuration")
     }
-    let cfg = parse_defcfg(cfg_expr)?;
+    let cfg = parse_defcfg(cfg_expr, errs)?;
 
     let src_expr = root_exprs
         .iter()
@@ -156,11 +419,13 @@ fn parse_cfg(
     {
         bail!("Only one defsrc is allowed in the configuration")
     }
-    let (src, mapping_order) = parse_defsrc(src_expr)?;
+    let (src, mapping_order, src_rows) = parse_defsrc(src_expr, text)?;
 
     let layer_exprs = root_exprs
         .iter()
-        .filter(gen_first_atom_filter("deflayer"))
+        .filter(|expr| {
+            gen_first_atom_filter("deflayer")(expr) || gen_first_atom_filter("deflayermap")(expr)
+        })
         .collect::<Vec<_>>();
     if layer_exprs.is_empty() {
         bail!("No deflayer expressions exist. At least one layer must be defined.")
@@ -168,20 +433,493 @@ fn parse_cfg(
     if layer_exprs.len() > MAX_LAYERS {
         bail!("Exceeded the maximum layer count of {}", MAX_LAYERS)
     }
-    let layer_idxs = parse_layer_indexes(&layer_exprs, mapping_order.len())?;
+    let mut s = ParsedState {
+        layer_idxs: parse_layer_indexes(&layer_exprs, mapping_order.len())?,
+        one_shot_defaults: parse_one_shot_defaults(&cfg, errs),
+        default_tap_time: errs
+            .check(parse_cfg_timeout(&cfg, "tap-time"))
+            .unwrap_or(200),
+        default_hold_time: errs
+            .check(parse_cfg_timeout(&cfg, "hold-time"))
+            .unwrap_or(200),
+        default_autoshift_time: errs
+            .check(parse_cfg_timeout(&cfg, "autoshift-timeout"))
+            .unwrap_or(200),
+        macro_cancel_on_press: errs
+            .check(parse_cfg_flag(&cfg, "macro-cancel-on-press"))
+            .unwrap_or_default(),
+        is_cmd_enabled: errs
+            .check(parse_cfg_flag(&cfg, "danger-enable-cmd"))
+            .unwrap_or_default(),
+        src_rows,
+        screen_size: match cfg.get("screen-size") {
+            Some(v) => errs.check(parse_screen_size(v).ok_or_else(|| {
+                anyhow!(
+                    "invalid screen-size in defcfg: {}, expected e.g. 1920x1080",
+                    v
+                )
+            })),
+            None => crate::oskbd::screen_size(),
+        },
+        ..Default::default()
+    };
+    let mut layer_names = vec![String::new(); s.layer_idxs.len()];
+    for (name, i) in s.layer_idxs.iter() {
+        layer_names[*i] = name.clone();
+    }
 
     let alias_exprs = root_exprs
         .iter()
         .filter(gen_first_atom_filter("defalias"))
         .collect::<Vec<_>>();
-    let aliases = parse_aliases(&alias_exprs, &layer_idxs)?;
-    let klayers = parse_layers(&layer_exprs, &aliases, &layer_idxs, &mapping_order)?;
-    Ok((
-        cfg,
-        src,
-        create_key_outputs(&klayers),
-        create_layout(klayers),
-    ))
+    let vkey_exprs = root_exprs
+        .iter()
+        .filter(gen_first_atom_filter("defvirtualkeys"))
+        .collect::<Vec<_>>();
+    errs.check(parse_virtual_keys(&vkey_exprs, &mut s));
+
+    parse_aliases(&alias_exprs, &mut s, errs);
+
+    let chord_exprs = root_exprs
+        .iter()
+        .filter(gen_first_atom_filter("defchords"))
+        .collect::<Vec<_>>();
+    let chords = errs
+        .check(parse_chords(&chord_exprs, &mut s, &src))
+        .unwrap_or_default();
+
+    let seq_exprs = root_exprs
+        .iter()
+        .filter(gen_first_atom_filter("defseq"))
+        .collect::<Vec<_>>();
+    let sequences = Sequences::new(
+        errs.check(parse_sequences(&seq_exprs, &mut s, &src))
+            .unwrap_or_default(),
+        errs.check(parse_cfg_num(&cfg, "sequence-timeout", 1000))
+            .unwrap_or(1000),
+        match cfg.get("sequence-input-mode").map(|m| m.as_str()) {
+            Some("hidden") | None => SequenceInputMode::Hidden,
+            Some("visible") => SequenceInputMode::Visible,
+            Some("visible-backspaced") => SequenceInputMode::VisibleBackspaced,
+            Some(m) => {
+                errs.0.push(anyhow!("invalid sequence-input-mode in defcfg: {}, expected hidden, visible, or visible-backspaced", m));
+                SequenceInputMode::Hidden
+            }
+        },
+    );
+
+    let override_exprs = root_exprs
+        .iter()
+        .filter(gen_first_atom_filter("defoverrides"))
+        .collect::<Vec<_>>();
+    let overrides = Overrides::new(
+        errs.check(parse_overrides(&override_exprs))
+            .unwrap_or_default(),
+    );
+
+    let socd_exprs = root_exprs
+        .iter()
+        .filter(gen_first_atom_filter("defsocd"))
+        .collect::<Vec<_>>();
+    let socd = Socd::new(errs.check(parse_socd(&socd_exprs)).unwrap_or_default());
+
+    #[cfg(target_os = "linux")]
+    let uinput = parse_uinput_device(&cfg, errs);
+
+    #[cfg(target_os = "linux")]
+    let device_key_exprs = root_exprs
+        .iter()
+        .filter(gen_first_atom_filter("defdevice"))
+        .collect::<Vec<_>>();
+    #[cfg(target_os = "linux")]
+    let device_keys = errs
+        .check(parse_device_keys(&device_key_exprs))
+        .unwrap_or_default();
+
+    let zippy_exprs = root_exprs
+        .iter()
+        .filter(gen_first_atom_filter("defzippy"))
+        .collect::<Vec<_>>();
+    let zippy_suffix_exprs = root_exprs
+        .iter()
+        .filter(gen_first_atom_filter("defzippy-suffixes"))
+        .collect::<Vec<_>>();
+    let zippy = Zippy::new(
+        errs.check(parse_zippy(&zippy_exprs, "defzippy", &src))
+            .unwrap_or_default(),
+        errs.check(parse_zippy(&zippy_suffix_exprs, "defzippy-suffixes", &src))
+            .unwrap_or_default(),
+        errs.check(parse_cfg_num(&cfg, "zippy-timeout", 50))
+            .unwrap_or(50),
+    );
+
+    let repeat_exprs = root_exprs
+        .iter()
+        .filter(gen_first_atom_filter("defrepeat"))
+        .collect::<Vec<_>>();
+    let key_repeat = parse_key_repeat(&cfg, &repeat_exprs, &src, errs);
+    #[cfg(target_os = "linux")]
+    if uinput.repeats_keys() && !key_repeat.is_os_only() {
+        errs.0.push(anyhow!("linux-uinput-repeat-delay and linux-uinput-repeat-rate make the kernel repeat all keys, which can't be combined with the kanata or none repeat modes"))
+    }
+
+    let debounce_exprs = root_exprs
+        .iter()
+        .filter(gen_first_atom_filter("defdebounce"))
+        .collect::<Vec<_>>();
+    let debounce = parse_debounce(&cfg, &debounce_exprs, &src, errs);
+    let slow_keys = errs.check(parse_cfg_num(&cfg, "slow-keys", 0)).unwrap_or(0);
+    let bounce_keys = errs
+        .check(parse_cfg_flag(&cfg, "bounce-keys"))
+        .unwrap_or_default();
+    let bounce_keys_time = errs
+        .check(parse_cfg_num(&cfg, "bounce-keys-time", 300))
+        .unwrap_or(300);
+    let mouse_move = parse_mouse_move(&cfg, errs);
+    let mouse_wheel = parse_mouse_wheel(&cfg, errs);
+    let text_layout = match cfg.get("text-layout") {
+        Some(l) => errs
+            .check(TextLayout::from_name(l).ok_or_else(|| {
+                anyhow!(
+                    "invalid text-layout in defcfg: {}, expected us, uk, de, or fr",
+                    l
+                )
+            }))
+            .unwrap_or(TextLayout::Us),
+        None => TextLayout::Us,
+    };
+
+    let reverse_scroll = errs
+        .check(parse_cfg_flag(&cfg, "reverse-scroll"))
+        .unwrap_or_default();
+
+    let tcp_server_port = match cfg.get("tcp-server-port") {
+        Some(p) => errs.check(
+            p.parse()
+                .map_err(|e| anyhow!("invalid tcp-server-port in defcfg: {}", e)),
+        ),
+        None => None,
+    };
+
+    #[cfg(target_os = "linux")]
+    let continue_if_no_devs = errs
+        .check(parse_cfg_flag(&cfg, "linux-continue-if-no-devs-found"))
+        .unwrap_or_default();
+
+    #[cfg(target_os = "linux")]
+    let event_policies = parse_event_policies(&cfg, errs);
+
+    #[cfg(target_os = "windows")]
+    let interception = errs
+        .check(parse_cfg_flag(&cfg, "windows-interception"))
+        .unwrap_or_default();
+    #[cfg(target_os = "windows")]
+    let interception = match (interception, cfg.get("windows-interception-hwids")) {
+        (true, hwids) => Some(hwids.map(|v| parse_dev_list(v)).unwrap_or_default()),
+        (false, None) => None,
+        (false, Some(_)) => {
+            errs.0.push(anyhow!(
+                "windows-interception-hwids in defcfg requires windows-interception yes"
+            ));
+            None
+        }
+    };
+    #[cfg(target_os = "windows")]
+    let scancodes = errs
+        .check(parse_cfg_flag(&cfg, "windows-scancodes"))
+        .unwrap_or_default();
+    #[cfg(target_os = "windows")]
+    let injected_events = match cfg.get("windows-injected-events").map(|s| s.as_str()) {
+        None | Some("ignore") => crate::oskbd::InjectedEvents::Ignore,
+        Some("process") => crate::oskbd::InjectedEvents::Process,
+        Some("process-only-own") => crate::oskbd::InjectedEvents::ProcessOnlyOwn,
+        Some(val) => {
+            errs.0.push(anyhow!(
+                "invalid windows-injected-events in defcfg: {}, expected ignore, process, or process-only-own",
+                val
+            ));
+            crate::oskbd::InjectedEvents::Ignore
+        }
+    };
+    #[cfg(target_os = "windows")]
+    let altgr = match cfg.get("windows-altgr").map(|s| s.as_str()) {
+        None | Some("do-nothing") => AltGrBehaviour::DoNothing,
+        Some("cancel-lctl-press") => AltGrBehaviour::CancelLctlPress,
+        Some("add-lctl-release") => AltGrBehaviour::AddLctlRelease,
+        Some(val) => {
+            errs.0.push(anyhow!(
+                "invalid windows-altgr in defcfg: {}, expected do-nothing, cancel-lctl-press, or add-lctl-release",
+                val
+            ));
+            AltGrBehaviour::DoNothing
+        }
+    };
+
+    #[cfg(target_os = "windows")]
+    let unicode_method = {
+        let mut method = crate::oskbd::UnicodeMethod::default();
+        if let Some(backend) = cfg.get("windows-unicode-method") {
+            match backend.as_str() {
+                "sendinput" => method.backend = crate::oskbd::UnicodeBackend::SendInput,
+                "alt-numpad" => method.backend = crate::oskbd::UnicodeBackend::AltNumpad,
+                _ => errs.0.push(anyhow!(
+                    "invalid windows-unicode-method in defcfg: {}, expected sendinput or alt-numpad",
+                    backend
+                )),
+            }
+        }
+        if let Some(apps) = cfg.get("windows-unicode-alt-numpad-apps") {
+            method.alt_numpad_apps = parse_dev_list(apps)
+                .into_iter()
+                .map(|app| app.to_lowercase())
+                .collect();
+        }
+        method
+    };
+
+    #[cfg(target_os = "linux")]
+    let unicode_method = {
+        let mut method = crate::oskbd::UnicodeMethod::default();
+        if let Some(backend) = cfg.get("linux-unicode-backend") {
+            match backend.as_str() {
+                "keys" => method.backend = crate::oskbd::UnicodeBackend::Keys,
+                "wtype" if crate::oskbd::wtype_is_installed() => {
+                    method.backend = crate::oskbd::UnicodeBackend::Wtype
+                }
+                "wtype" => errs.0.push(anyhow!(
+                    "linux-unicode-backend is wtype, but the wtype program is not in PATH"
+                )),
+                _ => errs.0.push(anyhow!(
+                    "invalid linux-unicode-backend in defcfg: {}, expected keys or wtype",
+                    backend
+                )),
+            }
+        }
+        if let Some(trigger) = cfg.get("linux-unicode-trigger") {
+            if let Some(trigger) = errs.check(
+                parse_key_combos(trigger)
+                    .map_err(|e| anyhow!("invalid linux-unicode-trigger in defcfg: {}", e)),
+            ) {
+                method.trigger = trigger;
+            }
+        }
+        if let Some(terminator) = cfg.get("linux-unicode-terminator") {
+            if let Some(terminator) = errs.check(
+                parse_key_combos(terminator)
+                    .map_err(|e| anyhow!("invalid linux-unicode-terminator in defcfg: {}", e)),
+            ) {
+                method.terminator = terminator;
+            }
+        }
+        let compose_exprs = root_exprs
+            .iter()
+            .filter(gen_first_atom_filter("defcompose"))
+            .collect::<Vec<_>>();
+        method.compose = errs
+            .check(parse_compose(&compose_exprs, text_layout))
+            .unwrap_or_default();
+        if let Some(key) = cfg.get("linux-compose-key") {
+            let combos = errs.check(
+                parse_key_combos(key)
+                    .map_err(|e| anyhow!("invalid linux-compose-key in defcfg: {}", e)),
+            );
+            match combos.as_deref() {
+                Some([combo]) => method.compose_key = combo.clone(),
+                Some(_) => errs.0.push(anyhow!(
+                    "linux-compose-key in defcfg must be one key combo: {}",
+                    key
+                )),
+                None => {}
+            }
+        } else if !method.compose.is_empty() {
+            errs.0
+                .push(anyhow!("defcompose requires linux-compose-key in defcfg"))
+        }
+        method
+    };
+
+    let klayers = parse_layers(&layer_exprs, &mut s, &mapping_order, errs);
+    Ok(Cfg {
+        items: cfg,
+        mapped_keys: src,
+        key_outputs: create_key_outputs(&klayers),
+        layout: create_layout(klayers),
+        layer_names,
+        chords: Chords::new(chords),
+        sequences,
+        overrides,
+        socd,
+        zippy,
+        key_repeat,
+        debounce,
+        slow_keys,
+        bounce_keys,
+        bounce_keys_time,
+        mouse_move,
+        mouse_wheel,
+        reverse_scroll,
+        text_layout,
+        #[cfg(target_os = "linux")]
+        continue_if_no_devs,
+        #[cfg(target_os = "linux")]
+        device_keys,
+        #[cfg(target_os = "linux")]
+        event_policies,
+        unicode_method,
+        tcp_server_port,
+        #[cfg(target_os = "linux")]
+        uinput,
+        #[cfg(target_os = "windows")]
+        interception,
+        #[cfg(target_os = "windows")]
+        altgr,
+        #[cfg(target_os = "windows")]
+        scancodes,
+        #[cfg(target_os = "windows")]
+        injected_events,
+    })
+}
+
+/// Split the value of linux-dev, linux-dev-names, linux-dev-exclude, or
+/// windows-interception-hwids into the paths, names, or hardware IDs of the input devices.
+/// Multiple items are separated by colons, and a colon that is part of an item is escaped with a
+/// backslash, e.g. `/dev/input/by-id/usb-kbd\:1-event-kbd`.
+pub fn parse_dev_list(val: &str) -> Vec<String> {
+    let mut items = Vec::new();
+    let mut item = String::new();
+    let mut chars = val.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => match chars.next() {
+                Some(':') => item.push(':'),
+                Some(c) => {
+                    item.push('\\');
+                    item.push(c);
+                }
+                None => item.push('\\'),
+            },
+            ':' => items.push(std::mem::take(&mut item)),
+            c => item.push(c),
+        }
+    }
+    items.push(item);
+    items.retain(|item| !item.is_empty());
+    items
+}
+
+/// Parse key combos separated by spaces, e.g. `C-S-u` or `C-v u`, into the keys of each combo. The
+/// modifier prefixes are those of the key combo actions. `none` is no combo.
+#[cfg(target_os = "linux")]
+fn parse_key_combos(val: &str) -> Result<Vec<Vec<OsCode>>> {
+    if val == "none" {
+        return Ok(Vec::new());
+    }
+    val.split_whitespace()
+        .map(|combo| {
+            let mut rem = combo;
+            let mut keys = Vec::new();
+            loop {
+                if let Some(rest) = rem.strip_prefix("C-") {
+                    keys.push(OsCode::KEY_LEFTCTRL);
+                    rem = rest;
+                } else if let Some(rest) = rem.strip_prefix("S-") {
+                    keys.push(OsCode::KEY_LEFTSHIFT);
+                    rem = rest;
+                } else if let Some(rest) = rem.strip_prefix("A-") {
+                    keys.push(OsCode::KEY_LEFTALT);
+                    rem = rest;
+                } else {
+                    break;
+                }
+            }
+            keys.push(str_to_oscode(rem).ok_or_else(|| anyhow!("unknown key {}", combo))?);
+            Ok(keys)
+        })
+        .collect()
+}
+
+/// Parse the policies of the non-key event types from defcfg. Relative movement is only valid for
+/// absolute events.
+#[cfg(target_os = "linux")]
+fn parse_event_policies(cfg: &HashMap<String, String>, errs: &mut CfgErrors) -> EventPolicies {
+    let parse = |key: &str, relative: bool| match cfg.get(key).map(|v| v.as_str()) {
+        None | Some("forward") => Ok(EventPolicy::Forward),
+        Some("drop") => Ok(EventPolicy::Drop),
+        Some("relative") if relative => Ok(EventPolicy::Relative),
+        Some(v) if relative => bail!(
+            "invalid {} in defcfg: {}, expected forward, drop, or relative",
+            key,
+            v
+        ),
+        Some(v) => bail!("invalid {} in defcfg: {}, expected forward or drop", key, v),
+    };
+    let mut parse = |key: &str, relative: bool| {
+        errs.check(parse(key, relative))
+            .unwrap_or(EventPolicy::Forward)
+    };
+    EventPolicies {
+        rel: parse("linux-rel-events", false),
+        abs: parse("linux-abs-events", true),
+        msc: parse("linux-msc-events", false),
+        sw: parse("linux-sw-events", false),
+    }
+}
+
+/// Parse the name, IDs, and repeat settings of the uinput output device from defcfg.
+#[cfg(target_os = "linux")]
+fn parse_uinput_device(
+    cfg: &HashMap<String, String>,
+    errs: &mut CfgErrors,
+) -> crate::oskbd::UinputDevice {
+    let mut uinput = crate::oskbd::UinputDevice::default();
+    if let Some(name) = cfg.get("linux-uinput-name") {
+        if name.is_empty() || name.len() > 64 {
+            errs.0.push(anyhow!(
+                "linux-uinput-name in defcfg must have 1 to 64 bytes: {}",
+                name
+            ))
+        }
+        uinput.name = name.clone();
+    }
+    if let Some(id) = cfg.get("linux-uinput-vendor-id") {
+        match parse_device_id(id) {
+            Some(id) => uinput.vendor = id,
+            None => errs
+                .0
+                .push(anyhow!("invalid linux-uinput-vendor-id in defcfg: {}", id)),
+        }
+    }
+    if let Some(id) = cfg.get("linux-uinput-product-id") {
+        match parse_device_id(id) {
+            Some(id) => uinput.product = id,
+            None => errs
+                .0
+                .push(anyhow!("invalid linux-uinput-product-id in defcfg: {}", id)),
+        }
+    }
+    if cfg.contains_key("linux-uinput-repeat-delay") {
+        uinput.repeat_delay = errs.check(parse_cfg_num(cfg, "linux-uinput-repeat-delay", 0));
+    }
+    if let Some(r) = cfg.get("linux-uinput-repeat-rate") {
+        match r.parse() {
+            Ok(rate @ 1..=1000) => uinput.repeat_rate = Some(rate),
+            _ => errs.0.push(anyhow!(
+                "invalid linux-uinput-repeat-rate in defcfg: {}, expected 1 to 1000 repeats per second",
+                r
+            )),
+        }
+    }
+    uinput
+}
+
+/// Parse a USB vendor or product ID, which is a hexadecimal number with a `0x` prefix or a decimal
+/// number.
+#[cfg(target_os = "linux")]
+fn parse_device_id(val: &str) -> Option<u16> {
+    match val.strip_prefix("0x") {
+        Some(hex) => u16::from_str_radix(hex, 16).ok(),
+        None => val.parse().ok(),
+    }
 }
 
 /// Return a closure that filters a root expression by the content of the first element. The
@@ -194,105 +932,235 @@ fn gen_first_atom_filter(a: &str) -> impl FnMut(&&Vec<SExpr>) -> bool {
             return false;
         }
         if let SExpr::Atom(atom) = &expr[0] {
-            atom == &a
+            **atom == a
         } else {
             false
         }
     }
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 /// I know this isn't the classic definition of an S-Expression which uses cons cell and atom, but
 /// this is more convenient to work with (I find).
 enum SExpr {
-    Atom(String),
-    List(Vec<SExpr>),
-}
-
-/// Get the root expressions and strip comments.
-fn get_root_exprs(cfg: &str) -> Result<Vec<String>> {
-    let mut open_paren_count = 0;
-    let mut close_paren_count = 0;
-    let mut s_exprs = Vec::new();
-    let mut cur_expr = String::new();
-    for line in cfg.lines() {
-        // remove comments
-        let line = line.split(";;").next().unwrap();
-        for c in line.chars() {
-            if c == '(' {
-                open_paren_count += 1;
-            } else if c == ')' {
-                close_paren_count += 1;
-            }
-        }
-        if open_paren_count == 0 {
-            continue;
-        }
-        cur_expr.push_str(line);
-        cur_expr.push('\n');
-        if open_paren_count == close_paren_count {
-            open_paren_count = 0;
-            close_paren_count = 0;
-            s_exprs.push(cur_expr.trim().to_owned());
-            cur_expr.clear();
+    Atom(Spanned<String>),
+    List(Spanned<Vec<SExpr>>),
+}
+
+impl SExpr {
+    fn span(&self) -> Span {
+        match self {
+            SExpr::Atom(a) => a.span,
+            SExpr::List(l) => l.span,
         }
     }
-    if !cur_expr.is_empty() {
-        bail!("Unclosed root expression:\n{}", cur_expr)
+}
+
+/// Byte offsets of the start (inclusive) and end (exclusive) of an expression in the
+/// configuration file.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+struct Span {
+    start: usize,
+    end: usize,
+}
+
+/// A value parsed from the configuration file together with its location in the file.
+#[derive(Clone)]
+struct Spanned<T> {
+    t: T,
+    span: Span,
+}
+
+impl<T> Spanned<T> {
+    fn new(t: T, span: Span) -> Self {
+        Self { t, span }
+    }
+}
+
+impl<T> std::ops::Deref for Spanned<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.t
+    }
+}
+
+impl PartialEq<str> for Spanned<String> {
+    fn eq(&self, other: &str) -> bool {
+        self.t == other
     }
-    Ok(s_exprs)
 }
 
-/// Parse an expression string into an SExpr
-fn parse_expr(expr: &str) -> Result<Vec<SExpr>> {
-    if !expr.starts_with('(') {
-        bail!("Expression in cfg does not start with '(':\n{}", expr)
+impl<T: std::fmt::Display> std::fmt::Display for Spanned<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.t.fmt(f)
     }
-    if !expr.ends_with(')') {
-        bail!("Expression in cfg does not end with ')':\n{}", expr)
+}
+
+/// Spans are left out so that expressions in error messages look like they are written in the
+/// configuration.
+impl<T: std::fmt::Debug> std::fmt::Debug for Spanned<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.t.fmt(f)
     }
-    let expr = expr.strip_prefix('(').unwrap_or(expr);
-    let expr = expr.strip_suffix(')').unwrap_or(expr);
+}
 
-    let mut ret = Vec::new();
-    let mut tokens = expr.split_whitespace();
-    loop {
-        let token = match tokens.next() {
-            None => break,
-            Some(t) => t,
-        };
-        if token.contains('(') {
-            // seek to matching close paren and recurse
-            let mut paren_stack_size = token.chars().filter(|c| *c == '(').count();
-            paren_stack_size -= token.chars().filter(|c| *c == ')').count();
-            let mut subexpr = String::new();
-            subexpr.push_str(token);
-            while paren_stack_size > 0 {
-                let token = match tokens.next() {
-                    None => bail!(
-                        "Sub expression does not close:\n{}\nwhole expr:\n{}",
-                        subexpr,
-                        expr
-                    ),
-                    Some(t) => t,
+/// An error in the configuration file at a known location. Errors that don't have a location yet
+/// are given one with `with_span` as they propagate up through the parsing functions, so the
+/// location is that of the innermost expression that failed to parse.
+#[derive(Debug)]
+struct ParseError {
+    span: Span,
+    msg: String,
+}
+
+impl ParseError {
+    fn new(span: Span, msg: impl Into<String>) -> Self {
+        Self {
+            span,
+            msg: msg.into(),
+        }
+    }
+}
+
+impl std::fmt::Display for ParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.msg)
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+/// Attach the span to the error, unless the error already has a span.
+fn with_span<T>(r: Result<T>, span: Span) -> Result<T> {
+    r.map_err(|e| {
+        if e.is::<ParseError>() {
+            e
+        } else {
+            ParseError {
+                span,
+                msg: format!("{:#}", e),
+            }
+            .into()
+        }
+    })
+}
+
+/// The errors found in a configuration so far. Parsing continues after an error in a defcfg
+/// entry, alias, layer, or other root expression, so that all errors are reported at once.
+#[derive(Default)]
+struct CfgErrors(Vec<anyhow::Error>);
+
+impl CfgErrors {
+    /// Returns the value, or records the error and returns `None`. The caller continues with a
+    /// placeholder, which never ends up in a `Cfg` because the errors are returned instead.
+    fn check<T>(&mut self, r: Result<T>) -> Option<T> {
+        match r {
+            Ok(v) => Some(v),
+            Err(e) => {
+                self.0.push(e);
+                None
+            }
+        }
+    }
+}
+
+/// Format an error with the file, line, and column of its span and the line of the configuration
+/// where it happened, with carets under the erroneous expression.
+fn format_parse_error(err: &ParseError, path: &std::path::Path, cfg: &str) -> String {
+    let start = err.span.start.min(cfg.len());
+    let line_start = cfg[..start].rfind('\n').map(|i| i + 1).unwrap_or(0);
+    let line_end = cfg[start..]
+        .find('\n')
+        .map(|i| start + i)
+        .unwrap_or(cfg.len());
+    let line_num = cfg[..start].matches('\n').count() + 1;
+    let col = cfg[line_start..start].chars().count() + 1;
+    let line = cfg[line_start..line_end].trim_end();
+    let caret_count = cfg[start..err.span.end.clamp(start, line_end)]
+        .chars()
+        .count()
+        .max(1);
+    let line_num_str = line_num.to_string();
+    format!(
+        "{}:{}:{}: {}\n{} | {}\n{} | {}{}",
+        path.display(),
+        line_num,
+        col,
+        err.msg,
+        line_num_str,
+        line,
+        " ".repeat(line_num_str.len()),
+        " ".repeat(col - 1),
+        "^".repeat(caret_count),
+    )
+}
+
+/// Parse the configuration into the lists of the root expressions, e.g. `(defsrc ...)`. Comments,
+/// which start with `;;` and continue until the end of the line, are skipped.
+fn parse_root_exprs(cfg: &str) -> Result<Vec<Vec<SExpr>>> {
+    let mut roots = Vec::new();
+    // Start offsets and items of the lists that are open.
+    let mut stack: Vec<(usize, Vec<SExpr>)> = Vec::new();
+    let mut chars = cfg.char_indices().peekable();
+    while let Some((i, c)) = chars.next() {
+        match c {
+            ';' if cfg[i..].starts_with(";;") => {
+                while chars.next_if(|(_, c)| *c != '\n').is_some() {}
+            }
+            '(' => stack.push((i, Vec::new())),
+            ')' => {
+                let (start, items) = match stack.pop() {
+                    Some(l) => l,
+                    None => bail!(ParseError::new(
+                        Span {
+                            start: i,
+                            end: i + 1
+                        },
+                        "Unexpected closing parenthesis"
+                    )),
                 };
-                paren_stack_size += token.chars().filter(|c| *c == '(').count();
-                paren_stack_size -= token.chars().filter(|c| *c == ')').count();
-                subexpr.push(' ');
-                subexpr.push_str(token);
+                match stack.last_mut() {
+                    Some((_, parent)) => {
+                        parent.push(SExpr::List(Spanned::new(items, Span { start, end: i + 1 })))
+                    }
+                    None => roots.push(items),
+                }
+            }
+            c if c.is_whitespace() => {}
+            _ => {
+                let mut end = i + c.len_utf8();
+                while let Some((j, c)) = chars.next_if(|(j, c)| {
+                    !c.is_whitespace() && *c != '(' && *c != ')' && !cfg[*j..].starts_with(";;")
+                }) {
+                    end = j + c.len_utf8();
+                }
+                let span = Span { start: i, end };
+                match stack.last_mut() {
+                    Some((_, parent)) => {
+                        parent.push(SExpr::Atom(Spanned::new(cfg[i..end].to_owned(), span)))
+                    }
+                    None => bail!(ParseError::new(
+                        span,
+                        format!(
+                            "Unexpected text outside of a root expression: {}",
+                            &cfg[i..end]
+                        )
+                    )),
+                }
             }
-            ret.push(SExpr::List(parse_expr(&subexpr)?))
-        } else if token.contains(')') {
-            bail!(
-                "Unexpected closing paren in token {} in expr:\n{}",
-                token,
-                expr
-            )
-        } else {
-            ret.push(SExpr::Atom(token.to_owned()));
         }
     }
-    Ok(ret)
+    if let Some((start, _)) = stack.first() {
+        bail!(ParseError::new(
+            Span {
+                start: *start,
+                end: start + 1
+            },
+            "Unclosed parenthesis"
+        ))
+    }
+    Ok(roots)
 }
 
 /// Consumes the first element and returns the rest of the iterator. Returns `Ok` if the first
@@ -325,100 +1193,537 @@ fn check_first_expr<'a>(
     Ok(exprs)
 }
 
-/// Parse configuration entries from an expression starting with defcfg.
-fn parse_defcfg(expr: &[SExpr]) -> Result<HashMap<String, String>> {
-    let mut cfg = HashMap::new();
-    let mut exprs = match check_first_expr(expr.iter(), "defcfg") {
-        Ok(s) => s,
-        Err(e) => bail!(e),
-    };
+/// Returns whether the items of a platform-conditional list, e.g. `(if-linux ...)`, are used on
+/// the current platform, or `None` if the list is not platform-conditional.
+fn platform_cond_matches(exprs: &[SExpr]) -> Option<bool> {
+    match exprs.first() {
+        Some(SExpr::Atom(a)) => match a.as_str() {
+            "if-linux" => Some(cfg!(target_os = "linux")),
+            "if-windows" => Some(cfg!(target_os = "windows")),
+            _ => None,
+        },
+        _ => None,
+    }
+}
 
-    // Read k-v pairs from the configuration
-    loop {
-        let key = match exprs.next() {
-            Some(k) => k,
-            None => return Ok(cfg),
-        };
-        let val = match exprs.next() {
-            Some(v) => v,
-            None => bail!("Incorrect number of elements found in defcfg; they should be pairs of keys and values."),
-        };
-        match (&key, &val) {
-            (SExpr::Atom(k), SExpr::Atom(v)) => {
-                if cfg.insert(k.clone(), v.clone()).is_some() {
-                    bail!("duplicate cfg entries for key {}", k);
+/// Replace the top-level platform-conditional lists with the root expressions they contain if the
+/// platform matches, or remove them otherwise. Conditionals that are nested inside other
+/// expressions are handled by `expand_exprs`.
+fn filter_platform_root_exprs(root_exprs: Vec<Vec<SExpr>>) -> Result<Vec<Vec<SExpr>>> {
+    let mut filtered = Vec::new();
+    for expr in root_exprs {
+        match platform_cond_matches(&expr) {
+            None => filtered.push(expr),
+            Some(false) => {}
+            Some(true) => {
+                let subexprs = expr
+                    .into_iter()
+                    .skip(1)
+                    .map(|subexpr| match subexpr {
+                        SExpr::List(l) => Ok(l.t),
+                        SExpr::Atom(a) => bail!(ParseError::new(a.span, format!("A top-level platform conditional must only contain lists, e.g. (defalias ...). Found: {}", a))),
+                    })
+                    .collect::<Result<Vec<_>>>()?;
+                filtered.extend(filter_platform_root_exprs(subexprs)?);
+            }
+        }
+    }
+    Ok(filtered)
+}
+
+type Vars = HashMap<String, SExpr>;
+
+/// A snippet of configuration declared with deftemplate.
+struct Template {
+    params: Vec<String>,
+    body: Vec<SExpr>,
+}
+
+type Templates = HashMap<String, Template>;
+
+/// Parse the variables declared in defvar and the templates declared in deftemplate, then replace
+/// the references to variables, e.g. `$tap-time`, and the uses of templates, e.g.
+/// `(expand-template hrm a lmet)`, in all other expressions. Variables and templates can only be
+/// used after they are declared.
+fn substitute_vars(root_exprs: &mut [Vec<SExpr>]) -> Result<()> {
+    let mut vars = Vars::new();
+    let mut templates = Templates::new();
+    for expr in root_exprs.iter_mut() {
+        if gen_first_atom_filter("defvar")(&&*expr) {
+            let mut subexprs = match check_first_expr(expr.iter(), "defvar") {
+                Ok(s) => s,
+                Err(e) => bail!(e),
+            };
+            while let Some(name) = subexprs.next() {
+                let name = match name {
+                    SExpr::Atom(a) => a,
+                    _ => bail!(ParseError::new(
+                        name.span(),
+                        format!("Variable names must be atoms. Invalid name: {:?}", name)
+                    )),
+                };
+                let val = match subexprs.next() {
+                    Some(v) => v.clone(),
+                    None => bail!(ParseError::new(name.span, "Incorrect number of elements found in defvar; they should be pairs of names and values.")),
+                };
+                let val_span = val.span();
+                let val = match &expand_exprs(vec![val], &vars, &templates, &[])?[..] {
+                    [val] => val.clone(),
+                    _ => bail!(ParseError::new(
+                        val_span,
+                        format!("The value of variable {} must be a single expression", name)
+                    )),
+                };
+                if vars.insert(name.t.clone(), val).is_some() {
+                    bail!(ParseError::new(
+                        name.span,
+                        format!("Duplicate variable: {}", name)
+                    ));
                 }
             }
-            (_, _) => {
-                bail!(
-                    "defcfg should only be composed of atoms. Incorrect (k,v) found: {:?},{:?}",
-                    key,
-                    val
-                );
+        } else if gen_first_atom_filter("deftemplate")(&&*expr) {
+            let (name, template) = parse_template(expr, &vars, &templates)?;
+            if templates.insert(name.t.clone(), template).is_some() {
+                bail!(ParseError::new(
+                    name.span,
+                    format!("Duplicate template: {}", name)
+                ));
             }
+        } else {
+            *expr = expand_exprs(std::mem::take(expr), &vars, &templates, &[])?;
         }
     }
+    Ok(())
 }
 
-/// Parse mapped keys from an expression starting with defsrc. Returns the key mapping as well as
-/// a vec of the indexes in order. The length of the returned vec should be matched by the length
-/// of all layer declarations.
-fn parse_defsrc(expr: &[SExpr]) -> Result<(MappedKeys, Vec<usize>)> {
-    // Validate first expression, which should be defsrc
-    let exprs = match check_first_expr(expr.iter(), "defsrc") {
+/// Parse `(deftemplate <name> (<params>...) <body>...)`.
+fn parse_template(
+    expr: &[SExpr],
+    vars: &Vars,
+    templates: &Templates,
+) -> Result<(Spanned<String>, Template)> {
+    const ERR_STR: &str = "deftemplate expects a name, a list of parameter names, and a body";
+    let mut subexprs = match check_first_expr(expr.iter(), "deftemplate") {
         Ok(s) => s,
         Err(e) => bail!(e),
     };
-
-    let mut mkeys = [false; 256];
-    let mut ordered_codes = Vec::new();
-    for expr in exprs {
-        let s = match expr {
-            SExpr::Atom(a) => a,
-            _ => bail!("No lists allowed in defsrc"),
-        };
-        let oscode: usize = match str_to_oscode(s) {
-            Some(c) => c.into(),
-            None => bail!("Unknown key in defsrc: \"{}\"", s),
-        };
-        if oscode >= MAPPED_KEYS_LEN {
-            bail!("Cannot use key \"{}\"", s)
-        }
-        if mkeys[oscode] {
-            bail!("Repeat declaration of key in defsrc: \"{}\"", s)
-        }
-        mkeys[oscode] = true;
-        ordered_codes.push(oscode);
+    let name = match subexprs.next() {
+        Some(SExpr::Atom(a)) => a.clone(),
+        _ => bail!(ParseError::new(expr[0].span(), ERR_STR)),
+    };
+    let params = match subexprs.next() {
+        Some(SExpr::List(l)) => l
+            .iter()
+            .map(|p| match p {
+                SExpr::Atom(a) => Ok(a.t.clone()),
+                SExpr::List(_) => bail!(ParseError::new(
+                    p.span(),
+                    format!("Template parameter names must be atoms: {:?}", p)
+                )),
+            })
+            .collect::<Result<Vec<_>>>()?,
+        _ => bail!(ParseError::new(name.span, ERR_STR)),
+    };
+    // Variables are substituted now, parameters when the template is expanded.
+    let body = expand_exprs(subexprs.cloned().collect(), vars, templates, &params)?;
+    if body.is_empty() {
+        bail!(ParseError::new(
+            name.span,
+            format!("{}. Template {} has no body.", ERR_STR, name)
+        ))
+    }
+    Ok((name, Template { params, body }))
+}
+
+/// Substitute variables and expand templates in a list of expressions. The expressions resulting
+/// from a template expansion are spliced into the list. References to `ignored` names are kept
+/// as-is.
+fn expand_exprs(
+    exprs: Vec<SExpr>,
+    vars: &Vars,
+    templates: &Templates,
+    ignored: &[String],
+) -> Result<Vec<SExpr>> {
+    let mut expanded = Vec::new();
+    for expr in exprs {
+        match expr {
+            SExpr::Atom(a) => {
+                match a.strip_prefix('$').filter(|name| !name.is_empty()) {
+                    Some(name) if !ignored.iter().any(|i| i == name) => match vars.get(name) {
+                        Some(val) => expanded.push(val.clone()),
+                        None => bail!(ParseError::new(a.span, format!("Referenced unknown variable {}. Note that order of declarations matter: a variable must be declared in defvar before it is used.", name))),
+                    },
+                    _ => expanded.push(SExpr::Atom(a)),
+                }
+            }
+            SExpr::List(l) => {
+                match platform_cond_matches(&l) {
+                    Some(true) => {
+                        let items = l.t.into_iter().skip(1).collect();
+                        expanded.extend(expand_exprs(items, vars, templates, ignored)?);
+                        continue;
+                    }
+                    Some(false) => continue,
+                    None => {}
+                }
+                let span = l.span;
+                let l = expand_exprs(l.t, vars, templates, ignored)?;
+                match l.first() {
+                    Some(SExpr::Atom(a)) if **a == "expand-template" => {
+                        expanded.extend(with_span(expand_template(&l[1..], templates), span)?)
+                    }
+                    _ => expanded.push(SExpr::List(Spanned::new(l, span))),
+                }
+            }
+        }
+    }
+    Ok(expanded)
+}
+
+/// Expand `(expand-template <name> <args>...)`, given the items after expand-template.
+fn expand_template(items: &[SExpr], templates: &Templates) -> Result<Vec<SExpr>> {
+    let name = match items.first() {
+        Some(SExpr::Atom(a)) => a,
+        _ => bail!("expand-template expects a template name followed by its arguments"),
+    };
+    let template = match templates.get(&name.t) {
+        Some(t) => t,
+        None => bail!(
+            "Referenced unknown template {}. Note that order of declarations matter: a template must be declared before it is used.",
+            name
+        ),
+    };
+    let args = &items[1..];
+    if args.len() != template.params.len() {
+        bail!(
+            "Template {} expects {} arguments, found {}",
+            name,
+            template.params.len(),
+            args.len()
+        )
+    }
+    let params: Vars = template
+        .params
+        .iter()
+        .cloned()
+        .zip(args.iter().cloned())
+        .collect();
+    expand_exprs(template.body.clone(), &params, templates, &[])
+}
+
+/// Parse configuration entries from an expression starting with defcfg. Errors in the entries are
+/// added to `errs`.
+fn parse_defcfg(expr: &[SExpr], errs: &mut CfgErrors) -> Result<HashMap<String, String>> {
+    let mut cfg = HashMap::new();
+    let mut exprs = match check_first_expr(expr.iter(), "defcfg") {
+        Ok(s) => s,
+        Err(e) => bail!(e),
+    };
+
+    // Read k-v pairs from the configuration
+    loop {
+        let key = match exprs.next() {
+            Some(k) => k,
+            None => return Ok(cfg),
+        };
+        let val = match exprs.next() {
+            Some(v) => v,
+            None => {
+                errs.0.push(ParseError::new(key.span(), "Incorrect number of elements found in defcfg; they should be pairs of keys and values.").into());
+                return Ok(cfg);
+            }
+        };
+        errs.check(parse_defcfg_entry(key, val, &mut cfg));
+    }
+}
+
+/// Parse a key and value of defcfg into `cfg`.
+fn parse_defcfg_entry(key: &SExpr, val: &SExpr, cfg: &mut HashMap<String, String>) -> Result<()> {
+    match (key, val) {
+        (SExpr::Atom(k), SExpr::Atom(v)) => {
+            if cfg.insert(k.t.clone(), v.t.clone()).is_some() {
+                bail!(ParseError::new(
+                    k.span,
+                    format!("duplicate cfg entries for key {}", k)
+                ));
+            }
+        }
+        (SExpr::Atom(k), SExpr::List(l)) => {
+            // A list is either an (env ...) value or words that are joined with spaces, e.g.
+            // for the names of devices.
+            let v = match l.first() {
+                Some(SExpr::Atom(a)) if **a == "env" => {
+                    with_span(parse_env_val(&l[1..]), l.span)
+                        .map_err(|e| anyhow!("Invalid value for defcfg key {}: {}", k, e))?
+                }
+                _ => l
+                    .iter()
+                    .map(|word| {
+                        get_atom(word).ok_or_else(|| {
+                            ParseError::new(
+                                word.span(),
+                                format!("defcfg key {} expects only words in its value", k),
+                            )
+                            .into()
+                        })
+                    })
+                    .collect::<Result<Vec<_>>>()?
+                    .join(" "),
+            };
+            if cfg.insert(k.t.clone(), v).is_some() {
+                bail!(ParseError::new(
+                    k.span,
+                    format!("duplicate cfg entries for key {}", k)
+                ));
+            }
+        }
+        (_, _) => {
+            bail!(ParseError::new(
+                    key.span(),
+                    format!(
+                        "defcfg should only be composed of atoms, lists of words, or (env ...) values. Incorrect (k,v) found: {:?},{:?}",
+                        key,
+                        val
+                    )
+                ));
+        }
+    }
+    Ok(())
+}
+
+/// Parse `(env <VAR> [default])`, given the items after env. Returns the value of the environment
+/// variable, or the default if the variable is not set.
+fn parse_env_val(items: &[SExpr]) -> Result<String> {
+    const ERR_STR: &str =
+        "env expects the name of an environment variable and an optional default value";
+    let (name, default) = match items {
+        [SExpr::Atom(name)] => (name, None),
+        [SExpr::Atom(name), SExpr::Atom(default)] => (name, Some(default)),
+        _ => bail!(ERR_STR),
+    };
+    match (std::env::var(&name.t), default) {
+        (Ok(v), _) => Ok(v),
+        (Err(_), Some(default)) => Ok(default.t.clone()),
+        (Err(e), None) => bail!(
+            "environment variable {} could not be read ({}) and no default is given",
+            name,
+            e
+        ),
+    }
+}
+
+/// Parse defsrc into the mapped keys, the keys in the order of the layers, and the rows of keys
+/// as they are written on the lines of the file. The keys declared by all-keys are not part of any
+/// row. `text` is the content of the configuration file.
+fn parse_defsrc(expr: &[SExpr], text: &str) -> Result<(MappedKeys, Vec<usize>, Vec<Vec<u8>>)> {
+    // Validate first expression, which should be defsrc
+    let exprs = match check_first_expr(expr.iter(), "defsrc") {
+        Ok(s) => s,
+        Err(e) => bail!(e),
+    };
+
+    // all-keys declares the keys that are not declared by the other items, so those are parsed
+    // first. `None` marks the position of all-keys.
+    let mut items = Vec::new();
+    for expr in exprs {
+        let keys = match expr {
+            SExpr::Atom(a) if **a == "all-keys" => None,
+            SExpr::List(l) if matches!(l.first(), Some(SExpr::Atom(a)) if **a == "range") => {
+                Some(with_span(parse_key_range(&l[1..]), l.span)?)
+            }
+            _ => Some(vec![parse_key_expr(expr, "defsrc")?]),
+        };
+        items.push((expr, keys));
+    }
+    let declared: HashSet<OsCode> = items
+        .iter()
+        .filter_map(|(_, keys)| keys.as_ref())
+        .flatten()
+        .copied()
+        .collect();
+
+    let mut mkeys = [false; 256];
+    let mut ordered_codes = Vec::new();
+    let mut rows: Vec<Vec<u8>> = Vec::new();
+    let mut prev_line = None;
+    for (expr, keys) in items {
+        let is_all_keys = keys.is_none();
+        let keys = keys.unwrap_or_else(|| {
+            remappable_keys()
+                .filter(|k| !declared.contains(k))
+                .collect()
+        });
+        for oscode in keys {
+            let name = match expr {
+                SExpr::Atom(a) => a.t.clone(),
+                SExpr::List(_) => format!("{:?}", oscode),
+            };
+            let oscode: usize = oscode.to_remappable().into();
+            if oscode >= MAPPED_KEYS_LEN {
+                bail!(ParseError::new(
+                    expr.span(),
+                    format!(
+                        "Cannot use key \"{}\", only keys with a code below {} can be remapped. Keys of a device with larger codes, e.g. gamepad buttons, can be treated as other keys with defdevice",
+                        name, MAPPED_KEYS_LEN
+                    )
+                ))
+            }
+            if mkeys[oscode] {
+                bail!(ParseError::new(
+                    expr.span(),
+                    format!("Repeat declaration of key in defsrc: \"{}\"", name)
+                ))
+            }
+            mkeys[oscode] = true;
+            ordered_codes.push(oscode);
+            if !is_all_keys {
+                let line = text
+                    .get(..expr.span().start)
+                    .map(|t| t.matches('\n').count());
+                if line.is_none() || line != prev_line {
+                    rows.push(Vec::new());
+                }
+                prev_line = line;
+                if let Some(row) = rows.last_mut() {
+                    row.push(oscode as u8);
+                }
+            }
+        }
+        if is_all_keys {
+            prev_line = None;
+        }
+    }
+    Ok((mkeys, ordered_codes, rows))
+}
+
+/// Returns all keys that can be declared in defsrc, in the order of their codes.
+fn remappable_keys() -> impl Iterator<Item = OsCode> {
+    // The codes that stand in for mouse buttons and the mouse wheel are left out.
+    (1..MAPPED_KEYS_LEN as u32)
+        .filter_map(OsCode::from_u32)
+        .filter(|k| k.to_original() == *k && k.mwheel_direction().is_none())
+}
+
+/// Parse the parameters of `(range <first> <last>)` in defsrc into the keys from first to last.
+/// The keys must both be letters, digits, or function keys, e.g. `(range a z)` or
+/// `(range f1 f12)`.
+fn parse_key_range(ac_params: &[SExpr]) -> Result<Vec<OsCode>> {
+    const ERR_STR: &str =
+        "range expects two letters, digits, or function keys after it, e.g. (range a z)";
+    let (first, last) = match ac_params {
+        [SExpr::Atom(first), SExpr::Atom(last)] => (first.as_str(), last.as_str()),
+        _ => bail!(ERR_STR),
+    };
+    let names: Vec<String> = match (first.as_bytes(), last.as_bytes()) {
+        ([lo], [hi])
+            if lo <= hi
+                && (lo.is_ascii_digit() && hi.is_ascii_digit()
+                    || lo.is_ascii_lowercase() && hi.is_ascii_lowercase()) =>
+        {
+            (*lo..=*hi).map(|c| char::from(c).to_string()).collect()
+        }
+        ([b'f', ..], [b'f', ..]) => match (first[1..].parse::<u8>(), last[1..].parse::<u8>()) {
+            (Ok(lo), Ok(hi)) if lo <= hi => (lo..=hi).map(|n| format!("f{}", n)).collect(),
+            _ => bail!(ERR_STR),
+        },
+        _ => bail!(ERR_STR),
+    };
+    names
+        .iter()
+        .map(|name| str_to_oscode(name).ok_or_else(|| anyhow!("unknown key in range: {}", name)))
+        .collect()
+}
+
+/// Parse a key name or `(raw <code>)` that is used as an input key in `section`.
+fn parse_key_expr(expr: &SExpr, section: &str) -> Result<OsCode> {
+    match expr {
+        SExpr::Atom(s) => str_to_oscode(s).ok_or_else(|| {
+            ParseError::new(s.span, format!("Unknown key in {}: \"{}\"", section, s)).into()
+        }),
+        SExpr::List(l) => match l.first() {
+            Some(SExpr::Atom(a)) if **a == "raw" => with_span(parse_raw_oscode(&l[1..]), l.span),
+            _ => bail!(ParseError::new(
+                expr.span(),
+                format!("No lists allowed in {} except (raw <code>)", section)
+            )),
+        },
     }
-    Ok((mkeys, ordered_codes))
 }
 
 type LayerIndexes = HashMap<String, usize>;
 type Aliases = HashMap<String, &'static KanataAction>;
 
+/// State that is built up while parsing the actions of the configuration.
+#[derive(Default)]
+struct ParsedState {
+    layer_idxs: LayerIndexes,
+    aliases: Aliases,
+    /// Actions of the virtual keys. The index of an action is its column in the virtual row.
+    virtual_keys: Vec<&'static KanataAction>,
+    one_shot_defaults: OneShotConfig,
+    /// Tap timeout of tap-hold actions and timeout of tap-dance actions that don't specify one.
+    default_tap_time: u16,
+    /// Hold timeout of tap-hold actions that don't specify one.
+    default_hold_time: u16,
+    /// Timeout of autoshift actions that don't specify one.
+    default_autoshift_time: u16,
+    macro_cancel_on_press: bool,
+    /// Whether defcfg allows the cmd action.
+    is_cmd_enabled: bool,
+    /// Names of the virtual keys declared in defvirtualkeys and their columns in the virtual row.
+    virtual_key_names: HashMap<String, u8>,
+    /// Rows of the keys of defsrc as they are written in the file, used by the mirror action.
+    src_rows: Vec<Vec<u8>>,
+    /// Size of the screen in pixels, used by setmouse actions with pixel coordinates.
+    screen_size: Option<(u16, u16)>,
+}
+
+impl ParsedState {
+    /// Add an action to the virtual row and return its column index.
+    fn add_virtual_key(&mut self, action: &'static KanataAction) -> Result<u8> {
+        if self.virtual_keys.len() >= KEYS_IN_ROW {
+            bail!(
+                "Exceeded the maximum of {} actions used by tap-dance and similar actions",
+                KEYS_IN_ROW
+            );
+        }
+        self.virtual_keys.push(action);
+        Ok((self.virtual_keys.len() - 1) as u8)
+    }
+}
+
 /// Returns layer names and their indexes into the keyberon layout. This also checks that all
-/// layers have the same number of items as the defsrc.
+/// deflayer layers have the same number of items as the defsrc.
 fn parse_layer_indexes(exprs: &[&Vec<SExpr>], expected_len: usize) -> Result<LayerIndexes> {
     let mut layer_indexes = HashMap::new();
     for (i, expr) in exprs.iter().enumerate() {
-        let mut subexprs = match check_first_expr(expr.iter(), "deflayer") {
+        let is_map = is_layer_map(expr);
+        let deflayer = if is_map { "deflayermap" } else { "deflayer" };
+        let mut subexprs = match check_first_expr(expr.iter(), deflayer) {
             Ok(s) => s,
             Err(e) => bail!(e),
         };
-        let layer_name = get_atom(
-            subexprs
-                .next()
-                .ok_or_else(|| anyhow!("deflayer requires a name and keys"))?,
-        )
-        .ok_or_else(|| anyhow!("layer name after deflayer must be an atom"))?;
-        let num_actions = subexprs.count();
-        if num_actions != expected_len {
-            bail!(
-                "layer {} has {} items, but requires {} to match defsrc",
-                layer_name,
-                num_actions,
-                expected_len
+        let name_expr = subexprs.next().ok_or_else(|| {
+            ParseError::new(
+                expr[0].span(),
+                format!("{} requires a name and keys", deflayer),
             )
+        })?;
+        let layer_name = get_atom(name_expr).ok_or_else(|| {
+            ParseError::new(
+                name_expr.span(),
+                format!("layer name after {} must be an atom", deflayer),
+            )
+        })?;
+        let num_actions = subexprs.count();
+        if !is_map && num_actions != expected_len {
+            bail!(ParseError::new(
+                name_expr.span(),
+                format!(
+                    "layer {} has {} items, but requires {} to match defsrc",
+                    layer_name, num_actions, expected_len
+                )
+            ))
         }
         layer_indexes.insert(layer_name, i);
     }
@@ -428,37 +1733,82 @@ fn parse_layer_indexes(exprs: &[&Vec<SExpr>], expected_len: usize) -> Result<Lay
 /// Returns the content of an `SExpr::Atom` or returns `None` for `SExpr::List`.
 fn get_atom(a: &SExpr) -> Option<String> {
     match a {
-        SExpr::Atom(a) => Some(a.clone()),
+        SExpr::Atom(a) => Some(a.t.clone()),
         _ => None,
     }
 }
 
-/// Parse alias->action mappings from multiple exprs starting with defalias.
-fn parse_aliases(exprs: &[&Vec<SExpr>], layers: &HashMap<String, usize>) -> Result<Aliases> {
-    let mut aliases = HashMap::new();
+/// Parse name->action mappings from multiple exprs starting with defvirtualkeys. The actions are
+/// added to the virtual row. Virtual keys are parsed before aliases, so their actions can't use
+/// aliases.
+fn parse_virtual_keys(exprs: &[&Vec<SExpr>], s: &mut ParsedState) -> Result<()> {
     for expr in exprs {
-        let mut subexprs = match check_first_expr(expr.iter(), "defalias") {
+        let mut subexprs = match check_first_expr(expr.iter(), "defvirtualkeys") {
             Ok(s) => s,
             Err(e) => bail!(e),
         };
-
-        // Read k-v pairs from the configuration
-        while let Some(alias) = subexprs.next() {
+        while let Some(name) = subexprs.next() {
             let action = match subexprs.next() {
                 Some(v) => v,
-                None => bail!("Incorrect number of elements found in defcfg; they should be pairs of aliases and actions."),
+                None => bail!(ParseError::new(name.span(), "Incorrect number of elements found in defvirtualkeys; they should be pairs of names and actions.")),
             };
-            let alias = match alias {
+            let name = match name {
                 SExpr::Atom(a) => a,
-                _ => bail!("Alias keys must be atoms. Invalid alias: {:?}", alias),
+                _ => bail!(ParseError::new(
+                    name.span(),
+                    format!("Virtual key names must be atoms. Invalid name: {:?}", name)
+                )),
             };
-            let action = parse_action(action, &aliases, layers)?;
-            if aliases.insert(alias.into(), action).is_some() {
-                bail!("Duplicate alias: {}", alias);
+            let action = parse_action(action, s)?;
+            let vkey = s.add_virtual_key(action)?;
+            if s.virtual_key_names.insert(name.t.clone(), vkey).is_some() {
+                bail!(ParseError::new(
+                    name.span,
+                    format!("Duplicate virtual key: {}", name)
+                ));
             }
         }
     }
-    Ok(aliases)
+    Ok(())
+}
+
+/// Parse alias->action mappings from multiple exprs starting with defalias. An alias whose action
+/// fails to parse is still defined, as a no-op, so that its uses are not reported as errors too.
+fn parse_aliases(exprs: &[&Vec<SExpr>], s: &mut ParsedState, errs: &mut CfgErrors) {
+    for expr in exprs {
+        let r = parse_defalias(expr, s, errs);
+        errs.check(r);
+    }
+}
+
+/// Parse alias->action mappings from an expr starting with defalias. Errors in the actions are
+/// added to `errs`, and the returned error is one in the structure of the expr.
+fn parse_defalias(expr: &[SExpr], s: &mut ParsedState, errs: &mut CfgErrors) -> Result<()> {
+    let mut subexprs = match check_first_expr(expr.iter(), "defalias") {
+        Ok(s) => s,
+        Err(e) => bail!(e),
+    };
+
+    // Read k-v pairs from the configuration
+    while let Some(alias) = subexprs.next() {
+        let action = match subexprs.next() {
+            Some(v) => v,
+            None => bail!(ParseError::new(alias.span(), "Incorrect number of elements found in defalias; they should be pairs of aliases and actions.")),
+        };
+        let alias = match alias {
+            SExpr::Atom(a) => a,
+            _ => bail!(ParseError::new(
+                alias.span(),
+                format!("Alias keys must be atoms. Invalid alias: {:?}", alias)
+            )),
+        };
+        let action = errs.check(parse_action(action, s)).unwrap_or(&Action::NoOp);
+        if s.aliases.insert(alias.t.clone(), action).is_some() {
+            errs.0
+                .push(ParseError::new(alias.span, format!("Duplicate alias: {}", alias)).into());
+        }
+    }
+    Ok(())
 }
 
 /// Returns a `&'static T` by leaking a box.
@@ -467,15 +1817,12 @@ fn sref<T>(v: T) -> &'static T {
 }
 
 /// Parse a `kanata_keyberon::action::Action` from a `SExpr`.
-fn parse_action(
-    expr: &SExpr,
-    aliases: &Aliases,
-    layers: &LayerIndexes,
-) -> Result<&'static KanataAction> {
-    match expr {
-        SExpr::Atom(a) => parse_action_atom(a, aliases),
-        SExpr::List(l) => parse_action_list(l, aliases, layers),
-    }
+fn parse_action(expr: &SExpr, s: &mut ParsedState) -> Result<&'static KanataAction> {
+    let action = match expr {
+        SExpr::Atom(a) => parse_action_atom(a, &s.aliases),
+        SExpr::List(l) => parse_action_list(l, s),
+    };
+    with_span(action, expr.span())
 }
 
 /// Parse a `kanata_keyberon::action::Action` from a string.
@@ -484,19 +1831,67 @@ fn parse_action_atom(ac: &str, aliases: &Aliases) -> Result<&'static KanataActio
         "_" => return Ok(sref(Action::Trans)),
         "XX" => return Ok(sref(Action::NoOp)),
         "lrld" => return Ok(sref(Action::Custom(CustomAction::LiveReload))),
-        "mlft" => return Ok(sref(Action::Custom(CustomAction::Mouse(Btn::Left)))),
-        "mrgt" => return Ok(sref(Action::Custom(CustomAction::Mouse(Btn::Right)))),
-        "mmid" => return Ok(sref(Action::Custom(CustomAction::Mouse(Btn::Mid)))),
+        "sldr" => return Ok(sref(Action::Custom(CustomAction::SequenceLeader))),
+        "rpt" => return Ok(sref(Action::Custom(CustomAction::Repeat))),
+        "bounce-keys-toggle" => return Ok(sref(Action::Custom(CustomAction::BounceKeysToggle))),
+        "dynamic-macro-record-stop" => {
+            return Ok(sref(Action::Custom(CustomAction::DynamicMacroRecordStop)))
+        }
+        "layer-lock" => return Ok(sref(Action::Custom(CustomAction::LayerLock))),
+        "mwheel-up" => {
+            return Ok(sref(Action::Custom(CustomAction::MWheel {
+                direction: MWheelDirection::Up,
+                reverse: None,
+            })))
+        }
+        "mwheel-down" => {
+            return Ok(sref(Action::Custom(CustomAction::MWheel {
+                direction: MWheelDirection::Down,
+                reverse: None,
+            })))
+        }
+        "mwheel-left" => {
+            return Ok(sref(Action::Custom(CustomAction::MWheel {
+                direction: MWheelDirection::Left,
+                reverse: None,
+            })))
+        }
+        "mwheel-right" => {
+            return Ok(sref(Action::Custom(CustomAction::MWheel {
+                direction: MWheelDirection::Right,
+                reverse: None,
+            })))
+        }
+        "movemouse-up" => {
+            return Ok(sref(Action::Custom(CustomAction::MoveMouse(
+                MoveDirection::Up,
+            ))))
+        }
+        "movemouse-down" => {
+            return Ok(sref(Action::Custom(CustomAction::MoveMouse(
+                MoveDirection::Down,
+            ))))
+        }
+        "movemouse-left" => {
+            return Ok(sref(Action::Custom(CustomAction::MoveMouse(
+                MoveDirection::Left,
+            ))))
+        }
+        "movemouse-right" => {
+            return Ok(sref(Action::Custom(CustomAction::MoveMouse(
+                MoveDirection::Right,
+            ))))
+        }
         _ => {}
     };
     if let Some(oscode) = str_to_oscode(ac) {
-        return Ok(sref(k(oscode.into())));
+        return Ok(oscode_action(oscode));
     }
     if let Some(alias) = ac.strip_prefix('@') {
         return match aliases.get(alias) {
             Some(ac) => Ok(*ac),
             None => bail!(
-                "Referenced unknown alias {}. Note that order of declarations matter.",
+                "Referenced unknown alias {}. Note that order of declarations matter: an alias can only reference aliases declared before it.",
                 alias
             ),
         };
@@ -533,11 +1928,7 @@ fn parse_action_atom(ac: &str, aliases: &Aliases) -> Result<&'static KanataActio
 }
 
 /// Parse a `kanata_keyberon::action::Action` from a `SExpr::List`.
-fn parse_action_list(
-    ac: &[SExpr],
-    aliases: &Aliases,
-    layers: &LayerIndexes,
-) -> Result<&'static KanataAction> {
+fn parse_action_list(ac: &[SExpr], s: &mut ParsedState) -> Result<&'static KanataAction> {
     if ac.is_empty() {
         return Ok(sref(Action::NoOp));
     }
@@ -546,27 +1937,82 @@ fn parse_action_list(
         _ => bail!("Action list must start with an atom"),
     };
     match ac_type.as_str() {
-        "layer-switch" => parse_layer_base(&ac[1..], layers),
-        "layer-toggle" => parse_layer_toggle(&ac[1..], layers),
-        "tap-hold" => parse_tap_hold(&ac[1..], aliases, layers),
-        "multi" => parse_multi(&ac[1..], aliases, layers),
-        "macro" => parse_macro(&ac[1..], aliases, layers),
+        "layer-switch" => parse_layer_base(&ac[1..], &s.layer_idxs),
+        "layer-while-held" => parse_layer_while_held(&ac[1..], &s.layer_idxs),
+        "layer-toggle" => parse_layer_toggle(&ac[1..], &s.layer_idxs),
+        "tap-hold" => parse_tap_hold(&ac[1..], s, HoldTapConfig::Default),
+        "tap-hold-press" => parse_tap_hold(&ac[1..], s, HoldTapConfig::HoldOnOtherKeyPress),
+        "tap-hold-release" => parse_tap_hold(&ac[1..], s, HoldTapConfig::PermissiveHold),
+        "tap-dance" => parse_tap_dance(&ac[1..], s, TapDanceConfig::Lazy),
+        "tap-dance-eager" => parse_tap_dance(&ac[1..], s, TapDanceConfig::Eager),
+        "one-shot" => parse_one_shot(&ac[1..], s),
+        "raw" => Ok(oscode_action(parse_raw_oscode(&ac[1..])?)),
+        "mod-lock" => parse_key_lock(&ac[1..], "mod-lock"),
+        "key-lock" => parse_key_lock(&ac[1..], "key-lock"),
+        "unshift" => parse_unmod(&ac[1..], "unshift", &[KeyCode::LShift, KeyCode::RShift]),
+        "unmod" => parse_unmod(&ac[1..], "unmod", MODIFIERS),
+        "multi" => parse_multi(&ac[1..], s),
+        "macro" => parse_macro(&ac[1..], s, MacroRepeat::Times(1)),
+        "macro-repeat" => parse_macro_repeat(&ac[1..], s),
+        "macro-loop" => parse_macro(&ac[1..], s, MacroRepeat::WhileHeld),
         "unicode" => parse_unicode(&ac[1..]),
+        "dynamic-macro-record" => parse_dynamic_macro(&ac[1..], "dynamic-macro-record")
+            .map(|n| sref(Action::Custom(CustomAction::DynamicMacroRecord(n)))),
+        "dynamic-macro-play" => parse_dynamic_macro(&ac[1..], "dynamic-macro-play")
+            .map(|n| sref(Action::Custom(CustomAction::DynamicMacroPlay(n)))),
+        "switch" => parse_switch(&ac[1..], s),
+        "fork" => parse_fork(&ac[1..], s),
+        "timeout" => parse_timeout_action(&ac[1..], s),
+        "turbo" => parse_turbo(&ac[1..], s),
+        "mirror" => parse_mirror(&ac[1..], s),
+        "mwheel-up" => parse_mwheel(&ac[1..], "mwheel-up", MWheelDirection::Up),
+        "mwheel-down" => parse_mwheel(&ac[1..], "mwheel-down", MWheelDirection::Down),
+        "mwheel-left" => parse_mwheel(&ac[1..], "mwheel-left", MWheelDirection::Left),
+        "mwheel-right" => parse_mwheel(&ac[1..], "mwheel-right", MWheelDirection::Right),
+        "movemouse-speed" => parse_move_mouse_speed(&ac[1..]),
+        "mouse-jiggler" => parse_mouse_jiggler(&ac[1..]),
+        "setmouse" => parse_set_mouse(&ac[1..], s),
+        "gamepad-axis" => parse_gamepad_axis(&ac[1..]),
+        "caps-word" => parse_caps_word(&ac[1..]),
+        "autoshift" => parse_autoshift(&ac[1..], s),
+        "on-press" => parse_virtual_key_action(&ac[1..], s, "on-press"),
+        "on-release" => parse_virtual_key_action(&ac[1..], s, "on-release"),
+        "cmd" => Ok(sref(Action::Custom(CustomAction::Cmd(parse_cmd(
+            &ac[1..],
+            s,
+            "cmd",
+        )?)))),
+        "cmd-output-keys" => Ok(sref(Action::Custom(CustomAction::CmdOutputKeys(
+            parse_cmd(&ac[1..], s, "cmd-output-keys")?,
+        )))),
         _ => bail!(
-            "Unknown action type: {}. Valid types:\n\tlayer-switch\n\tlayer-toggle\n\ttap-hold\n\tmulti\n\tmacro\n\tunicode",
+            "Unknown action type: {}. Valid types:\n\tlayer-switch\n\tlayer-while-held\n\tlayer-toggle\n\ttap-hold\n\ttap-hold-press\n\ttap-hold-release\n\ttap-dance\n\ttap-dance-eager\n\tone-shot\n\tmod-lock\n\tkey-lock\n\tunshift\n\tunmod\n\tmulti\n\tmacro\n\tmacro-repeat\n\tmacro-loop\n\tdynamic-macro-record\n\tdynamic-macro-play\n\tunicode\n\traw\n\tswitch\n\tfork\n\ttimeout\n\tturbo\n\tmirror\n\tmwheel-up\n\tmwheel-down\n\tmwheel-left\n\tmwheel-right\n\tmovemouse-speed\n\tmouse-jiggler\n\tsetmouse\n\tgamepad-axis\n\tcaps-word\n\tautoshift\n\ton-press\n\ton-release\n\tcmd\n\tcmd-output-keys",
             ac_type
         ),
     }
 }
 
 fn parse_layer_base(ac_params: &[SExpr], layers: &LayerIndexes) -> Result<&'static KanataAction> {
-    Ok(sref(Action::DefaultLayer(layer_idx(ac_params, layers)?)))
+    // The base layer is changed by kanata rather than keyberon so that layer-toggle knows which
+    // base layer to restore.
+    Ok(sref(Action::Custom(CustomAction::LayerSwitch(layer_idx(
+        ac_params, layers,
+    )?))))
 }
 
-fn parse_layer_toggle(ac_params: &[SExpr], layers: &LayerIndexes) -> Result<&'static KanataAction> {
+fn parse_layer_while_held(
+    ac_params: &[SExpr],
+    layers: &LayerIndexes,
+) -> Result<&'static KanataAction> {
     Ok(sref(Action::Layer(layer_idx(ac_params, layers)?)))
 }
 
+fn parse_layer_toggle(ac_params: &[SExpr], layers: &LayerIndexes) -> Result<&'static KanataAction> {
+    Ok(sref(Action::Custom(CustomAction::LayerToggle(layer_idx(
+        ac_params, layers,
+    )?))))
+}
+
 fn layer_idx(ac_params: &[SExpr], layers: &LayerIndexes) -> Result<usize> {
     if ac_params.len() != 1 {
         bail!(
@@ -581,7 +2027,7 @@ fn layer_idx(ac_params: &[SExpr], layers: &LayerIndexes) -> Result<usize> {
             ac_params[0]
         ),
     };
-    match layers.get(layer_name) {
+    match layers.get(&layer_name.t) {
         Some(i) => Ok(*i),
         None => bail!("layer name {} is not declared in any deflayer", layer_name),
     }
@@ -589,132 +2035,1718 @@ fn layer_idx(ac_params: &[SExpr], layers: &LayerIndexes) -> Result<usize> {
 
 fn parse_tap_hold(
     ac_params: &[SExpr],
-    aliases: &Aliases,
-    layers: &LayerIndexes,
+    s: &mut ParsedState,
+    config: HoldTapConfig,
 ) -> Result<&'static KanataAction> {
-    if ac_params.len() != 4 {
-        bail!("tap-hold expects 4 atoms after it: <tap-timeout> <hold-timeout> <tap-action> <hold-action>, got {}", ac_params.len())
-    }
-    let tap_timeout =
-        parse_timeout(&ac_params[0]).map_err(|e| anyhow!("invalid tap-timeout: {}", e))?;
-    let hold_timeout =
-        parse_timeout(&ac_params[1]).map_err(|e| anyhow!("invalid tap-timeout: {}", e))?;
-    let tap_action = parse_action(&ac_params[2], aliases, layers)?;
-    let hold_action = parse_action(&ac_params[3], aliases, layers)?;
-    Ok(sref(Action::HoldTap {
-        config: HoldTapConfig::Default,
+    if !(2..=5).contains(&ac_params.len()) {
+        bail!("tap-hold expects 2 to 5 items after it: [<tap-timeout> <hold-timeout>] <tap-action> <hold-action> [(<except-keys>)], got {}", ac_params.len())
+    }
+    // The timeouts are optional as a pair. If they are missing, the defaults from defcfg are used.
+    // The tap timeout is the quick-tap window. Tapping the key and then pressing it again within
+    // the window holds the tap action instead of activating the hold action.
+    let (tap_timeout, hold_timeout, ac_params) = if ac_params.len() >= 4 {
+        (
+            parse_timeout(&ac_params[0]).map_err(|e| anyhow!("invalid tap-timeout: {}", e))?,
+            parse_timeout(&ac_params[1]).map_err(|e| anyhow!("invalid hold-timeout: {}", e))?,
+            &ac_params[2..],
+        )
+    } else {
+        (s.default_tap_time, s.default_hold_time, ac_params)
+    };
+    let tap_action = parse_action(&ac_params[0], s)?;
+    let hold_action = parse_action(&ac_params[1], s)?;
+    let tap_hold = Action::HoldTap {
+        config,
         tap_hold_interval: tap_timeout,
         timeout: hold_timeout,
         tap: tap_action,
         hold: hold_action,
-    }))
+    };
+    let except_keys = match ac_params.get(2) {
+        None => return Ok(sref(tap_hold)),
+        Some(keys) => parse_key_list(keys).map_err(|e| anyhow!("invalid except-keys: {}", e))?,
+    };
+    // The custom action is reported by keyberon as soon as the tap-hold key is pressed, which lets
+    // the processing loop know that the tap/hold decision is pending. The coordinate is filled in
+    // later by `parse_layers`.
+    Ok(sref(Action::MultipleActions(sref(vec![
+        tap_hold,
+        Action::Custom(CustomAction::TapHoldExcept {
+            coord: (0, 0),
+            timeout: hold_timeout,
+            keys: except_keys,
+        }),
+    ]))))
 }
 
-fn parse_timeout(a: &SExpr) -> Result<u16> {
-    match a {
-        SExpr::Atom(a) => a.parse().map_err(|e| anyhow!("expected integer: {}", e)),
-        _ => bail!("expected atom, not list for integer"),
+/// Parse a list of keys, e.g. `(a s d f)`.
+fn parse_key_list(expr: &SExpr) -> Result<&'static [OsCode]> {
+    let keys = match expr {
+        SExpr::List(l) => l,
+        SExpr::Atom(_) => bail!("expected a list of keys, found atom: {:?}", expr),
+    };
+    let mut oscodes = Vec::new();
+    for key in keys.iter() {
+        match key {
+            SExpr::Atom(a) => oscodes.push(str_to_oscode(a).ok_or_else(|| {
+                ParseError::new(a.span, format!("unknown key in list: \"{}\"", a))
+            })?),
+            SExpr::List(_) => bail!(ParseError::new(
+                key.span(),
+                format!("expected only keys in the list, found: {:?}", key)
+            )),
+        }
     }
+    Ok(sref(oscodes))
 }
 
-fn parse_multi(
-    ac_params: &[SExpr],
-    aliases: &Aliases,
-    layers: &LayerIndexes,
-) -> Result<&'static KanataAction> {
-    if ac_params.is_empty() {
-        bail!("multi expects at least one atom after it")
-    }
-    let mut actions = Vec::new();
-    for expr in ac_params {
-        let ac = parse_action(expr, aliases, layers)?;
-        actions.push(*ac);
+/// Returns a copy of the action that knows the key position it is mapped to, for the actions that
+/// need it: tap-hold with except-keys, tap-dance, one-shot, switch and timeout. These actions are
+/// also found inside tap-hold and multi actions, so that actions nested in different keys don't
+/// share a position. The actions of virtual keys are given the positions of their virtual keys.
+/// Other actions are returned as-is.
+fn set_action_coord(ac: &'static KanataAction, coord: KeyCoord) -> &'static KanataAction {
+    match ac {
+        Action::HoldTap {
+            timeout,
+            hold,
+            tap,
+            config,
+            tap_hold_interval,
+        } => sref(Action::HoldTap {
+            timeout: *timeout,
+            hold: set_action_coord(hold, coord),
+            tap: set_action_coord(tap, coord),
+            config: *config,
+            tap_hold_interval: *tap_hold_interval,
+        }),
+        Action::MultipleActions(actions) => sref(Action::MultipleActions(sref(
+            actions
+                .iter()
+                .map(|ac| *set_action_coord(ac, coord))
+                .collect::<Vec<_>>(),
+        ))),
+        Action::Custom(CustomAction::TapHoldExcept { timeout, keys, .. }) => {
+            sref(Action::Custom(CustomAction::TapHoldExcept {
+                coord,
+                timeout: *timeout,
+                keys,
+            }))
+        }
+        Action::Custom(CustomAction::TapDance { td, .. }) => {
+            sref(Action::Custom(CustomAction::TapDance { coord, td }))
+        }
+        Action::Custom(CustomAction::OneShot { vkey, cfg, .. }) => {
+            sref(Action::Custom(CustomAction::OneShot {
+                coord,
+                vkey: *vkey,
+                cfg: *cfg,
+            }))
+        }
+        Action::Custom(CustomAction::Switch { switch, .. }) => {
+            sref(Action::Custom(CustomAction::Switch { coord, switch }))
+        }
+        Action::Custom(CustomAction::Timeout { timeout, .. }) => {
+            sref(Action::Custom(CustomAction::Timeout { coord, timeout }))
+        }
+        _ => ac,
     }
-    Ok(sref(Action::MultipleActions(sref(actions))))
 }
 
-fn parse_macro(
+/// Returns the actions of the virtual keys, with the positions of their virtual keys set.
+fn virtual_key_actions(s: &ParsedState) -> Vec<&'static KanataAction> {
+    s.virtual_keys
+        .iter()
+        .enumerate()
+        .map(|(i, ac)| set_action_coord(ac, (VIRTUAL_ROW, i as u8)))
+        .collect()
+}
+
+fn parse_tap_dance(
     ac_params: &[SExpr],
-    aliases: &Aliases,
-    layers: &LayerIndexes,
+    s: &mut ParsedState,
+    config: TapDanceConfig,
 ) -> Result<&'static KanataAction> {
-    if ac_params.is_empty() {
-        bail!("macro expects at least one atom after it")
-    }
-    let mut events = Vec::new();
-    for expr in ac_params {
-        if let Ok(delay) = parse_timeout(expr) {
-            events.push(SequenceEvent::Delay {
-                duration: delay.into(),
-            });
-            continue;
-        }
-        match parse_action(expr, aliases, layers)? {
-            Action::KeyCode(kc) => {
-                // Should note that I tried `SequenceEvent::Tap` initially but it seems to be buggy
-                // so I changed the code to use individual press and release. The SequenceEvent
-                // code is from a PR that (at the time of this writing) hasn't yet been merged into
-                // keyberon master and doesn't have tests written for it yet. This seems to work as
-                // expected right now though.
-                events.push(SequenceEvent::Press(*kc));
-                events.push(SequenceEvent::Release(*kc));
-            }
-            Action::MultipleKeyCodes(kcs) => {
-                // chord - press in order then release in the reverse order
-                for kc in kcs.iter() {
-                    events.push(SequenceEvent::Press(*kc));
-                }
-                for kc in kcs.iter().rev() {
-                    events.push(SequenceEvent::Release(*kc));
-                }
-            }
-            _ => {
-                bail!(
-                    "Action \"macro\" only accepts delays, keys, and chords. Invalid value {:?}",
-                    expr
-                )
-            }
-        }
+    // The timeout is optional. If it is missing, tap-time from defcfg is used.
+    let (timeout, actions) = match ac_params {
+        [actions] => (s.default_tap_time, actions),
+        [timeout, actions] => (
+            parse_timeout(timeout).map_err(|e| anyhow!("invalid tap-dance timeout: {}", e))?,
+            actions,
+        ),
+        _ => bail!(
+            "tap-dance expects 1 or 2 items after it: [<timeout>] (<actions>...), got {}",
+            ac_params.len()
+        ),
+    };
+    let actions = match actions {
+        SExpr::List(l) if !l.is_empty() => l,
+        _ => bail!(
+            "tap-dance expects a non-empty list of actions, found: {:?}",
+            actions
+        ),
+    };
+    // Each action of the dance becomes a virtual key that is activated by kanata once the number
+    // of taps is known.
+    let mut vkeys = Vec::new();
+    for expr in actions.iter() {
+        let ac = parse_action(expr, s)?;
+        vkeys.push(s.add_virtual_key(ac)?);
     }
-    Ok(sref(Action::Sequence {
-        events: sref(events),
-    }))
+    Ok(sref(Action::Custom(CustomAction::TapDance {
+        coord: (0, 0),
+        td: sref(TapDance {
+            timeout,
+            config,
+            actions: sref(vkeys),
+        }),
+    })))
 }
 
-fn parse_unicode(ac_params: &[SExpr]) -> Result<&'static KanataAction> {
-    const ERR_STR: &str = "unicode expects exactly one unicode character as an argument";
-    if ac_params.len() != 1 {
-        bail!(ERR_STR)
+/// Parse `(timeout <interrupted-action> <timeout> <timed-out-action>)`. The first action is
+/// activated if another key is pressed within the timeout, otherwise the second action is
+/// activated once the timeout expires.
+fn parse_timeout_action(ac_params: &[SExpr], s: &mut ParsedState) -> Result<&'static KanataAction> {
+    let (interrupted, timeout, timed_out) = match ac_params {
+        [interrupted, timeout, timed_out] => (interrupted, timeout, timed_out),
+        _ => bail!(
+            "timeout expects 3 items after it: <interrupted-action> <timeout> <timed-out-action>, got {}",
+            ac_params.len()
+        ),
+    };
+    let timeout =
+        parse_timeout(timeout).map_err(|e| anyhow!("invalid timeout of timeout: {}", e))?;
+    let interrupted = parse_action(interrupted, s)?;
+    let timed_out = parse_action(timed_out, s)?;
+    Ok(sref(Action::Custom(CustomAction::Timeout {
+        coord: (0, 0),
+        timeout: sref(Timeout {
+            timeout,
+            interrupted: s.add_virtual_key(interrupted)?,
+            timed_out: s.add_virtual_key(timed_out)?,
+        }),
+    })))
+}
+
+/// Parse `(turbo <interval> <action>)`, which taps the action every interval milliseconds while it
+/// is held.
+fn parse_turbo(ac_params: &[SExpr], s: &mut ParsedState) -> Result<&'static KanataAction> {
+    let (interval, action) = match ac_params {
+        [interval, action] => (interval, action),
+        _ => bail!(
+            "turbo expects 2 items after it: <interval> <action>, got {}",
+            ac_params.len()
+        ),
+    };
+    let interval = parse_timeout(interval).map_err(|e| anyhow!("invalid turbo interval: {}", e))?;
+    // A tap takes two milliseconds: one for the press and one for the release.
+    if interval < 2 {
+        bail!("invalid turbo interval: {}, expected at least 2", interval)
     }
-    match &ac_params[0] {
-        SExpr::Atom(s) => {
-            if s.chars().count() != 1 {
-                bail!(ERR_STR)
+    let action = parse_action(action, s)?;
+    Ok(sref(Action::Custom(CustomAction::Turbo(sref(Turbo {
+        interval,
+        vkey: s.add_virtual_key(action)?,
+    })))))
+}
+
+/// Parse `(mirror <left-key> <right-key>)`. The keys are adjacent keys of a row of defsrc, between
+/// which the axis of the mirror lies. In every row of defsrc, the keys at the same distance from
+/// the axis swap places.
+fn parse_mirror(ac_params: &[SExpr], s: &mut ParsedState) -> Result<&'static KanataAction> {
+    const ERR_STR: &str = "mirror expects 2 items after it: <left-key> <right-key>, two adjacent keys of a row of defsrc";
+    let (left, right) = match ac_params {
+        [left, right] => (
+            usize::from(parse_key_expr(left, "mirror")?.to_remappable()),
+            usize::from(parse_key_expr(right, "mirror")?.to_remappable()),
+        ),
+        _ => bail!(ERR_STR),
+    };
+    let axis = s.src_rows.iter().find_map(|row| {
+        row.windows(2)
+            .position(|w| usize::from(w[0]) == left && usize::from(w[1]) == right)
+            .map(|i| i + 1)
+    });
+    let axis = match axis {
+        Some(axis) => axis,
+        None => bail!(ERR_STR),
+    };
+    let mut pairs = Vec::new();
+    for row in s.src_rows.iter() {
+        // Key `i` swaps places with key `2 * axis - 1 - i`.
+        for i in 0..axis.min(row.len()) {
+            if let Some(&mirrored) = row.get(2 * axis - 1 - i) {
+                pairs.push((row[i], mirrored));
+                pairs.push((mirrored, row[i]));
             }
-            Ok(sref(Action::Custom(CustomAction::Unicode(
-                s.chars().next().unwrap(),
-            ))))
         }
-        _ => bail!(ERR_STR),
     }
+    Ok(sref(Action::Custom(CustomAction::Mirror(sref(Mirror {
+        pairs: sref(pairs).as_ref(),
+    })))))
 }
 
-/// Mutates `layers::LAYERS` using the inputs.
-fn parse_layers(
-    layers: &[&Vec<SExpr>],
-    aliases: &Aliases,
-    layer_idxs: &LayerIndexes,
-    mapping_order: &[usize],
-) -> Result<KanataLayers> {
-    let mut layers_cfg = new_layers();
-    for (layer_level, layer) in layers.iter().enumerate() {
-        // skip deflayer and name
-        for (i, ac) in layer.iter().skip(2).enumerate() {
-            let ac = parse_action(ac, aliases, layer_idxs)?;
+/// Parse `(mwheel-<direction> reverse)` or `(mwheel-<direction> no-reverse)`, which override
+/// reverse-scroll of defcfg for the action.
+fn parse_mwheel(
+    ac_params: &[SExpr],
+    name: &str,
+    direction: MWheelDirection,
+) -> Result<&'static KanataAction> {
+    let reverse = match ac_params {
+        [SExpr::Atom(a)] if a.t == "reverse" => true,
+        [SExpr::Atom(a)] if a.t == "no-reverse" => false,
+        _ => bail!("{} expects one item after it: reverse or no-reverse", name),
+    };
+    Ok(sref(Action::Custom(CustomAction::MWheel {
+        direction,
+        reverse: Some(reverse),
+    })))
+}
+
+fn parse_move_mouse_speed(ac_params: &[SExpr]) -> Result<&'static KanataAction> {
+    const ERR_STR: &str = "movemouse-speed expects one item after it: <percentage>";
+    let percentage = match ac_params {
+        [percentage] => parse_timeout(percentage).map_err(|e| anyhow!("{}: {}", ERR_STR, e))?,
+        _ => bail!(ERR_STR),
+    };
+    Ok(sref(Action::Custom(CustomAction::MoveMouseSpeed(
+        percentage,
+    ))))
+}
+
+fn parse_mouse_jiggler(ac_params: &[SExpr]) -> Result<&'static KanataAction> {
+    const ERR_STR: &str = "mouse-jiggler expects one item after it: <seconds>";
+    let seconds = match ac_params {
+        [seconds] => parse_timeout(seconds).map_err(|e| anyhow!("{}: {}", ERR_STR, e))?,
+        _ => bail!(ERR_STR),
+    };
+    if seconds == 0 {
+        bail!("{}: the number of seconds must be positive", ERR_STR)
+    }
+    Ok(sref(Action::Custom(CustomAction::MouseJiggler(seconds))))
+}
+
+/// Parse `(setmouse <x> <y>)` or `(setmouse <position>)`. The coordinates are pixels or
+/// percentages of the screen size, e.g. `50%`. The position is a corner of the screen or its center.
+fn parse_set_mouse(ac_params: &[SExpr], s: &ParsedState) -> Result<&'static KanataAction> {
+    const ERR_STR: &str = "setmouse expects items after it: <x> <y> or one of top-left, top-right, bottom-left, bottom-right, center";
+    let (x, y) = match ac_params {
+        [SExpr::Atom(position)] => match position.t.as_str() {
+            "top-left" => (0, 0),
+            "top-right" => (u16::MAX, 0),
+            "bottom-left" => (0, u16::MAX),
+            "bottom-right" => (u16::MAX, u16::MAX),
+            "center" => (u16::MAX / 2, u16::MAX / 2),
+            _ => bail!(ERR_STR),
+        },
+        [SExpr::Atom(x), SExpr::Atom(y)] => (
+            parse_mouse_coord(x, s.screen_size.map(|(width, _)| width))?,
+            parse_mouse_coord(y, s.screen_size.map(|(_, height)| height))?,
+        ),
+        _ => bail!(ERR_STR),
+    };
+    Ok(sref(Action::Custom(CustomAction::SetMouse { x, y })))
+}
+
+/// Parse `(gamepad-axis <axis> <value>)`. The axis is one of the sticks or triggers of the
+/// virtual gamepad, which only exists on Linux.
+fn parse_gamepad_axis(ac_params: &[SExpr]) -> Result<&'static KanataAction> {
+    const ERR_STR: &str =
+        "gamepad-axis expects two items after it: <axis> <value>, where the axis is one of lx, ly, rx, ry, lt, rt";
+    if cfg!(not(target_os = "linux")) {
+        bail!("gamepad-axis is only supported on Linux")
+    }
+    let (axis, value) = match ac_params {
+        [SExpr::Atom(axis), SExpr::Atom(value)] => (axis, value),
+        _ => bail!(ERR_STR),
+    };
+    let axis = match axis.t.as_str() {
+        "lx" => GamepadAxis::LeftX,
+        "ly" => GamepadAxis::LeftY,
+        "rx" => GamepadAxis::RightX,
+        "ry" => GamepadAxis::RightY,
+        "lt" => GamepadAxis::LeftTrigger,
+        "rt" => GamepadAxis::RightTrigger,
+        _ => bail!(ERR_STR),
+    };
+    let value: i32 = value
+        .parse()
+        .map_err(|e| anyhow!("invalid gamepad-axis value {}: {}", value.t, e))?;
+    let (min, max) = axis.range();
+    if !(min..=max).contains(&value) {
+        bail!(
+            "invalid gamepad-axis value {}, expected {} to {}",
+            value,
+            min,
+            max
+        )
+    }
+    Ok(sref(Action::Custom(CustomAction::GamepadAxis {
+        axis,
+        value,
+    })))
+}
+
+/// Parse a coordinate of setmouse into the range of 0 to 65535. `screen_len` is the width or
+/// height of the screen in pixels, which is required for pixel coordinates.
+fn parse_mouse_coord(coord: &str, screen_len: Option<u16>) -> Result<u16> {
+    if let Some(percentage) = coord.strip_suffix('%') {
+        let percentage: f64 = percentage
+            .parse()
+            .map_err(|e| anyhow!("invalid setmouse percentage {}: {}", coord, e))?;
+        if !(0.0..=100.0).contains(&percentage) {
+            bail!("invalid setmouse percentage {}, expected 0% to 100%", coord)
+        }
+        return Ok((percentage / 100.0 * f64::from(u16::MAX)).round() as u16);
+    }
+    let pixels: u16 = coord
+        .parse()
+        .map_err(|e| anyhow!("invalid setmouse coordinate {}: {}", coord, e))?;
+    let screen_len = match screen_len {
+        Some(len) => len,
+        None => bail!("setmouse with pixel coordinates requires screen-size in defcfg, e.g. screen-size 1920x1080"),
+    };
+    if pixels >= screen_len {
+        bail!(
+            "invalid setmouse coordinate {}, expected less than the screen size {}",
+            pixels,
+            screen_len
+        )
+    }
+    let max = u32::from(screen_len.saturating_sub(1).max(1));
+    Ok((u32::from(pixels) * u32::from(u16::MAX) / max) as u16)
+}
+
+/// Parse a screen size like `1920x1080`.
+fn parse_screen_size(size: &str) -> Option<(u16, u16)> {
+    let (width, height) = size.split_once('x')?;
+    match (width.parse().ok()?, height.parse().ok()?) {
+        (0, _) | (_, 0) => None,
+        size => Some(size),
+    }
+}
+
+fn parse_one_shot(ac_params: &[SExpr], s: &mut ParsedState) -> Result<&'static KanataAction> {
+    const ERR_STR: &str =
+        "one-shot expects items after it: [<timeout>] <action> [no-stack|stack] [lock|no-lock]";
+    let mut cfg = s.one_shot_defaults;
+    // The timeout is optional. If it is missing, the timeout from defcfg is used.
+    let mut params = ac_params.iter();
+    let has_timeout = ac_params.len() >= 2 && parse_one_shot_option(&ac_params[1]).is_none();
+    if has_timeout {
+        cfg.timeout =
+            parse_timeout(&ac_params[0]).map_err(|e| anyhow!("invalid one-shot timeout: {}", e))?;
+        params.next();
+    }
+    let action = match params.next() {
+        Some(ac) => parse_action(ac, s)?,
+        None => bail!(ERR_STR),
+    };
+    for param in params {
+        match parse_one_shot_option(param) {
+            Some(OneShotOption::Stack(stack)) => cfg.stack = stack,
+            Some(OneShotOption::DoubleTapLock(lock)) => cfg.double_tap_lock = lock,
+            None => bail!("{}\ninvalid one-shot option: {:?}", ERR_STR, param),
+        }
+    }
+    Ok(sref(Action::Custom(CustomAction::OneShot {
+        coord: (0, 0),
+        vkey: s.add_virtual_key(action)?,
+        cfg,
+    })))
+}
+
+enum OneShotOption {
+    Stack(bool),
+    DoubleTapLock(bool),
+}
+
+fn parse_one_shot_option(expr: &SExpr) -> Option<OneShotOption> {
+    match expr {
+        SExpr::Atom(a) => match a.as_str() {
+            "stack" => Some(OneShotOption::Stack(true)),
+            "no-stack" => Some(OneShotOption::Stack(false)),
+            "lock" => Some(OneShotOption::DoubleTapLock(true)),
+            "no-lock" => Some(OneShotOption::DoubleTapLock(false)),
+            _ => None,
+        },
+        SExpr::List(_) => None,
+    }
+}
+
+/// Parse the defaults of one-shot actions from defcfg.
+fn parse_one_shot_defaults(cfg: &HashMap<String, String>, errs: &mut CfgErrors) -> OneShotConfig {
+    let mut defaults = OneShotConfig::default();
+    if let Some(timeout) = errs.check(parse_cfg_num(cfg, "one-shot-timeout", defaults.timeout)) {
+        defaults.timeout = timeout;
+    }
+    if let Some(stack) = cfg.get("one-shot-stack") {
+        match parse_cfg_bool(stack) {
+            Some(stack) => defaults.stack = stack,
+            None => errs
+                .0
+                .push(anyhow!("invalid one-shot-stack in defcfg: {}", stack)),
+        }
+    }
+    if let Some(lock) = errs.check(parse_cfg_flag(cfg, "one-shot-double-tap-lock")) {
+        defaults.double_tap_lock = lock;
+    }
+    defaults
+}
+
+/// Parse the key repeat settings from defcfg and the repeat modes of keys from multiple exprs
+/// starting with defrepeat. Without a repeat-mode in defcfg, keys are repeated by the OS unless
+/// repeat-delay or repeat-rate is set.
+fn parse_key_repeat(
+    cfg: &HashMap<String, String>,
+    exprs: &[&Vec<SExpr>],
+    mapped_keys: &MappedKeys,
+    errs: &mut CfgErrors,
+) -> KeyRepeat {
+    let default_mode = match cfg.get("repeat-mode") {
+        Some(m) => errs
+            .check(parse_repeat_mode(m).ok_or_else(|| {
+                anyhow!(
+                    "invalid repeat-mode in defcfg: {}, expected os, kanata, or none",
+                    m
+                )
+            }))
+            .unwrap_or(RepeatMode::Os),
+        None if cfg.contains_key("repeat-delay") || cfg.contains_key("repeat-rate") => {
+            RepeatMode::Kanata
+        }
+        None => RepeatMode::Os,
+    };
+    let delay = errs
+        .check(parse_cfg_num(cfg, "repeat-delay", 300))
+        .unwrap_or(300);
+    let mut rate: u16 = errs
+        .check(parse_cfg_num(cfg, "repeat-rate", 25))
+        .unwrap_or(25);
+    if !(1..=1000).contains(&rate) {
+        errs.0.push(anyhow!(
+            "invalid repeat-rate in defcfg: {}, expected 1 to 1000 repeats per second",
+            rate
+        ));
+        rate = 25;
+    }
+    let mut modes = HashMap::new();
+    for expr in exprs {
+        errs.check(parse_defrepeat(expr, mapped_keys, &mut modes));
+    }
+    KeyRepeat {
+        delay,
+        interval: 1000 / rate,
+        default_mode,
+        modes,
+    }
+}
+
+/// Parse the repeat modes of keys from an expr starting with defrepeat into `modes`.
+fn parse_defrepeat(
+    expr: &[SExpr],
+    mapped_keys: &MappedKeys,
+    modes: &mut HashMap<u8, RepeatMode>,
+) -> Result<()> {
+    let mut subexprs = match check_first_expr(expr.iter(), "defrepeat") {
+        Ok(s) => s,
+        Err(e) => bail!(e),
+    };
+    while let Some(keys_expr) = subexprs.next() {
+        let mode = match subexprs.next() {
+            Some(SExpr::Atom(m)) => parse_repeat_mode(m).ok_or_else(|| {
+                ParseError::new(m.span, "invalid repeat mode, expected os, kanata, or none")
+            })?,
+            Some(m) => bail!(ParseError::new(
+                m.span(),
+                "invalid repeat mode, expected os, kanata, or none"
+            )),
+            None => bail!(ParseError::new(keys_expr.span(), "Incorrect number of elements found in defrepeat; they should be pairs of key lists and repeat modes.")),
+        };
+        for k in with_span(parse_key_list(keys_expr), keys_expr.span())?.iter() {
+            let code: usize = k.to_remappable().into();
+            if code >= MAPPED_KEYS_LEN || !mapped_keys[code] {
+                bail!(ParseError::new(
+                    keys_expr.span(),
+                    format!("defrepeat key {:?} is not declared in defsrc", k)
+                ))
+            }
+            modes.insert(code as u8, mode);
+        }
+    }
+    Ok(())
+}
+
+/// Parse the debounce time from defcfg and the debounce times of keys from multiple exprs starting
+/// with defdebounce.
+fn parse_debounce(
+    cfg: &HashMap<String, String>,
+    exprs: &[&Vec<SExpr>],
+    mapped_keys: &MappedKeys,
+    errs: &mut CfgErrors,
+) -> Debounce {
+    let default_time = errs.check(parse_cfg_num(cfg, "debounce", 0)).unwrap_or(0);
+    let mut times = HashMap::new();
+    for expr in exprs {
+        errs.check(parse_defdebounce(expr, mapped_keys, &mut times));
+    }
+    Debounce {
+        default_time,
+        times,
+    }
+}
+
+/// Parse the debounce times of keys from an expr starting with defdebounce into `times`.
+fn parse_defdebounce(
+    expr: &[SExpr],
+    mapped_keys: &MappedKeys,
+    times: &mut HashMap<u8, u16>,
+) -> Result<()> {
+    let mut subexprs = match check_first_expr(expr.iter(), "defdebounce") {
+        Ok(s) => s,
+        Err(e) => bail!(e),
+    };
+    while let Some(keys_expr) = subexprs.next() {
+        let time = match subexprs.next() {
+            Some(t) => with_span(
+                parse_timeout(t).map_err(|e| anyhow!("invalid debounce time: {}", e)),
+                t.span(),
+            )?,
+            None => bail!(ParseError::new(keys_expr.span(), "Incorrect number of elements found in defdebounce; they should be pairs of key lists and debounce times.")),
+        };
+        for k in with_span(parse_key_list(keys_expr), keys_expr.span())?.iter() {
+            let code: usize = k.to_remappable().into();
+            if code >= MAPPED_KEYS_LEN || !mapped_keys[code] {
+                bail!(ParseError::new(
+                    keys_expr.span(),
+                    format!("defdebounce key {:?} is not declared in defsrc", k)
+                ))
+            }
+            times.insert(code as u8, time);
+        }
+    }
+    Ok(())
+}
+
+fn parse_repeat_mode(mode: &str) -> Option<RepeatMode> {
+    match mode {
+        "os" => Some(RepeatMode::Os),
+        "kanata" => Some(RepeatMode::Kanata),
+        "none" => Some(RepeatMode::None),
+        _ => None,
+    }
+}
+
+/// Parse a defcfg timeout in milliseconds that defaults to 200 if it is missing.
+fn parse_cfg_timeout(cfg: &HashMap<String, String>, name: &str) -> Result<u16> {
+    match cfg.get(name) {
+        Some(t) => t
+            .parse()
+            .map_err(|e| anyhow!("invalid {} in defcfg: {}", name, e)),
+        None => Ok(200),
+    }
+}
+
+/// Parse the speed settings of the movemouse actions from defcfg.
+fn parse_mouse_move(cfg: &HashMap<String, String>, errs: &mut CfgErrors) -> MouseMove {
+    let mut parse_speed = |name: &str, default: u16| {
+        errs.check(parse_cfg_num(cfg, name, default))
+            .unwrap_or(default)
+    };
+    let mouse_move = MouseMove {
+        start_speed: parse_speed("movemouse-start-speed", 100),
+        max_speed: parse_speed("movemouse-max-speed", 1000),
+        ramp_time: parse_speed("movemouse-ramp-time", 1000),
+    };
+    if mouse_move.max_speed < mouse_move.start_speed {
+        errs.0.push(anyhow!(
+            "invalid movemouse-max-speed in defcfg: {}, expected at least movemouse-start-speed {}",
+            mouse_move.max_speed,
+            mouse_move.start_speed
+        ))
+    }
+    mouse_move
+}
+
+fn parse_mouse_wheel(cfg: &HashMap<String, String>, errs: &mut CfgErrors) -> MouseWheel {
+    let mouse_wheel = MouseWheel {
+        interval: errs
+            .check(parse_cfg_num(cfg, "mwheel-interval", 0))
+            .unwrap_or(0),
+        distance: errs
+            .check(parse_cfg_num(cfg, "mwheel-distance", 120))
+            .unwrap_or(120),
+    };
+    if mouse_wheel.distance == 0 {
+        errs.0.push(anyhow!(
+            "invalid mwheel-distance in defcfg: 0, expected a positive number"
+        ))
+    }
+    mouse_wheel
+}
+
+/// Parse a boolean defcfg value.
+fn parse_cfg_bool(val: &str) -> Option<bool> {
+    match val {
+        "true" | "yes" => Some(true),
+        "false" | "no" => Some(false),
+        _ => None,
+    }
+}
+
+/// Parse a boolean defcfg entry that defaults to false if it is missing.
+fn parse_cfg_flag(cfg: &HashMap<String, String>, name: &str) -> Result<bool> {
+    match cfg.get(name) {
+        Some(v) => parse_cfg_bool(v).ok_or_else(|| anyhow!("invalid {} in defcfg: {}", name, v)),
+        None => Ok(false),
+    }
+}
+
+/// Parse a numeric defcfg entry that defaults to `default` if it is missing.
+fn parse_cfg_num<T>(cfg: &HashMap<String, String>, name: &str, default: T) -> Result<T>
+where
+    T: std::str::FromStr,
+    T::Err: std::fmt::Display,
+{
+    match cfg.get(name) {
+        Some(v) => v
+            .parse()
+            .map_err(|e| anyhow!("invalid {} in defcfg: {}", name, e)),
+        None => Ok(default),
+    }
+}
+
+/// Parse the parameters of `(raw <code>)` into the OS key code with that number.
+fn parse_raw_oscode(ac_params: &[SExpr]) -> Result<OsCode> {
+    const ERR_STR: &str = "raw expects one atom: the number of an OS key code";
+    let code: u32 = match ac_params {
+        [SExpr::Atom(n)] => n.parse().map_err(|e| anyhow!("{}: {}", ERR_STR, e))?,
+        _ => bail!(ERR_STR),
+    };
+    OsCode::from_u32(code).ok_or_else(|| anyhow!("{} is not a valid OS key code", code))
+}
+
+/// Returns the action that presses the OS key code. Codes without an equivalent keyberon key code
+/// are pressed by kanata instead of by the layout. The codes of the mouse wheel scroll it.
+fn oscode_action(oscode: OsCode) -> &'static KanataAction {
+    if let Some(direction) = oscode.mwheel_direction() {
+        return sref(Action::Custom(CustomAction::MWheel {
+            direction,
+            reverse: None,
+        }));
+    }
+    match KeyCode::from(oscode) {
+        KeyCode::No => sref(Action::Custom(CustomAction::RawKey(oscode))),
+        kc => sref(k(kc)),
+    }
+}
+
+const MODIFIERS: &[KeyCode] = &[
+    KeyCode::LCtrl,
+    KeyCode::RCtrl,
+    KeyCode::LShift,
+    KeyCode::RShift,
+    KeyCode::LAlt,
+    KeyCode::RAlt,
+    KeyCode::LGui,
+    KeyCode::RGui,
+];
+
+/// Parse `(unshift <keys>...)` or `(unmod <keys>...)`, which release `mods` while the keys are
+/// held.
+fn parse_unmod(
+    ac_params: &[SExpr],
+    ac_type: &str,
+    mods: &'static [KeyCode],
+) -> Result<&'static KanataAction> {
+    if ac_params.is_empty() {
+        bail!("{} expects one or more keys", ac_type)
+    }
+    let keys = ac_params
+        .iter()
+        .map(|key| match key {
+            SExpr::Atom(a) => Ok(str_to_oscode(a)
+                .ok_or_else(|| ParseError::new(a.span, format!("unknown key: \"{}\"", a)))?
+                .into()),
+            SExpr::List(_) => bail!("{} expects only keys, found: {:?}", ac_type, key),
+        })
+        .collect::<Result<Vec<KeyCode>>>()?;
+    Ok(sref(Action::Custom(CustomAction::Unmod {
+        keys: sref(keys),
+        mods,
+    })))
+}
+
+/// Parse `(mod-lock <modifier>)` or `(key-lock <key>)`.
+fn parse_key_lock(ac_params: &[SExpr], ac_type: &str) -> Result<&'static KanataAction> {
+    let err_str = match ac_type {
+        "mod-lock" => "mod-lock expects exactly one modifier key as an argument",
+        _ => "key-lock expects exactly one key or mouse button as an argument",
+    };
+    let a = match ac_params {
+        [SExpr::Atom(a)] => a,
+        _ => bail!(err_str),
+    };
+    let kc: KeyCode = str_to_oscode(a)
+        .ok_or_else(|| ParseError::new(a.span, format!("{}, found: {}", err_str, a)))?
+        .into();
+    if ac_type == "mod-lock" && !MODIFIERS.contains(&kc) {
+        bail!(ParseError::new(
+            a.span,
+            format!("{}, found: {}", err_str, a)
+        ))
+    }
+    Ok(sref(Action::Custom(CustomAction::KeyLock(kc))))
+}
+
+fn parse_timeout(a: &SExpr) -> Result<u16> {
+    match a {
+        SExpr::Atom(a) => a.parse().map_err(|e| anyhow!("expected integer: {}", e)),
+        _ => bail!("expected atom, not list for integer"),
+    }
+}
+
+fn parse_multi(ac_params: &[SExpr], s: &mut ParsedState) -> Result<&'static KanataAction> {
+    if ac_params.is_empty() {
+        bail!("multi expects at least one atom after it")
+    }
+    let mut actions = Vec::new();
+    for expr in ac_params {
+        let ac = parse_action(expr, s)?;
+        actions.push(*ac);
+    }
+    Ok(sref(Action::MultipleActions(sref(actions))))
+}
+
+fn parse_macro_repeat(ac_params: &[SExpr], s: &mut ParsedState) -> Result<&'static KanataAction> {
+    if ac_params.len() < 2 {
+        bail!("macro-repeat expects a repeat count followed by at least one atom")
+    }
+    let times = match parse_timeout(&ac_params[0]) {
+        Ok(0) | Err(_) => bail!(
+            "macro-repeat expects a positive repeat count, found: {:?}",
+            ac_params[0]
+        ),
+        Ok(n) => n,
+    };
+    parse_macro(&ac_params[1..], s, MacroRepeat::Times(times))
+}
+
+fn parse_macro(
+    ac_params: &[SExpr],
+    s: &mut ParsedState,
+    repeat: MacroRepeat,
+) -> Result<&'static KanataAction> {
+    if ac_params.is_empty() {
+        bail!("macro expects at least one atom after it")
+    }
+    let mut events = Vec::new();
+    for expr in ac_params {
+        if let Ok(delay) = parse_timeout(expr) {
+            events.push(MacroEvent::Delay(delay));
+            continue;
+        }
+        if let Some((mods, idx)) = parse_matched_key_ref(expr) {
+            for kc in mods.iter() {
+                events.push(MacroEvent::Press(*kc));
+            }
+            events.push(MacroEvent::PressMatched(idx));
+            events.push(MacroEvent::ReleaseMatched(idx));
+            for kc in mods.iter().rev() {
+                events.push(MacroEvent::Release(*kc));
+            }
+            continue;
+        }
+        match parse_action(expr, s)? {
+            Action::KeyCode(kc) => {
+                events.push(MacroEvent::Press(*kc));
+                events.push(MacroEvent::Release(*kc));
+            }
+            Action::MultipleKeyCodes(kcs) => {
+                // chord - press in order then release in the reverse order
+                for kc in kcs.iter() {
+                    events.push(MacroEvent::Press(*kc));
+                }
+                for kc in kcs.iter().rev() {
+                    events.push(MacroEvent::Release(*kc));
+                }
+            }
+            _ => {
+                bail!(
+                    "Action \"macro\" only accepts delays, keys, chords, and sequence matches. Invalid value {:?}",
+                    expr
+                )
+            }
+        }
+    }
+    Ok(sref(Action::Custom(CustomAction::Macro(sref(Macro {
+        events: sref(events),
+        cancel_on_press: s.macro_cancel_on_press,
+        repeat,
+    })))))
+}
+
+/// Parse a reference to a key matched by a wildcard or range of a sequence, e.g. `%1` for the
+/// first one, optionally with modifier prefixes like `C-S-%1`. Returns the modifiers and the
+/// zero-based index of the match.
+fn parse_matched_key_ref(expr: &SExpr) -> Option<(Vec<KeyCode>, u8)> {
+    let mut rem = match expr {
+        SExpr::Atom(a) => a.as_str(),
+        SExpr::List(_) => return None,
+    };
+    let mut mods = Vec::new();
+    loop {
+        if let Some(rest) = rem.strip_prefix("C-") {
+            mods.push(KeyCode::LCtrl);
+            rem = rest;
+        } else if let Some(rest) = rem.strip_prefix("S-") {
+            mods.push(KeyCode::LShift);
+            rem = rest;
+        } else if let Some(rest) = rem.strip_prefix("A-") {
+            mods.push(KeyCode::LAlt);
+            rem = rest;
+        } else {
+            break;
+        }
+    }
+    match rem.strip_prefix('%')?.parse::<u8>() {
+        Ok(n) if n > 0 => Some((mods, n - 1)),
+        _ => None,
+    }
+}
+
+fn parse_unicode(ac_params: &[SExpr]) -> Result<&'static KanataAction> {
+    const ERR_STR: &str = "unicode expects exactly one unicode character as an argument";
+    if ac_params.len() != 1 {
+        bail!(ERR_STR)
+    }
+    match &ac_params[0] {
+        SExpr::Atom(s) => {
+            if s.chars().count() != 1 {
+                bail!(ERR_STR)
+            }
+            Ok(sref(Action::Custom(CustomAction::Unicode(
+                s.chars().next().unwrap(),
+            ))))
+        }
+        _ => bail!(ERR_STR),
+    }
+}
+
+/// Parse the number of the dynamic macro of the dynamic macro actions.
+fn parse_dynamic_macro(ac_params: &[SExpr], ac_type: &str) -> Result<u16> {
+    match ac_params {
+        [SExpr::Atom(n)] => n
+            .parse()
+            .map_err(|e| anyhow!("invalid {} number: {}", ac_type, e)),
+        _ => bail!("{} expects one atom: the number of the macro", ac_type),
+    }
+}
+
+fn parse_caps_word(ac_params: &[SExpr]) -> Result<&'static KanataAction> {
+    if ac_params.len() != 1 {
+        bail!("caps-word expects one atom: the timeout in milliseconds")
+    }
+    let timeout =
+        parse_timeout(&ac_params[0]).map_err(|e| anyhow!("invalid caps-word timeout: {}", e))?;
+    Ok(sref(Action::Custom(CustomAction::CapsWord(timeout))))
+}
+
+/// Parse `(autoshift [<timeout>] <key>)`. Tapping the key outputs it as usual, while holding it for
+/// longer than the timeout outputs it with shift instead.
+fn parse_autoshift(ac_params: &[SExpr], s: &ParsedState) -> Result<&'static KanataAction> {
+    const ERR_STR: &str = "autoshift expects items after it: [<timeout>] <key>";
+    // The timeout is optional. If it is missing, autoshift-timeout from defcfg is used.
+    let (timeout, key) = match ac_params {
+        [SExpr::Atom(key)] => (s.default_autoshift_time, key),
+        [timeout, SExpr::Atom(key)] => (
+            parse_timeout(timeout).map_err(|e| anyhow!("invalid autoshift timeout: {}", e))?,
+            key,
+        ),
+        _ => bail!(ERR_STR),
+    };
+    let kc: KeyCode = str_to_oscode(key)
+        .ok_or_else(|| ParseError::new(key.span, format!("unknown key: \"{}\"", key)))?
+        .into();
+    Ok(sref(Action::HoldTap {
+        config: HoldTapConfig::Default,
+        tap_hold_interval: 0,
+        timeout,
+        tap: sref(k(kc)),
+        hold: sref(Action::MultipleKeyCodes(
+            sref(vec![KeyCode::LShift, kc]).as_ref(),
+        )),
+    }))
+}
+
+/// Parse `(switch (<conditions>) <action> ...)`. The items are pairs of a condition list and an
+/// action. A condition list matches if any of its conditions match, or if it is empty.
+fn parse_switch(ac_params: &[SExpr], s: &mut ParsedState) -> Result<&'static KanataAction> {
+    const ERR_STR: &str =
+        "switch expects pairs of a list of conditions and an action, e.g. (switch (lsft) a () b)";
+    if ac_params.is_empty() {
+        bail!(ERR_STR)
+    }
+    let mut branches = Vec::new();
+    for pair in ac_params.chunks(2) {
+        let (conds, action) = match pair {
+            [conds, action] => (conds, action),
+            _ => bail!(ERR_STR),
+        };
+        let conds = match conds {
+            SExpr::List(l) => l
+                .iter()
+                .map(|c| parse_switch_cond(c, s))
+                .collect::<Result<Vec<_>>>()?,
+            SExpr::Atom(a) => bail!(ParseError::new(
+                a.span,
+                format!("{}. Found atom instead of a condition list: {}", ERR_STR, a)
+            )),
+        };
+        let cond = match conds.len() {
+            0 => SwitchCond::And(&[]),
+            _ => SwitchCond::Or(sref(conds)),
+        };
+        let action = parse_action(action, s)?;
+        let vkey = s.add_virtual_key(action)?;
+        branches.push(SwitchBranch { cond, vkey });
+    }
+    Ok(sref(Action::Custom(CustomAction::Switch {
+        // The coordinate is set when the switch is mapped to a key in a layer.
+        coord: (0, 0),
+        switch: sref(Switch {
+            branches: sref(branches),
+        }),
+    })))
+}
+
+/// Parse `(fork <base-action> <alt-action> (<trigger-keys>) [swallow])`, which activates the
+/// alternative action instead of the base action if any of the trigger keys is physically held.
+/// With swallow, the trigger keys are released in the output while the alternative action is
+/// held. A fork is a switch with two branches.
+fn parse_fork(ac_params: &[SExpr], s: &mut ParsedState) -> Result<&'static KanataAction> {
+    const ERR_STR: &str =
+        "fork expects items after it: <base-action> <alt-action> (<trigger-keys>) [swallow]";
+    let (base, alt, triggers, swallow) = match ac_params {
+        [base, alt, triggers] => (base, alt, triggers, false),
+        [base, alt, triggers, SExpr::Atom(a)] if **a == "swallow" => (base, alt, triggers, true),
+        _ => bail!(ERR_STR),
+    };
+    let triggers: Vec<KeyCode> = with_span(parse_key_list(triggers), triggers.span())?
+        .iter()
+        .map(|k| (*k).into())
+        .collect();
+    if triggers.is_empty() {
+        bail!("{}\nthe list of trigger keys must not be empty", ERR_STR)
+    }
+    let base = parse_action(base, s)?;
+    let mut alt = parse_action(alt, s)?;
+    if swallow {
+        let unmod = Action::Custom(CustomAction::Unmod {
+            keys: &[],
+            mods: sref(triggers.clone()),
+        });
+        alt = match alt {
+            // keyberon reports only one custom action at a time, so the alternative action can't
+            // be handled by kanata as well.
+            Action::Custom(_) => bail!(
+                "{}\nswallow can't be used with an alternative action that is handled by kanata, e.g. a macro",
+                ERR_STR
+            ),
+            _ => sref(Action::MultipleActions(sref(vec![*alt, unmod]))),
+        };
+    }
+    let alt_cond = SwitchCond::Or(sref(
+        triggers
+            .into_iter()
+            .map(SwitchCond::Key)
+            .collect::<Vec<_>>(),
+    ));
+    let branches = vec![
+        SwitchBranch {
+            cond: alt_cond,
+            vkey: s.add_virtual_key(alt)?,
+        },
+        SwitchBranch {
+            cond: SwitchCond::And(&[]),
+            vkey: s.add_virtual_key(base)?,
+        },
+    ];
+    Ok(sref(Action::Custom(CustomAction::Switch {
+        // The coordinate is set when the fork is mapped to a key in a layer.
+        coord: (0, 0),
+        switch: sref(Switch {
+            branches: sref(branches),
+        }),
+    })))
+}
+
+/// Parse a switch condition: a key, `(vkey <name>)`, `(and ...)`, `(or ...)`, or `(not ...)`.
+fn parse_switch_cond(expr: &SExpr, s: &ParsedState) -> Result<SwitchCond> {
+    let l = match expr {
+        SExpr::Atom(a) => {
+            return match str_to_oscode(a) {
+                Some(osc) => Ok(SwitchCond::Key(osc.into())),
+                None => bail!(ParseError::new(
+                    a.span,
+                    format!("Unknown key in switch condition: {}", a)
+                )),
+            }
+        }
+        SExpr::List(l) => l,
+    };
+    let (op, params) = match l.split_first() {
+        Some((SExpr::Atom(op), params)) => (op, params),
+        _ => bail!(ParseError::new(
+            l.span,
+            "switch condition lists must start with one of: and, or, not, vkey"
+        )),
+    };
+    let parse_conds = |params: &[SExpr]| -> Result<&'static [SwitchCond]> {
+        Ok(sref(
+            params
+                .iter()
+                .map(|c| parse_switch_cond(c, s))
+                .collect::<Result<Vec<_>>>()?,
+        ))
+    };
+    Ok(match (op.as_str(), params) {
+        ("and", _) => SwitchCond::And(parse_conds(params)?),
+        ("or", _) => SwitchCond::Or(parse_conds(params)?),
+        ("not", [cond]) => SwitchCond::Not(sref(parse_switch_cond(cond, s)?)),
+        ("vkey", [SExpr::Atom(name)]) => match s.virtual_key_names.get(&name.t) {
+            Some(vkey) => SwitchCond::VirtualKey(*vkey),
+            None => bail!(ParseError::new(
+                name.span,
+                format!(
+                    "Unknown virtual key: {}. Virtual keys must be declared in defvirtualkeys.",
+                    name
+                )
+            )),
+        },
+        ("not", _) => bail!(ParseError::new(l.span, "not expects one condition")),
+        ("vkey", _) => bail!(ParseError::new(
+            l.span,
+            "vkey expects the name of a virtual key"
+        )),
+        _ => bail!(ParseError::new(
+            op.span,
+            format!(
+                "Unknown switch condition: {}. Valid conditions: and, or, not, vkey",
+                op
+            )
+        )),
+    })
+}
+
+/// Parse `(on-press <op> <virtual key>)` or `(on-release <op> <virtual key>)`, where `op` is one
+/// of press-vkey, release-vkey, tap-vkey, or toggle-vkey.
+fn parse_virtual_key_action(
+    ac_params: &[SExpr],
+    s: &ParsedState,
+    ac_type: &str,
+) -> Result<&'static KanataAction> {
+    let (op, name) = match ac_params {
+        [SExpr::Atom(op), SExpr::Atom(name)] => (op, name),
+        _ => bail!(
+            "{} expects two atoms: one of press-vkey, release-vkey, tap-vkey, or toggle-vkey, and the name of a virtual key",
+            ac_type
+        ),
+    };
+    let op = match op.as_str() {
+        "press-vkey" => VirtualKeyOp::Press,
+        "release-vkey" => VirtualKeyOp::Release,
+        "tap-vkey" => VirtualKeyOp::Tap,
+        "toggle-vkey" => VirtualKeyOp::Toggle,
+        _ => bail!(ParseError::new(
+            op.span,
+            format!(
+                "Unknown virtual key operation: {}. Valid operations: press-vkey, release-vkey, tap-vkey, toggle-vkey",
+                op
+            )
+        )),
+    };
+    let vkey = match s.virtual_key_names.get(&name.t) {
+        Some(vkey) => *vkey,
+        None => bail!(ParseError::new(
+            name.span,
+            format!(
+                "Unknown virtual key: {}. Virtual keys must be declared in defvirtualkeys.",
+                name
+            )
+        )),
+    };
+    Ok(sref(Action::Custom(match ac_type {
+        "on-press" => CustomAction::VirtualKeyOnPress(vkey, op),
+        _ => CustomAction::VirtualKeyOnRelease(vkey, op),
+    })))
+}
+
+/// Parse the parameters of `(<ac_type> <program> <args>...)` for the cmd actions. The actions are
+/// only allowed if kanata is built with the cmd feature and the configuration enables them with
+/// danger-enable-cmd.
+fn parse_cmd(ac_params: &[SExpr], s: &ParsedState, ac_type: &str) -> Result<&'static [String]> {
+    if !cfg!(feature = "cmd") {
+        bail!(
+            "{} is not supported by this build of kanata. Build kanata with the cmd feature to use it.",
+            ac_type
+        )
+    }
+    if !s.is_cmd_enabled {
+        bail!(
+            "{} is not enabled. Set danger-enable-cmd to true in defcfg to use it.",
+            ac_type
+        )
+    }
+    let cmd = ac_params
+        .iter()
+        .map(|p| match p {
+            SExpr::Atom(a) => Ok(a.t.clone()),
+            SExpr::List(_) => bail!("{} expects only atoms, found: {:?}", ac_type, p),
+        })
+        .collect::<Result<Vec<_>>>()?;
+    if cmd.is_empty() {
+        bail!(
+            "{} expects a program to run, optionally followed by its arguments",
+            ac_type
+        )
+    }
+    Ok(sref(cmd))
+}
+
+/// Parse overrides from expressions starting with defoverrides, e.g.
+/// `(defoverrides (lsft 2) (lalt f4))`. The items are pairs of a list of input keys and a list of
+/// output keys.
+fn parse_overrides(exprs: &[&Vec<SExpr>]) -> Result<Vec<Override>> {
+    let mut overrides = Vec::new();
+    for expr in exprs {
+        let mut subexprs = match check_first_expr(expr.iter(), "defoverrides") {
+            Ok(s) => s,
+            Err(e) => bail!(e),
+        };
+        while let Some(in_keys) = subexprs.next() {
+            let out_keys = match subexprs.next() {
+                Some(v) => v,
+                None => bail!(ParseError::new(in_keys.span(), "Incorrect number of elements found in defoverrides; they should be pairs of input and output key lists.")),
+            };
+            let in_keys = parse_override_keys(in_keys)?;
+            let out_keys = parse_override_keys(out_keys)?;
+            overrides.push(Override { in_keys, out_keys });
+        }
+    }
+    Ok(overrides)
+}
+
+/// Parse the opposing key pairs from expressions starting with defsocd, e.g.
+/// `(defsocd last-input-priority (a d) (w s))`. The policy is followed by pairs of keys.
+fn parse_socd(exprs: &[&Vec<SExpr>]) -> Result<Vec<SocdPair>> {
+    let mut pairs: Vec<SocdPair> = Vec::new();
+    for expr in exprs {
+        let mut subexprs = match check_first_expr(expr.iter(), "defsocd") {
+            Ok(s) => s,
+            Err(e) => bail!(e),
+        };
+        const POLICY_ERR: &str =
+            "defsocd expects a policy first: last-input-priority, neutral, or first-wins";
+        let policy = match subexprs.next() {
+            Some(SExpr::Atom(p)) => match p.t.as_str() {
+                "last-input-priority" => SocdPolicy::LastInputPriority,
+                "neutral" => SocdPolicy::Neutral,
+                "first-wins" => SocdPolicy::FirstWins,
+                _ => bail!(ParseError::new(p.span, POLICY_ERR)),
+            },
+            Some(p) => bail!(ParseError::new(p.span(), POLICY_ERR)),
+            None => bail!(POLICY_ERR),
+        };
+        for pair_expr in subexprs {
+            let keys = match with_span(parse_key_list(pair_expr), pair_expr.span())? {
+                [a, b] if a != b => [KeyCode::from(*a), KeyCode::from(*b)],
+                _ => bail!(ParseError::new(
+                    pair_expr.span(),
+                    "defsocd pairs must be lists of two different keys"
+                )),
+            };
+            if let Some(k) = keys
+                .iter()
+                .find(|k| pairs.iter().any(|pair| pair.keys.contains(k)))
+            {
+                bail!(ParseError::new(
+                    pair_expr.span(),
+                    format!("defsocd key {:?} is already part of another pair", k)
+                ))
+            }
+            pairs.push(SocdPair { keys, policy });
+        }
+    }
+    Ok(pairs)
+}
+
+/// Parse the compose sequences of characters from expressions starting with defcompose, e.g.
+/// `(defcompose é (' e) ñ (~ n))`. Each character is followed by the characters that are typed
+/// after the compose key to enter it, which must have keys in the text layout.
+#[cfg(target_os = "linux")]
+fn parse_compose(exprs: &[&Vec<SExpr>], layout: TextLayout) -> Result<HashMap<char, Vec<char>>> {
+    const ERR_STR: &str =
+        "defcompose expects pairs of a character and a list of the characters of its compose sequence";
+    let single_char = |expr: &SExpr| -> Result<char> {
+        let atom = match expr {
+            SExpr::Atom(a) => a,
+            SExpr::List(l) => bail!(ParseError::new(l.span, ERR_STR)),
+        };
+        let mut chars = atom.chars();
+        match (chars.next(), chars.next()) {
+            (Some(c), None) => Ok(c),
+            _ => bail!(ParseError::new(
+                atom.span,
+                format!("{}, found {}", ERR_STR, atom.t)
+            )),
+        }
+    };
+    let mut compose = HashMap::new();
+    for expr in exprs {
+        let mut subexprs = match check_first_expr(expr.iter(), "defcompose") {
+            Ok(s) => s,
+            Err(e) => bail!(e),
+        };
+        while let Some(char_expr) = subexprs.next() {
+            let c = single_char(char_expr)?;
+            let seq_expr = match subexprs.next() {
+                Some(SExpr::List(l)) => l,
+                _ => bail!(ParseError::new(char_expr.span(), ERR_STR)),
+            };
+            let mut seq = Vec::new();
+            for item in seq_expr.t.iter() {
+                let seq_char = single_char(item)?;
+                if char_to_oscode(seq_char, layout).is_none() {
+                    bail!(ParseError::new(
+                        item.span(),
+                        format!("{} has no key in text-layout", seq_char)
+                    ))
+                }
+                seq.push(seq_char);
+            }
+            if seq.is_empty() {
+                bail!(ParseError::new(seq_expr.span, ERR_STR))
+            }
+            if compose.insert(c, seq).is_some() {
+                bail!(ParseError::new(
+                    char_expr.span(),
+                    format!("defcompose character {} is already declared", c)
+                ))
+            }
+        }
+    }
+    Ok(compose)
+}
+
+/// Parse the keys of input devices that are treated as other keys from expressions starting with
+/// defdevice, e.g. `(defdevice (Macro Pad) 1 f13 2 f14)`. The devices are given like the value of
+/// linux-dev-exclude and are followed by pairs of the key of the device and the key that it is
+/// treated as.
+#[cfg(target_os = "linux")]
+fn parse_device_keys(exprs: &[&Vec<SExpr>]) -> Result<Vec<DeviceKeys>> {
+    let mut device_keys = Vec::new();
+    for expr in exprs {
+        let mut subexprs = match check_first_expr(expr.iter(), "defdevice") {
+            Ok(s) => s,
+            Err(e) => bail!(e),
+        };
+        const DEVICE_ERR: &str = "defdevice expects the paths or names of the devices first";
+        let devices = match subexprs.next() {
+            Some(SExpr::Atom(a)) => a.t.clone(),
+            Some(SExpr::List(l)) => l
+                .iter()
+                .map(|word| {
+                    get_atom(word).ok_or_else(|| ParseError::new(word.span(), DEVICE_ERR).into())
+                })
+                .collect::<Result<Vec<_>>>()?
+                .join(" "),
+            None => bail!(DEVICE_ERR),
+        };
+        let mut keys = HashMap::new();
+        while let Some(from_expr) = subexprs.next() {
+            let from = parse_key_expr(from_expr, "defdevice")?;
+            let to = match subexprs.next() {
+                Some(to_expr) => parse_key_expr(to_expr, "defdevice")?,
+                None => bail!(ParseError::new(
+                    from_expr.span(),
+                    "defdevice expects pairs of a key of the device and the key that it is treated as"
+                )),
+            };
+            if keys.insert(from, to).is_some() {
+                bail!(ParseError::new(
+                    from_expr.span(),
+                    format!("defdevice key {:?} is already mapped", from)
+                ))
+            }
+        }
+        device_keys.push(DeviceKeys {
+            devices: parse_dev_list(&devices),
+            keys,
+        });
+    }
+    Ok(device_keys)
+}
+
+fn parse_override_keys(expr: &SExpr) -> Result<Vec<KeyCode>> {
+    let keys = with_span(parse_key_list(expr), expr.span())?;
+    if keys.is_empty() {
+        bail!(ParseError::new(
+            expr.span(),
+            "Override key lists must not be empty"
+        ))
+    }
+    Ok(keys.iter().map(|k| (*k).into()).collect())
+}
+
+/// Parse chords from expressions starting with defchords, e.g. `(defchords 50 (j k) esc)`.
+/// Each chord can be followed by its own timeout and release behaviour, e.g.
+/// `(defchords 50 (j k) esc 100 all-released)`.
+fn parse_chords(
+    exprs: &[&Vec<SExpr>],
+    s: &mut ParsedState,
+    mapped_keys: &MappedKeys,
+) -> Result<Vec<ChordDef>> {
+    let mut chords: Vec<ChordDef> = Vec::new();
+    for expr in exprs {
+        let mut subexprs = match check_first_expr(expr.iter(), "defchords") {
+            Ok(s) => s,
+            Err(e) => bail!(e),
+        };
+        let default_timeout = match subexprs.next() {
+            Some(t) => parse_timeout(t).map_err(|e| anyhow!("invalid defchords timeout: {}", e))?,
+            None => bail!("defchords expects a timeout followed by pairs of keys and actions"),
+        };
+        let mut subexprs = subexprs.peekable();
+        while let Some(keys_expr) = subexprs.next() {
+            let action = match subexprs.next() {
+                Some(ac) => parse_action(ac, s)?,
+                None => bail!(
+                    "Incorrect number of elements found in defchords; they should be pairs of keys and actions. Missing action for: {:?}",
+                    keys_expr
+                ),
+            };
+            let mut keys = parse_key_list(keys_expr)
+                .map_err(|e| anyhow!("invalid defchords keys: {}", e))?
+                .iter()
+                .map(|k| {
+                    let code: usize = k.to_remappable().into();
+                    if code >= MAPPED_KEYS_LEN || !mapped_keys[code] {
+                        bail!("chord key {:?} is not declared in defsrc", k)
+                    }
+                    Ok(code as u8)
+                })
+                .collect::<Result<Vec<_>>>()?;
+            keys.sort_unstable();
+            keys.dedup();
+            if keys.len() < 2 {
+                bail!(
+                    "a chord needs at least two different keys, found: {:?}",
+                    keys_expr
+                )
+            }
+            if chords.iter().any(|c| c.keys == keys) {
+                bail!("Duplicate chord: {:?}", keys_expr)
+            }
+
+            // Optional per-chord options are atoms; the keys of the next chord are a list.
+            let mut timeout = default_timeout;
+            let mut release = ChordRelease::FirstRelease;
+            while let Some(SExpr::Atom(opt)) = subexprs.peek() {
+                match opt.as_str() {
+                    "first-release" => release = ChordRelease::FirstRelease,
+                    "all-released" => release = ChordRelease::AllReleased,
+                    _ => {
+                        timeout = opt.parse().map_err(|_| {
+                            anyhow!(
+                                "invalid chord option {}, expected a timeout, first-release, or all-released",
+                                opt
+                            )
+                        })?
+                    }
+                }
+                subexprs.next();
+            }
+
+            chords.push(ChordDef {
+                keys,
+                vkey: s.add_virtual_key(action)?,
+                timeout,
+                release,
+            });
+        }
+    }
+    Ok(chords)
+}
+
+/// Parse sequences from expressions starting with defseq, e.g.
+/// `(defseq (g s t) (macro g i t spc s t a t u s))`.
+fn parse_sequences(
+    exprs: &[&Vec<SExpr>],
+    s: &mut ParsedState,
+    mapped_keys: &MappedKeys,
+) -> Result<Vec<SequenceDef>> {
+    let mut seqs: Vec<SequenceDef> = Vec::new();
+    for expr in exprs {
+        let mut subexprs = match check_first_expr(expr.iter(), "defseq") {
+            Ok(s) => s,
+            Err(e) => bail!(e),
+        };
+        while let Some(keys_expr) = subexprs.next() {
+            let action = match subexprs.next() {
+                Some(ac) => parse_action(ac, s)?,
+                None => bail!(
+                    "Incorrect number of elements found in defseq; they should be pairs of keys and actions. Missing action for: {:?}",
+                    keys_expr
+                ),
+            };
+            let keys = parse_sequence_keys(keys_expr, mapped_keys)
+                .map_err(|e| anyhow!("invalid defseq keys: {}", e))?;
+            if keys.is_empty() {
+                bail!("a sequence needs at least one key")
+            }
+            if let Some(other) = seqs
+                .iter()
+                .find(|other| other.keys.starts_with(&keys) || keys.starts_with(&other.keys))
+            {
+                // Sequences with wildcards or ranges may still overlap; the first complete
+                // sequence wins.
+                bail!(
+                    "sequence {:?} conflicts with another sequence: one of them starts with the other. Keys: {:?}",
+                    keys_expr,
+                    other.keys
+                )
+            }
+            let mut backspaces = Vec::new();
+            for _ in 0..keys.len() {
+                backspaces.push(MacroEvent::Press(KeyCode::BSpace));
+                backspaces.push(MacroEvent::Release(KeyCode::BSpace));
+            }
+            seqs.push(SequenceDef {
+                keys,
+                vkey: s.add_virtual_key(action)?,
+                backspaces: sref(Macro {
+                    events: sref(backspaces),
+                    cancel_on_press: false,
+                    repeat: MacroRepeat::Times(1),
+                }),
+            });
+        }
+    }
+    Ok(seqs)
+}
+
+/// Parse the chords and texts of multiple exprs starting with defzippy or defzippy-suffixes. The
+/// text is an atom or a list of atoms that are joined with spaces.
+fn parse_zippy(
+    exprs: &[&Vec<SExpr>],
+    section: &str,
+    mapped_keys: &MappedKeys,
+) -> Result<HashMap<Vec<u8>, String>> {
+    let mut dict = HashMap::new();
+    for expr in exprs {
+        let mut subexprs = match check_first_expr(expr.iter(), section) {
+            Ok(s) => s,
+            Err(e) => bail!(e),
+        };
+        while let Some(keys_expr) = subexprs.next() {
+            let text = match subexprs.next() {
+                Some(SExpr::Atom(a)) => a.t.clone(),
+                Some(SExpr::List(l)) => l
+                    .iter()
+                    .map(|word| {
+                        get_atom(word).ok_or_else(|| {
+                            ParseError::new(word.span(), "expected only words in the text").into()
+                        })
+                    })
+                    .collect::<Result<Vec<_>>>()?
+                    .join(" "),
+                None => bail!(ParseError::new(
+                    keys_expr.span(),
+                    format!("Incorrect number of elements found in {}; they should be pairs of keys and texts. Missing text for: {:?}", section, keys_expr)
+                )),
+            };
+            let mut keys = parse_key_list(keys_expr)
+                .map_err(|e| anyhow!("invalid {} keys: {}", section, e))?
+                .iter()
+                .map(|k| {
+                    let code: usize = k.to_remappable().into();
+                    if code >= MAPPED_KEYS_LEN || !mapped_keys[code] {
+                        bail!("{} key {:?} is not declared in defsrc", section, k)
+                    }
+                    Ok(code as u8)
+                })
+                .collect::<Result<Vec<_>>>()?;
+            keys.sort_unstable();
+            keys.dedup();
+            if keys.len() < 2 {
+                bail!(ParseError::new(
+                    keys_expr.span(),
+                    "a chord needs at least two different keys"
+                ))
+            }
+            if dict.insert(keys, text).is_some() {
+                bail!(ParseError::new(
+                    keys_expr.span(),
+                    format!("Duplicate chord in {}: {:?}", section, keys_expr)
+                ))
+            }
+        }
+    }
+    Ok(dict)
+}
+
+/// Parse the keys of a sequence. Besides keys, a sequence may contain the wildcard `*`, which
+/// matches any key, and ranges like `0-9` or `a-f`, which match any digit or letter in the range.
+fn parse_sequence_keys(expr: &SExpr, mapped_keys: &MappedKeys) -> Result<Vec<SequenceToken>> {
+    let items = match expr {
+        SExpr::List(l) => l,
+        SExpr::Atom(_) => bail!("expected a list of keys, found atom: {:?}", expr),
+    };
+    let mapped_coord = |k: OsCode| -> Result<u8> {
+        let code: usize = k.to_remappable().into();
+        if code >= MAPPED_KEYS_LEN || !mapped_keys[code] {
+            bail!("sequence key {:?} is not declared in defsrc", k)
+        }
+        Ok(code as u8)
+    };
+    let mut tokens = Vec::new();
+    for item in items.iter() {
+        let a = match item {
+            SExpr::Atom(a) => a,
+            SExpr::List(_) => bail!(ParseError::new(
+                item.span(),
+                format!("expected only keys in the list, found: {:?}", item)
+            )),
+        };
+        if **a == "*" {
+            tokens.push(SequenceToken::Any);
+            continue;
+        }
+        if let Some(oscode) = str_to_oscode(a) {
+            tokens.push(SequenceToken::Key(mapped_coord(oscode)?));
+            continue;
+        }
+        let chars: Vec<char> = a.chars().collect();
+        match chars[..] {
+            [lo, '-', hi]
+                if lo <= hi
+                    && (lo.is_ascii_digit() && hi.is_ascii_digit()
+                        || lo.is_ascii_lowercase() && hi.is_ascii_lowercase()) =>
+            {
+                let coords = (lo..=hi)
+                    .map(|c| {
+                        let oscode = str_to_oscode(&c.to_string())
+                            .ok_or_else(|| anyhow!("unknown key in range: \"{}\"", c))?;
+                        mapped_coord(oscode)
+                    })
+                    .collect::<Result<Vec<_>>>()?;
+                tokens.push(SequenceToken::OneOf(coords));
+            }
+            _ => bail!("unknown key in list: \"{}\"", a),
+        }
+    }
+    Ok(tokens)
+}
+
+/// Returns true if the layer is defined with deflayermap instead of deflayer.
+fn is_layer_map(layer: &[SExpr]) -> bool {
+    matches!(layer.first(), Some(SExpr::Atom(a)) if **a == "deflayermap")
+}
+
+/// Parse the actions of a deflayermap layer, in the order of the defsrc keys. Each item of the
+/// layer is an `(input-key action)` pair. The action of the optional `(default action)` item is
+/// used for the keys that are not listed and is transparent if the item is missing.
+fn parse_layer_map(
+    layer: &[SExpr],
+    s: &mut ParsedState,
+    mapping_order: &[usize],
+) -> Result<Vec<&'static KanataAction>> {
+    const ERR_STR: &str = "deflayermap items must be (input-key action) pairs";
+    let mut default = None;
+    let mut actions = vec![None; mapping_order.len()];
+    // skip deflayermap and name
+    for item in layer.iter().skip(2) {
+        let (key, ac) = match item {
+            SExpr::List(l) => match &l[..] {
+                [key, ac] => (key, ac),
+                _ => bail!(ParseError::new(item.span(), ERR_STR)),
+            },
+            SExpr::Atom(_) => bail!(ParseError::new(item.span(), ERR_STR)),
+        };
+        let action = parse_action(ac, s)?;
+        let slot = match key {
+            SExpr::Atom(a) if **a == "default" => &mut default,
+            _ => {
+                let oscode: usize = parse_key_expr(key, "deflayermap")?.to_remappable().into();
+                let i = mapping_order
+                    .iter()
+                    .position(|c| *c == oscode)
+                    .ok_or_else(|| ParseError::new(key.span(), "key is not declared in defsrc"))?;
+                &mut actions[i]
+            }
+        };
+        if slot.is_some() {
+            bail!(ParseError::new(
+                key.span(),
+                "key is mapped more than once in this layer"
+            ))
+        }
+        *slot = Some(action);
+    }
+    let default = default.unwrap_or(&Action::Trans);
+    Ok(actions
+        .into_iter()
+        .map(|ac| ac.unwrap_or(default))
+        .collect())
+}
+
+/// Mutates `layers::LAYERS` using the inputs.
+fn parse_layers(
+    layers: &[&Vec<SExpr>],
+    s: &mut ParsedState,
+    mapping_order: &[usize],
+    errs: &mut CfgErrors,
+) -> KanataLayers {
+    let mut layers_cfg = new_layers();
+    for (layer_level, layer) in layers.iter().enumerate() {
+        let actions = if is_layer_map(layer) {
+            match errs.check(parse_layer_map(layer, s, mapping_order)) {
+                Some(actions) => actions,
+                None => continue,
+            }
+        } else {
+            // skip deflayer and name
+            layer
+                .iter()
+                .skip(2)
+                .map(|ac| errs.check(parse_action(ac, s)).unwrap_or(&Action::NoOp))
+                .collect::<Vec<_>>()
+        };
+        for (i, ac) in actions.into_iter().enumerate() {
+            let ac = match ac {
+                // keyberon resolves transparent keys to the key of the base layer. On the first
+                // layer, which is the initial base layer, there is nothing to fall through to, so
+                // a transparent key is the defsrc key itself.
+                Action::Trans if layer_level == 0 => {
+                    oscode_action(OsCode::from(mapping_order[i] as u32))
+                }
+                ac => ac,
+            };
+            let ac = set_action_coord(ac, (0, mapping_order[i] as u8));
             layers_cfg[layer_level][0][mapping_order[i]] = *ac;
         }
     }
-    Ok(layers_cfg)
+    // The virtual keys are the same on every layer so that activating them does not depend on
+    // the active layer.
+    let virtual_keys = virtual_key_actions(s);
+    for layer in layers_cfg.iter_mut() {
+        for (i, ac) in virtual_keys.iter().enumerate() {
+            layer[usize::from(VIRTUAL_ROW)][i] = **ac;
+        }
+    }
+    layers_cfg
 }
 
 /// Creates a `KeyOutputs` from `layers::LAYERS`.
@@ -741,32 +3773,82 @@ fn create_key_outputs(layers: &KanataLayers) -> KeyOutputs {
         None,
     ];
     for layer in layers.iter() {
+        let virtual_keys = &layer[usize::from(VIRTUAL_ROW)];
         for (i, action) in layer[0].iter().enumerate() {
-            match action {
-                Action::KeyCode(kc) => {
-                    add_kc_output(i, kc.into(), &mut outs);
-                }
-                Action::HoldTap {
-                    tap,
-                    hold,
-                    timeout: _,
-                    config: _,
-                    tap_hold_interval: _,
-                } => {
-                    if let Action::KeyCode(kc) = tap {
-                        add_kc_output(i, kc.into(), &mut outs);
-                    }
-                    if let Action::KeyCode(kc) = hold {
-                        add_kc_output(i, kc.into(), &mut outs);
-                    }
-                }
-                _ => {} // do nothing for other types
-            };
+            add_action_outputs(i, action, virtual_keys, &mut outs);
         }
     }
     outs
 }
 
+/// Adds the key outputs of an action at the key position `i` to `outs`.
+fn add_action_outputs(
+    i: usize,
+    action: &KanataAction,
+    virtual_keys: &[KanataAction],
+    outs: &mut KeyOutputs,
+) {
+    match action {
+        Action::KeyCode(kc) => {
+            add_kc_output(i, kc.into(), outs);
+        }
+        Action::HoldTap {
+            tap,
+            hold,
+            timeout: _,
+            config: _,
+            tap_hold_interval: _,
+        } => {
+            if let Action::KeyCode(kc) = tap {
+                add_kc_output(i, kc.into(), outs);
+            }
+            if let Action::KeyCode(kc) = hold {
+                add_kc_output(i, kc.into(), outs);
+            }
+        }
+        // Tap-hold actions with except-keys are wrapped in multiple actions.
+        Action::MultipleActions(actions) => {
+            for action in actions.iter() {
+                if let Action::HoldTap { .. } = action {
+                    add_action_outputs(i, action, virtual_keys, outs);
+                }
+            }
+        }
+        Action::Custom(CustomAction::TapDance { td, .. }) => {
+            for vkey in td.actions.iter() {
+                add_action_outputs(i, &virtual_keys[usize::from(*vkey)], virtual_keys, outs);
+            }
+        }
+        Action::Custom(CustomAction::OneShot { vkey, .. }) => {
+            add_action_outputs(i, &virtual_keys[usize::from(*vkey)], virtual_keys, outs);
+        }
+        Action::Custom(CustomAction::Switch { switch, .. }) => {
+            for branch in switch.branches.iter() {
+                add_action_outputs(
+                    i,
+                    &virtual_keys[usize::from(branch.vkey)],
+                    virtual_keys,
+                    outs,
+                );
+            }
+        }
+        Action::Custom(CustomAction::Turbo(turbo)) => {
+            add_action_outputs(
+                i,
+                &virtual_keys[usize::from(turbo.vkey)],
+                virtual_keys,
+                outs,
+            );
+        }
+        Action::Custom(CustomAction::Timeout { timeout, .. }) => {
+            for vkey in [timeout.interrupted, timeout.timed_out] {
+                add_action_outputs(i, &virtual_keys[usize::from(vkey)], virtual_keys, outs);
+            }
+        }
+        _ => {} // do nothing for other types
+    };
+}
+
 /// Create a layout from `layers::LAYERS`.
 fn create_layout(layers: KanataLayers) -> KanataLayout {
     // LAYERS is permanently locked after this.
@@ -0,0 +1,285 @@
+//! Chords: combinations of physical keys that are pressed together to activate an action.
+//!
+//! Key events of the keys that participate in a chord are buffered before they reach the keyberon
+//! layout. If the buffered keys match a chord, the chord's action is activated through its
+//! virtual key. Otherwise the buffered key presses are sent to the layout as if they were never
+//! buffered.
+
+use std::collections::HashSet;
+
+use kanata_keyberon::layout::Event;
+
+use crate::cfg::KanataLayout;
+use crate::layers::VIRTUAL_ROW;
+
+/// Receiver of the key events that come out of the chord buffer: the keyberon layout.
+pub trait EventSink {
+    fn event(&mut self, event: Event);
+}
+
+impl EventSink for KanataLayout {
+    fn event(&mut self, event: Event) {
+        KanataLayout::event(self, event)
+    }
+}
+
+/// A combination of physical keys mapped to an action.
+#[derive(Debug)]
+pub struct ChordDef {
+    /// Coordinates of the participating keys, sorted.
+    pub keys: Vec<u8>,
+    /// Virtual key of the action of the chord.
+    pub vkey: u8,
+    /// Maximum number of milliseconds between the first and the last key press of the chord.
+    pub timeout: u16,
+    pub release: ChordRelease,
+}
+
+/// When the action of an activated chord is released.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChordRelease {
+    /// Release the action when any of the chord's keys is released.
+    FirstRelease,
+    /// Release the action when all of the chord's keys are released.
+    AllReleased,
+}
+
+/// A chord that has been activated and whose keys are not all released yet.
+struct ActiveChord {
+    vkey: u8,
+    release: ChordRelease,
+    /// Participating keys that are still pressed.
+    pressed: Vec<u8>,
+    vkey_released: bool,
+}
+
+/// The chords of the configuration and the state of the key presses that may become a chord.
+#[derive(Default)]
+pub struct Chords {
+    defs: Vec<ChordDef>,
+    /// All keys that participate in at least one chord.
+    participants: HashSet<u8>,
+    /// Buffered key presses in the order that they happened.
+    pending: Vec<u8>,
+    ticks_since_first_press: u16,
+    active: Vec<ActiveChord>,
+}
+
+impl Chords {
+    pub fn new(defs: Vec<ChordDef>) -> Self {
+        let participants = defs.iter().flat_map(|d| d.keys.iter().copied()).collect();
+        Self {
+            defs,
+            participants,
+            ..Default::default()
+        }
+    }
+
+    /// Handle a key press. Returns true if the press was consumed; otherwise the press should be
+    /// sent to the layout by the caller.
+    pub fn press(&mut self, coord: u8, layout: &mut impl EventSink) -> bool {
+        if !self.participants.contains(&coord) {
+            self.flush(layout);
+            return false;
+        }
+        if self.pending.contains(&coord) {
+            // Should not happen without a release in between, but be safe.
+            self.flush(layout);
+            return false;
+        }
+        if self.pending.is_empty() {
+            self.ticks_since_first_press = 0;
+        }
+        self.pending.push(coord);
+        let candidates: Vec<&ChordDef> = self.candidates().collect();
+        match candidates[..] {
+            [] => self.flush(layout),
+            [def] if def.keys.len() == self.pending.len() => {
+                let (vkey, release) = (def.vkey, def.release);
+                self.activate(vkey, release, layout);
+            }
+            _ => {}
+        }
+        true
+    }
+
+    /// Handle a key release. Returns true if the release was consumed; otherwise the release
+    /// should be sent to the layout by the caller.
+    pub fn release(&mut self, coord: u8, layout: &mut impl EventSink) -> bool {
+        if self.pending.contains(&coord) {
+            self.resolve(layout);
+        }
+        let mut consumed = false;
+        for chord in self.active.iter_mut() {
+            if !chord.pressed.contains(&coord) {
+                continue;
+            }
+            consumed = true;
+            chord.pressed.retain(|c| *c != coord);
+            let release_vkey = match chord.release {
+                ChordRelease::FirstRelease => true,
+                ChordRelease::AllReleased => chord.pressed.is_empty(),
+            };
+            if release_vkey && !chord.vkey_released {
+                layout.event(Event::Release(VIRTUAL_ROW, chord.vkey));
+                chord.vkey_released = true;
+            }
+        }
+        self.active.retain(|chord| !chord.pressed.is_empty());
+        consumed
+    }
+
+    /// Advance the state by one millisecond.
+    pub fn tick(&mut self, layout: &mut impl EventSink) {
+        if self.pending.is_empty() {
+            return;
+        }
+        self.ticks_since_first_press = self.ticks_since_first_press.saturating_add(1);
+        let timeout = self.candidates().map(|d| d.timeout).max().unwrap_or(0);
+        if self.ticks_since_first_press >= timeout {
+            self.resolve(layout);
+        }
+    }
+
+    /// Chords that contain all of the pending keys.
+    fn candidates(&self) -> impl Iterator<Item = &ChordDef> {
+        let pending = &self.pending;
+        self.defs
+            .iter()
+            .filter(move |d| pending.iter().all(|c| d.keys.contains(c)))
+    }
+
+    /// Activate the chord matching the pending keys exactly, if one exists. Otherwise send the
+    /// pending key presses to the layout.
+    fn resolve(&mut self, layout: &mut impl EventSink) {
+        let chord = self
+            .candidates()
+            .find(|d| d.keys.len() == self.pending.len())
+            .map(|d| (d.vkey, d.release));
+        match chord {
+            Some((vkey, release)) => self.activate(vkey, release, layout),
+            None => self.flush(layout),
+        }
+    }
+
+    fn activate(&mut self, vkey: u8, release: ChordRelease, layout: &mut impl EventSink) {
+        log::debug!("chord     {:?}", self.pending);
+        layout.event(Event::Press(VIRTUAL_ROW, vkey));
+        self.active.push(ActiveChord {
+            vkey,
+            release,
+            pressed: std::mem::take(&mut self.pending),
+            vkey_released: false,
+        });
+    }
+
+    /// Send the pending key presses to the layout.
+    fn flush(&mut self, layout: &mut impl EventSink) {
+        for coord in self.pending.drain(..) {
+            layout.event(Event::Press(0, coord));
+        }
+    }
+}
+
+#[cfg(test)]
+impl EventSink for Vec<Event> {
+    fn event(&mut self, event: Event) {
+        self.push(event)
+    }
+}
+
+#[cfg(test)]
+fn test_chords() -> Chords {
+    Chords::new(vec![
+        ChordDef {
+            keys: vec![1, 2],
+            vkey: 0,
+            timeout: 50,
+            release: ChordRelease::FirstRelease,
+        },
+        ChordDef {
+            keys: vec![1, 2, 3],
+            vkey: 1,
+            timeout: 100,
+            release: ChordRelease::AllReleased,
+        },
+    ])
+}
+
+#[test]
+fn chord_keys_are_buffered_until_the_chord_is_complete() {
+    let mut chords = test_chords();
+    let mut events = Vec::new();
+    assert!(chords.press(1, &mut events));
+    assert!(chords.press(2, &mut events));
+    assert!(events.is_empty());
+    assert!(chords.press(3, &mut events));
+    assert_eq!(events, vec![Event::Press(VIRTUAL_ROW, 1)]);
+}
+
+#[test]
+fn chord_is_activated_on_timeout() {
+    let mut chords = test_chords();
+    let mut events = Vec::new();
+    chords.press(2, &mut events);
+    chords.press(1, &mut events);
+    // [1 2 3] is still possible, so its timeout applies.
+    for _ in 0..99 {
+        chords.tick(&mut events);
+    }
+    assert!(events.is_empty());
+    chords.tick(&mut events);
+    assert_eq!(events, vec![Event::Press(VIRTUAL_ROW, 0)]);
+}
+
+#[test]
+fn incomplete_chord_is_flushed_on_timeout() {
+    let mut chords = test_chords();
+    let mut events = Vec::new();
+    chords.press(3, &mut events);
+    for _ in 0..100 {
+        chords.tick(&mut events);
+    }
+    assert_eq!(events, vec![Event::Press(0, 3)]);
+}
+
+#[test]
+fn other_key_flushes_the_buffer() {
+    let mut chords = test_chords();
+    let mut events = Vec::new();
+    chords.press(1, &mut events);
+    assert!(!chords.press(4, &mut events));
+    assert_eq!(events, vec![Event::Press(0, 1)]);
+    assert!(!chords.release(1, &mut events));
+}
+
+#[test]
+fn release_resolves_the_buffer() {
+    let mut chords = test_chords();
+    let mut events = Vec::new();
+    chords.press(1, &mut events);
+    chords.press(2, &mut events);
+    assert!(chords.release(2, &mut events));
+    assert_eq!(
+        events,
+        vec![Event::Press(VIRTUAL_ROW, 0), Event::Release(VIRTUAL_ROW, 0)]
+    );
+    // The other key of the chord is consumed too.
+    assert!(chords.release(1, &mut events));
+    assert_eq!(events.len(), 2);
+}
+
+#[test]
+fn all_released_chord_is_released_with_the_last_key() {
+    let mut chords = test_chords();
+    let mut events = Vec::new();
+    for key in [3, 1, 2] {
+        chords.press(key, &mut events);
+    }
+    events.clear();
+    assert!(chords.release(1, &mut events));
+    assert!(chords.release(3, &mut events));
+    assert!(events.is_empty());
+    assert!(chords.release(2, &mut events));
+    assert_eq!(events, vec![Event::Release(VIRTUAL_ROW, 1)]);
+}
@@ -1,13 +1,298 @@
+use crate::keys::OsCode;
+use kanata_keyberon::key_code::KeyCode;
+
+/// Row and column of a key in the layout. Row 0 has the physical keys and `VIRTUAL_ROW` has the
+/// virtual keys, so every key that an action is mapped to has its own coordinate.
+pub type KeyCoord = (u8, u8);
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum CustomAction {
     Unicode(char),
-    Mouse(Btn),
+    /// Press an OS key code that has no equivalent keyberon key code.
+    RawKey(OsCode),
+    /// Scroll the mouse wheel by one notch. `reverse` overrides whether the direction is reversed,
+    /// which otherwise follows reverse-scroll in defcfg.
+    MWheel {
+        direction: MWheelDirection,
+        reverse: Option<bool>,
+    },
+    /// Move the mouse pointer while the action is held.
+    MoveMouse(MoveDirection),
+    /// Move the mouse pointer to a position on the screen. The coordinates range from 0 at the top
+    /// or left edge to 65535 at the bottom or right edge.
+    SetMouse {
+        x: u16,
+        y: u16,
+    },
+    /// Multiply the speed of the mouse pointer movement by the percentage while the action is
+    /// held.
+    MoveMouseSpeed(u16),
+    /// Turn on moving the mouse pointer slightly once every given number of seconds, which keeps
+    /// the session from going idle, or turn it off.
+    MouseJiggler(u16),
     LiveReload,
+    /// Emitted together with a tap-hold action that has except-keys. `coord` is the position of
+    /// the tap-hold key and `timeout` is its hold timeout.
+    TapHoldExcept {
+        coord: KeyCoord,
+        timeout: u16,
+        keys: &'static [OsCode],
+    },
+    /// A tap-dance key mapped at `coord`.
+    TapDance {
+        coord: KeyCoord,
+        td: &'static TapDance,
+    },
+    /// A one-shot key mapped at `coord`. `vkey` is the virtual key of the action that is held
+    /// until the next key press is released or until the timeout expires.
+    OneShot {
+        coord: KeyCoord,
+        vkey: u8,
+        cfg: OneShotConfig,
+    },
+    /// Hold the key until the action is pressed again.
+    KeyLock(KeyCode),
+    /// Hold the keys while the action is held, with the modifiers released in the meantime.
+    Unmod {
+        keys: &'static [KeyCode],
+        mods: &'static [KeyCode],
+    },
+    /// Play back a sequence of key events. The events are scheduled by kanata over multiple ticks.
+    Macro(&'static Macro),
+    /// Start recording the output keys into the dynamic macro with this number, or stop the
+    /// recording if one is in progress.
+    DynamicMacroRecord(u16),
+    /// Stop recording a dynamic macro.
+    DynamicMacroRecordStop,
+    /// Play back the dynamic macro with this number.
+    DynamicMacroPlay(u16),
+    /// Output the most recent keys or macro again while the action is held.
+    Repeat,
+    /// Turn bounce-keys on or off.
+    BounceKeysToggle,
+    /// Start entering a sequence.
+    SequenceLeader,
+    /// Change the base layer.
+    LayerSwitch(usize),
+    /// Turn the layer on by making it the base layer, or turn it off by restoring the base layer.
+    LayerToggle(usize),
+    /// Lock the active layer-while-held layer on, or unlock a locked or toggled layer.
+    LayerLock,
+    /// Shift the letters that are typed until a key that is not part of a word is pressed, or until
+    /// no key is pressed for the timeout in milliseconds.
+    CapsWord(u16),
+    /// Run a program. The first item is the program and the rest are its arguments.
+    Cmd(&'static [String]),
+    /// Run a program and type its output.
+    CmdOutputKeys(&'static [String]),
+    /// A switch mapped at `coord`. The action of the first branch whose condition matches is
+    /// activated when the switch is pressed.
+    Switch {
+        coord: KeyCoord,
+        switch: &'static Switch,
+    },
+    /// A timeout action mapped at `coord`, which activates one of two actions depending on
+    /// whether another key is pressed before the timeout expires.
+    Timeout {
+        coord: KeyCoord,
+        timeout: &'static Timeout,
+    },
+    /// Tap an action repeatedly while the turbo action is held.
+    Turbo(&'static Turbo),
+    /// Mirror the physical keys horizontally while the action is held.
+    Mirror(&'static Mirror),
+    /// Change the state of a virtual key declared in defvirtualkeys when the action is pressed.
+    VirtualKeyOnPress(u8, VirtualKeyOp),
+    /// Change the state of a virtual key declared in defvirtualkeys when the action is released.
+    VirtualKeyOnRelease(u8, VirtualKeyOp),
+    /// Move an axis of the virtual gamepad to the value while the action is held.
+    GamepadAxis {
+        axis: GamepadAxis,
+        value: i32,
+    },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Macro {
+    pub events: &'static [MacroEvent],
+    /// Whether a physical key press cancels the remaining events of the macro.
+    pub cancel_on_press: bool,
+    pub repeat: MacroRepeat,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum MacroRepeat {
+    /// Play back the events the given number of times.
+    Times(u16),
+    /// Play back the events repeatedly while the key is held.
+    WhileHeld,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
-pub enum Btn {
+pub enum MacroEvent {
+    Press(KeyCode),
+    Release(KeyCode),
+    /// Wait for the given number of milliseconds.
+    Delay(u16),
+    /// Press the key typed for the wildcard or range with this index in the last matched sequence.
+    PressMatched(u8),
+    /// Release the key typed for the wildcard or range with this index in the last matched
+    /// sequence.
+    ReleaseMatched(u8),
+}
+
+/// The action activated by a tap-dance depends on the number of times it was tapped within the
+/// timeout of the previous tap.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct TapDance {
+    pub timeout: u16,
+    pub config: TapDanceConfig,
+    /// Indexes of the actions in the virtual row of the layout, in the order of the tap count.
+    pub actions: &'static [u8],
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum MWheelDirection {
+    Up,
+    Down,
     Left,
     Right,
-    Mid,
+}
+
+impl MWheelDirection {
+    /// Returns the opposite direction.
+    pub fn reversed(self) -> Self {
+        match self {
+            Self::Up => Self::Down,
+            Self::Down => Self::Up,
+            Self::Left => Self::Right,
+            Self::Right => Self::Left,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum MoveDirection {
+    Up,
+    Down,
+    Left,
+    Right,
+}
+
+/// An axis of the virtual gamepad.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum GamepadAxis {
+    LeftX,
+    LeftY,
+    RightX,
+    RightY,
+    LeftTrigger,
+    RightTrigger,
+}
+
+impl GamepadAxis {
+    /// Returns the minimum and maximum value of the axis. The axis rests at 0.
+    pub fn range(self) -> (i32, i32) {
+        match self {
+            Self::LeftTrigger | Self::RightTrigger => (0, 255),
+            _ => (-32768, 32767),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TapDanceConfig {
+    /// Activate a single action once the dance has ended.
+    Lazy,
+    /// Activate the action for the current tap count on every tap.
+    Eager,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Switch {
+    pub branches: &'static [SwitchBranch],
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct SwitchBranch {
+    pub cond: SwitchCond,
+    /// Index of the action of the branch in the virtual row of the layout.
+    pub vkey: u8,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Timeout {
+    pub timeout: u16,
+    /// Virtual key of the action that is activated if another key is pressed within the timeout.
+    pub interrupted: u8,
+    /// Virtual key of the action that is activated if the timeout expires first.
+    pub timed_out: u8,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Turbo {
+    /// Milliseconds between two taps.
+    pub interval: u16,
+    /// Virtual key of the action that is tapped.
+    pub vkey: u8,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Mirror {
+    /// Pairs of a physical key and the physical key that it is mirrored to.
+    pub pairs: &'static [(u8, u8)],
+}
+
+impl Mirror {
+    /// Returns the physical key that the key at `coord` is mirrored to, if any.
+    pub fn mirrored(&self, coord: u8) -> Option<u8> {
+        self.pairs
+            .iter()
+            .find(|(from, _)| *from == coord)
+            .map(|(_, to)| *to)
+    }
+}
+
+/// A condition on the keys that are held when a switch is pressed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SwitchCond {
+    /// The physical key is held.
+    Key(KeyCode),
+    /// The virtual key declared in defvirtualkeys is pressed.
+    VirtualKey(u8),
+    And(&'static [SwitchCond]),
+    Or(&'static [SwitchCond]),
+    Not(&'static SwitchCond),
+}
+
+/// How to change the state of a virtual key.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum VirtualKeyOp {
+    Press,
+    Release,
+    /// Press and then release the key.
+    Tap,
+    /// Press the key if it is released, otherwise release it.
+    Toggle,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct OneShotConfig {
+    pub timeout: u16,
+    /// Whether the one-shot can be active together with other one-shots, e.g. to apply both shift
+    /// and ctrl to the next key.
+    pub stack: bool,
+    /// Whether pressing the one-shot again while it is active locks it until it is pressed once
+    /// more. Otherwise pressing it again cancels it.
+    pub double_tap_lock: bool,
+}
+
+impl Default for OneShotConfig {
+    fn default() -> Self {
+        Self {
+            timeout: 500,
+            stack: true,
+            double_tap_lock: false,
+        }
+    }
 }
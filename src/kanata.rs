@@ -1,10 +1,10 @@
 //! Implements the glue between OS input/output and keyberon state management.
 
 use anyhow::{bail, Result};
-use log::{error, info};
+use log::info;
 
 use crossbeam_channel::{Receiver, Sender, TryRecvError};
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet, VecDeque};
 use std::convert::TryFrom;
 use std::path::PathBuf;
 use std::time;
@@ -13,62 +13,514 @@ use parking_lot::Mutex;
 use std::sync::Arc;
 
 use crate::cfg;
+use crate::chords::Chords;
 use crate::custom_action::*;
 use crate::keys::*;
+use crate::layers::VIRTUAL_ROW;
 use crate::oskbd::*;
+use crate::overrides::Overrides;
+use crate::sequences::*;
+use crate::socd::Socd;
+use crate::zippy::Zippy;
 
 use kanata_keyberon::key_code::*;
 use kanata_keyberon::layout::*;
 
+#[cfg(target_os = "linux")]
+use evdev_rs::enums::{EventCode, EventType, EV_KEY};
+
 pub struct Kanata {
-    pub kbd_in_path: PathBuf,
+    pub kbd_in_paths: Vec<PathBuf>,
+    /// The input devices to read, which are looked up again when devices are plugged in.
+    #[cfg(target_os = "linux")]
+    pub kbd_in_selection: DeviceSelection,
+    /// Keys of input devices that are treated as other keys.
+    #[cfg(target_os = "linux")]
+    device_keys: Vec<cfg::DeviceKeys>,
+    /// What happens to the events of the input devices that are not key events.
+    #[cfg(target_os = "linux")]
+    event_policies: cfg::EventPolicies,
+    /// Hardware ID patterns of the keyboards that are remapped when the Interception driver is
+    /// used. The keys of other keyboards are passed through.
+    #[cfg(target_os = "windows")]
+    interception_hwids: Vec<String>,
     pub kbd_out: KbdOut,
     pub cfg_path: PathBuf,
     pub mapped_keys: [bool; cfg::MAPPED_KEYS_LEN],
     pub key_outputs: cfg::KeyOutputs,
     pub layout: cfg::KanataLayout,
+    layer_names: Vec<String>,
+    /// The active layer as of the last tick, to notice layer changes.
+    prev_layer: usize,
+    /// Reload the configuration once no keys are held.
+    live_reload_requested: bool,
+    /// Wakes up the GUI thread to update the tray icon when the active layer changes.
+    #[cfg(target_os = "windows")]
+    pub layer_change_notice: Option<native_windows_gui::NoticeSender>,
+    /// Port of the TCP server from defcfg. The server is started once, so a live reload doesn't
+    /// change it.
+    pub tcp_server_port: Option<u16>,
+    /// Sends the name of the active layer to the TCP server when the layer changes.
+    pub layer_change_tx: Option<Sender<String>>,
+    /// Output of the programs of cmd-output-keys, which run in other threads, to be typed.
+    cmd_output_tx: Sender<String>,
+    cmd_output_rx: Receiver<String>,
+    pub chords: Chords,
+    pub sequences: Sequences,
+    pub overrides: Overrides,
+    pub socd: Socd,
+    pub zippy: Zippy,
+    /// Virtual key of the matched sequence, activated once the active macros have finished.
+    sequence_action: Option<u8>,
+    /// Keys typed for the wildcards and ranges of the last matched sequence.
+    sequence_matched_keys: Vec<KeyCode>,
     pub prev_keys: Vec<KeyCode>,
     last_tick: time::Instant,
+    tap_hold_except: Option<TapHoldExceptState>,
+    /// Physical keys whose next release is not sent to the layout, e.g. because the layout already
+    /// received the release or never received the press.
+    ignored_releases: HashSet<u8>,
+    tap_dance: Option<TapDanceState>,
+    timeout: Option<TimeoutState>,
+    /// Held turbo actions and the number of milliseconds until their next tap.
+    turbos: Vec<(&'static Turbo, u16)>,
+    /// Virtual keys that are held by kanata until the key of the action that pressed them is
+    /// released, as pairs of `(coord of the action, virtual coord)`.
+    held_virtual_keys: Vec<(KeyCoord, u8)>,
+    one_shots: Vec<OneShotState>,
+    /// Keys that are held by kanata regardless of the layout state.
+    locked_keys: Vec<KeyCode>,
+    /// The base layer set by layer-switch.
+    base_layer: usize,
+    /// The layer that is toggled on by layer-toggle or locked by layer-lock, which temporarily
+    /// replaces the base layer.
+    toggled_layer: Option<usize>,
+    /// Macros waiting to be played back. Only the first one is active.
+    macros: VecDeque<ActiveMacro>,
+    /// Keys that are currently pressed by the active macro.
+    macro_keys: Vec<KeyCode>,
+    /// Virtual keys declared in defvirtualkeys that are pressed.
+    pressed_virtual_keys: HashSet<u8>,
+    /// Physical keys that are held, which the key conditions of switch are checked against.
+    held_physical_keys: Vec<KeyCode>,
+    caps_word: Option<CapsWordState>,
+    /// Keys of the held unshift and unmod actions and the modifiers that they release.
+    unmod_keys: Vec<(&'static [KeyCode], &'static [KeyCode])>,
+    dynamic_macro_recording: Option<DynamicMacroRecording>,
+    /// Dynamic macros by their numbers.
+    dynamic_macros: HashMap<u16, &'static Macro>,
+    /// The most recent output, which is output again by the repeat action.
+    last_action: Option<LastAction>,
+    /// Keys that are held by a held repeat action.
+    repeat_keys: Vec<KeyCode>,
+    key_repeat: cfg::KeyRepeat,
+    /// The key that is repeated by kanata, if its repeat mode is kanata.
+    key_repeat_state: Option<KeyRepeatState>,
+    debounce: cfg::Debounce,
+    /// Time of the most recent release of each physical key, for debounce and bounce-keys.
+    last_releases: HashMap<u8, time::Instant>,
+    slow_keys: u16,
+    bounce_keys: bool,
+    bounce_keys_time: u16,
+    /// Pressed keys whose press is not handled yet because of slow-keys, with the number of
+    /// milliseconds until it is handled.
+    pending_slow_keys: Vec<(OsCode, u16)>,
+    /// Mouse wheel directions of the input device that are mapped in defsrc and were scrolled.
+    mwheel_keys: Vec<MWheelKeyState>,
+    /// The held mirror action.
+    mirror: Option<&'static Mirror>,
+    /// Held physical keys that were pressed while a mirror action was held and the physical keys
+    /// that they were mirrored to.
+    mirrored_keys: HashMap<u8, u8>,
+    mouse_move: cfg::MouseMove,
+    reverse_scroll: bool,
+    /// Keyboard layout of the OS that text and unicode are typed with.
+    text_layout: TextLayout,
+    #[cfg(target_os = "windows")]
+    altgr: cfg::AltGrBehaviour,
+    /// Whether the last key event was a press of lctl, which may be the fake press of AltGr.
+    #[cfg(target_os = "windows")]
+    lctl_pressed_last: bool,
+    mouse_wheel: cfg::MouseWheel,
+    /// Directions of the held mwheel actions, which scroll repeatedly if mwheel-interval is set.
+    mwheel_directions: Vec<MWheelDirection>,
+    /// Milliseconds until the next scroll of the held mwheel actions.
+    mwheel_ticks: u16,
+    /// Directions of the held movemouse actions.
+    move_directions: Vec<MoveDirection>,
+    /// Held gamepad-axis actions in the order they were pressed. The most recent action of an axis
+    /// sets its value.
+    gamepad_axes: Vec<(GamepadAxis, i32)>,
+    /// Percentages of the held movemouse-speed actions.
+    move_speeds: Vec<u16>,
+    /// Milliseconds since the mouse pointer started moving.
+    move_ticks: u16,
+    /// Distance in pixels that has not been moved yet because it is less than a pixel.
+    move_remainder: (f64, f64),
+    /// State of the mouse jiggler while it is on.
+    jiggler: Option<JigglerState>,
 }
 
+/// Milliseconds that the key of a mouse wheel direction is held for each scroll.
+const MWHEEL_KEY_HOLD_TIME: u16 = 10;
+
+/// Time between two checks for input devices that were plugged in.
+#[cfg(target_os = "linux")]
+const DEVICE_RESCAN_INTERVAL: time::Duration = time::Duration::from_secs(2);
+
+/// Epoll token of the signal to quit, which is distinct from the tokens of the input devices.
+#[cfg(target_os = "linux")]
+const QUIT_TOKEN: u64 = u64::MAX;
+
+/// Epoll token of the LED changes of the virtual keyboard.
+#[cfg(target_os = "linux")]
+const LED_TOKEN: u64 = u64::MAX - 1;
+
+/// An input device that is read by the event loop.
+#[cfg(target_os = "linux")]
+struct InputDevice {
+    path: PathBuf,
+    name: String,
+    kbd_in: KbdIn,
+    /// Keys of the device that are held in the processing loop.
+    held_mapped: HashSet<OsCode>,
+    /// Keys of the device that are held and were passed through.
+    held_passed: HashSet<OsCode>,
+    /// Previous absolute X and Y positions of the device, for turning them into relative movement.
+    abs_position: [Option<i32>; 2],
+}
+
+#[cfg(target_os = "linux")]
+impl InputDevice {
+    fn new(path: PathBuf, kbd_in: KbdIn) -> Self {
+        Self {
+            path,
+            name: kbd_in.name().to_owned(),
+            kbd_in,
+            held_mapped: HashSet::new(),
+            held_passed: HashSet::new(),
+            abs_position: [None, None],
+        }
+    }
+}
+
+/// A mouse wheel direction of the input device that is tapped as a key. Each scroll of the wheel is
+/// one tap of the key.
+struct MWheelKeyState {
+    code: OsCode,
+    held: bool,
+    /// Milliseconds until the key is released, or until it is pressed again for the next scroll.
+    ticks_remaining: u16,
+    /// Scrolls that are not handled yet.
+    queued: u16,
+}
+
+/// State of the mouse jiggler, which moves the mouse pointer by one pixel and back in turns.
+struct JigglerState {
+    /// Milliseconds between two movements.
+    interval: u32,
+    ticks_remaining: u32,
+    /// Whether the pointer was moved away from its position and is moved back next.
+    moved: bool,
+}
+
+/// State of the most recently pressed key while kanata repeats it.
+struct KeyRepeatState {
+    coord: u8,
+    ticks_remaining: u16,
+    /// Output of the key that the OS can't repeat, e.g. unicode or a macro. Key outputs are
+    /// repeated like the OS repeat events in `handle_repeat`.
+    action: Option<LastAction>,
+}
+
+/// An output that the repeat action can replay.
+enum LastAction {
+    /// Keys that were pressed together with the modifiers that were held at the time.
+    Keys(Vec<KeyCode>),
+    Macro(&'static Macro),
+    Unicode(char),
+}
+
+/// State of a pressed tap-hold key with except-keys whose tap/hold decision is still pending.
+struct TapHoldExceptState {
+    coord: KeyCoord,
+    ticks_remaining: u16,
+    keys: &'static [OsCode],
+}
+
+/// State of a tap-dance that is still counting taps.
+struct TapDanceState {
+    coord: KeyCoord,
+    td: &'static TapDance,
+    num_taps: usize,
+    ticks_since_press: u16,
+    pressed: bool,
+}
+
+/// State of a pressed timeout action that is waiting for another key press or for its timeout.
+struct TimeoutState {
+    coord: KeyCoord,
+    timeout: &'static Timeout,
+    ticks_remaining: u16,
+    /// Whether the timeout key is still physically pressed.
+    pressed: bool,
+}
+
+/// State of an active one-shot key.
+struct OneShotState {
+    coord: KeyCoord,
+    vkey: u8,
+    ticks_remaining: u16,
+    /// Whether the one-shot key is still physically pressed.
+    pressed: bool,
+    /// Coordinate of the key that was pressed while the one-shot was active. The one-shot ends
+    /// when that key is released.
+    used_by: Option<u8>,
+    /// A locked one-shot stays active until its key is pressed again.
+    locked: bool,
+}
+
+/// State of an active caps-word.
+struct CapsWordState {
+    timeout: u16,
+    ticks_remaining: u16,
+    /// Whether caps-word holds shift for the held letters.
+    shifted: bool,
+}
+
+impl CapsWordState {
+    /// Add shift to the front of `cur_keys`, so that it is pressed before the letters, when a
+    /// letter is pressed. Shift stays held while letters are held, until a digit or another key
+    /// that continues the word is pressed. Returns false if caps-word ended because a key that is
+    /// not part of a word was pressed, or because the timeout expired.
+    fn apply(&mut self, cur_keys: &mut Vec<KeyCode>, prev_keys: &[KeyCode]) -> bool {
+        let is_letter = |k: &KeyCode| (KeyCode::A..=KeyCode::Z).contains(k);
+        let mut letter_pressed = false;
+        let mut other_pressed = false;
+        for k in cur_keys.iter().filter(|k| !prev_keys.contains(k)) {
+            let continues_word = (KeyCode::A..=KeyCode::Z).contains(k)
+                || (KeyCode::Kb1..=KeyCode::Kb0).contains(k)
+                || matches!(
+                    k,
+                    KeyCode::Minus | KeyCode::BSpace | KeyCode::LShift | KeyCode::RShift
+                );
+            if !continues_word {
+                log::debug!("caps-word ended by {:?}", k);
+                return false;
+            }
+            match is_letter(k) {
+                true => letter_pressed = true,
+                false => other_pressed = true,
+            }
+            self.ticks_remaining = self.timeout;
+        }
+        self.ticks_remaining = self.ticks_remaining.saturating_sub(1);
+        if self.ticks_remaining == 0 {
+            log::debug!("caps-word timed out");
+            return false;
+        }
+        if other_pressed || !cur_keys.iter().any(is_letter) {
+            self.shifted = false;
+        } else if letter_pressed {
+            self.shifted = true;
+        }
+        if self.shifted && !cur_keys.contains(&KeyCode::LShift) {
+            cur_keys.insert(0, KeyCode::LShift);
+        }
+        true
+    }
+}
+
+/// A dynamic macro that is being recorded.
+struct DynamicMacroRecording {
+    slot: u16,
+    events: Vec<MacroEvent>,
+}
+
+/// State of a macro that is being played back.
+struct ActiveMacro {
+    m: &'static Macro,
+    /// The events of the current repetition that have not been played back yet.
+    events: &'static [MacroEvent],
+    /// Number of repetitions left after the current one.
+    repeats_remaining: u16,
+    /// Whether the macro repeats because its key is held.
+    looping: bool,
+    ticks_to_wait: u16,
+}
+
+#[cfg(target_os = "windows")]
 use once_cell::sync::Lazy;
 
+/// The mapped keys of the configuration, for the callback of the keyboard hook.
+#[cfg(target_os = "windows")]
 static MAPPED_KEYS: Lazy<Mutex<cfg::MappedKeys>> = Lazy::new(|| Mutex::new([false; 256]));
 
 #[cfg(target_os = "windows")]
 static PRESSED_KEYS: Lazy<Mutex<HashSet<OsCode>>> = Lazy::new(|| Mutex::new(HashSet::new()));
 
+/// Whether kanata is paused from the tray icon, in which case new key presses are handed back to
+/// the OS.
+#[cfg(target_os = "windows")]
+pub static PAUSED: std::sync::atomic::AtomicBool = std::sync::atomic::AtomicBool::new(false);
+
 impl Kanata {
     /// Create a new configuration from a file.
     pub fn new(cfg_path: PathBuf) -> Result<Self> {
         let cfg = cfg::Cfg::new_from_file(&cfg_path)?;
 
-        let kbd_out = match KbdOut::new() {
+        #[cfg(target_os = "linux")]
+        let kbd_in_selection = DeviceSelection {
+            paths: cfg
+                .items
+                .get("linux-dev")
+                .map(|val| {
+                    cfg::parse_dev_list(val)
+                        .into_iter()
+                        .map(PathBuf::from)
+                        .collect()
+                })
+                .unwrap_or_default(),
+            names: cfg
+                .items
+                .get("linux-dev-names")
+                .map(|val| cfg::parse_dev_list(val))
+                .unwrap_or_default(),
+            exclude: cfg
+                .items
+                .get("linux-dev-exclude")
+                .map(|val| cfg::parse_dev_list(val))
+                .unwrap_or_default(),
+        };
+        #[cfg(target_os = "linux")]
+        let mut kbd_in_paths = kbd_in_selection.devices();
+        #[cfg(target_os = "linux")]
+        if cfg.continue_if_no_devs {
+            // Devices that don't exist yet are read once they are plugged in.
+            kbd_in_paths.retain(|path| path.exists());
+            if kbd_in_paths.is_empty() {
+                log::warn!(
+                    "Kanata: no input devices were found, waiting for them to be plugged in"
+                );
+            }
+        } else if kbd_in_paths.is_empty() {
+            bail!("no input devices were found. Set linux-dev or linux-dev-names in defcfg.")
+        }
+        #[cfg(target_os = "windows")]
+        let kbd_in_paths = Vec::new();
+
+        // The virtual devices also advertise the events besides keys that the input devices can
+        // send, so that the events that pass through are not dropped.
+        #[cfg(target_os = "linux")]
+        let kbd_out = KbdOut::new(&cfg.uinput, &DeviceCapabilities::of_devices(&kbd_in_paths)).map(
+            |mut kbd_out| {
+                kbd_out.unicode_method = cfg.unicode_method;
+                kbd_out
+            },
+        );
+        #[cfg(target_os = "windows")]
+        set_scancode_mode(cfg.scancodes);
+        #[cfg(target_os = "windows")]
+        set_injected_events(cfg.injected_events);
+        #[cfg(target_os = "windows")]
+        let kbd_out = match cfg.interception {
+            Some(_) => KbdOut::new_interception(),
+            None => KbdOut::new(),
+        }
+        .map(|mut kbd_out| {
+            kbd_out.unicode_method = cfg.unicode_method;
+            kbd_out
+        });
+        let kbd_out = match kbd_out {
             Ok(kbd_out) => kbd_out,
             Err(err) => {
-                error!("Failed to open the output uinput device. Make sure you've added kanata to the `uinput` group");
+                #[cfg(target_os = "linux")]
+                log::error!("Failed to open the output uinput device. Make sure you've added kanata to the `uinput` group");
                 bail!(err)
             }
         };
-
-        #[cfg(target_os = "linux")]
-        let kbd_in_path = cfg
-            .items
-            .get("linux-dev")
-            .expect("linux-dev required in defcfg")
-            .into();
-        #[cfg(target_os = "windows")]
-        let kbd_in_path = "unused".into();
+        let (cmd_output_tx, cmd_output_rx) = crossbeam_channel::unbounded();
 
         Ok(Self {
-            kbd_in_path,
+            kbd_in_paths,
+            #[cfg(target_os = "linux")]
+            kbd_in_selection,
+            #[cfg(target_os = "linux")]
+            device_keys: cfg.device_keys,
+            #[cfg(target_os = "linux")]
+            event_policies: cfg.event_policies,
+            #[cfg(target_os = "windows")]
+            interception_hwids: cfg.interception.unwrap_or_default(),
             kbd_out,
             cfg_path,
             mapped_keys: cfg.mapped_keys,
             key_outputs: cfg.key_outputs,
             layout: cfg.layout,
+            layer_names: cfg.layer_names,
+            prev_layer: 0,
+            live_reload_requested: false,
+            #[cfg(target_os = "windows")]
+            layer_change_notice: None,
+            tcp_server_port: cfg.tcp_server_port,
+            layer_change_tx: None,
+            cmd_output_tx,
+            cmd_output_rx,
+            chords: cfg.chords,
+            sequences: cfg.sequences,
+            overrides: cfg.overrides,
+            socd: cfg.socd,
+            zippy: cfg.zippy,
+            sequence_action: None,
+            sequence_matched_keys: Vec::new(),
             prev_keys: Vec::new(),
             last_tick: time::Instant::now(),
+            tap_hold_except: None,
+            ignored_releases: HashSet::new(),
+            tap_dance: None,
+            timeout: None,
+            turbos: Vec::new(),
+            held_virtual_keys: Vec::new(),
+            one_shots: Vec::new(),
+            locked_keys: Vec::new(),
+            base_layer: 0,
+            toggled_layer: None,
+            macros: VecDeque::new(),
+            macro_keys: Vec::new(),
+            pressed_virtual_keys: HashSet::new(),
+            held_physical_keys: Vec::new(),
+            caps_word: None,
+            unmod_keys: Vec::new(),
+            dynamic_macro_recording: None,
+            dynamic_macros: HashMap::new(),
+            last_action: None,
+            repeat_keys: Vec::new(),
+            key_repeat: cfg.key_repeat,
+            key_repeat_state: None,
+            debounce: cfg.debounce,
+            last_releases: HashMap::new(),
+            slow_keys: cfg.slow_keys,
+            bounce_keys: cfg.bounce_keys,
+            bounce_keys_time: cfg.bounce_keys_time,
+            pending_slow_keys: Vec::new(),
+            mwheel_keys: Vec::new(),
+            mirror: None,
+            mirrored_keys: HashMap::new(),
+            mouse_move: cfg.mouse_move,
+            reverse_scroll: cfg.reverse_scroll,
+            text_layout: cfg.text_layout,
+            #[cfg(target_os = "windows")]
+            altgr: cfg.altgr,
+            #[cfg(target_os = "windows")]
+            lctl_pressed_last: false,
+            mouse_wheel: cfg.mouse_wheel,
+            mwheel_directions: Vec::new(),
+            mwheel_ticks: 0,
+            move_directions: Vec::new(),
+            gamepad_axes: Vec::new(),
+            move_speeds: Vec::new(),
+            move_ticks: 0,
+            move_remainder: (0.0, 0.0),
+            jiggler: None,
         })
     }
 
@@ -79,16 +531,521 @@ impl Kanata {
 
     /// Update keyberon layout state for press/release, handle repeat separately
     fn handle_key_event(&mut self, event: &KeyEvent) -> Result<()> {
+        #[cfg(target_os = "windows")]
+        self.handle_altgr(event)?;
+        if event.code.mwheel_direction().is_some() {
+            // The input device only sends presses for scrolls. The key is released after a short
+            // time and scrolls in the meantime are handled after that.
+            if let Some(state) = self.mwheel_keys.iter_mut().find(|s| s.code == event.code) {
+                state.queued = state.queued.saturating_add(1);
+                return Ok(());
+            }
+            self.mwheel_keys.push(MWheelKeyState {
+                code: event.code,
+                held: true,
+                ticks_remaining: MWHEEL_KEY_HOLD_TIME,
+                queued: 0,
+            });
+            return self.process_key_event(event);
+        }
+        let evc: u32 = event.code.into();
+        match event.value {
+            KeyValue::Press => {
+                if self.is_bounce(evc as u8) {
+                    log::debug!("discarded bounced press of {:?}", event.code);
+                    self.ignored_releases.insert(evc as u8);
+                    return Ok(());
+                }
+                if self.slow_keys > 0 {
+                    // The press is handled once the key has been held for the slow-keys time.
+                    self.pending_slow_keys.push((event.code, self.slow_keys));
+                    return Ok(());
+                }
+            }
+            KeyValue::Release => {
+                if self.bounce_time(evc as u8) > 0 {
+                    self.last_releases.insert(evc as u8, time::Instant::now());
+                }
+                if let Some(i) = self
+                    .pending_slow_keys
+                    .iter()
+                    .position(|(code, _)| *code == event.code)
+                {
+                    log::debug!("slow-keys discarded press of {:?}", event.code);
+                    self.pending_slow_keys.remove(i);
+                    return Ok(());
+                }
+            }
+            KeyValue::Repeat => {
+                if self
+                    .pending_slow_keys
+                    .iter()
+                    .any(|(code, _)| *code == event.code)
+                {
+                    return Ok(());
+                }
+            }
+        }
+        self.process_key_event(event)
+    }
+
+    /// Work around the fake lctl events that Windows sends for AltGr, which are received by the
+    /// keyboard hook before the events of ralt, according to windows-altgr.
+    #[cfg(target_os = "windows")]
+    fn handle_altgr(&mut self, event: &KeyEvent) -> Result<()> {
+        let lctl_pressed_last = std::mem::replace(
+            &mut self.lctl_pressed_last,
+            event.code == OsCode::KEY_LEFTCTRL && event.value == KeyValue::Press,
+        );
+        if event.code != OsCode::KEY_RIGHTALT {
+            return Ok(());
+        }
+        match (self.altgr, event.value) {
+            (cfg::AltGrBehaviour::CancelLctlPress, KeyValue::Press) if lctl_pressed_last => {
+                log::debug!("altgr: cancelling the press of lctl");
+                PRESSED_KEYS.lock().remove(&OsCode::KEY_LEFTCTRL);
+                self.handle_key_event(&KeyEvent::new(OsCode::KEY_LEFTCTRL, KeyValue::Release))?;
+                // The fake release of lctl follows when AltGr is released.
+                let lctl: u32 = OsCode::KEY_LEFTCTRL.into();
+                self.ignored_releases.insert(lctl as u8);
+            }
+            (cfg::AltGrBehaviour::AddLctlRelease, KeyValue::Release) => {
+                if PRESSED_KEYS.lock().remove(&OsCode::KEY_LEFTCTRL) {
+                    log::debug!("altgr: adding a release of lctl");
+                    self.handle_key_event(&KeyEvent::new(OsCode::KEY_LEFTCTRL, KeyValue::Release))?;
+                }
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
+    /// Handle a key event that passed debouncing and slow-keys.
+    fn process_key_event(&mut self, event: &KeyEvent) -> Result<()> {
+        let event = &self.mirror_event(event);
         let evc: u32 = event.code.into();
         let kbrn_ev = match event.value {
-            KeyValue::Press => Event::Press(0, evc as u8),
-            KeyValue::Release => Event::Release(0, evc as u8),
+            KeyValue::Press => {
+                let kc = KeyCode::from(event.code);
+                if !self.held_physical_keys.contains(&kc) {
+                    self.held_physical_keys.push(kc);
+                }
+                if let Some(seq_event) = self.sequences.press(evc as u8) {
+                    if self.handle_sequence_event(seq_event) {
+                        self.ignored_releases.insert(evc as u8);
+                        return Ok(());
+                    }
+                }
+                self.zippy.press(evc as u8);
+                // Only the most recently pressed key is repeated.
+                self.key_repeat_state = match self.key_repeat.mode(evc as u8) {
+                    cfg::RepeatMode::Kanata => Some(KeyRepeatState {
+                        coord: evc as u8,
+                        ticks_remaining: self.key_repeat.delay,
+                        action: None,
+                    }),
+                    _ => None,
+                };
+                self.release_tap_hold_on_except_key(event.code);
+                if self.timeout.is_some() {
+                    // Pressing another key decides for the interrupted action.
+                    self.finish_timeout(true);
+                }
+                if matches!(&self.tap_dance, Some(state) if state.coord != (0, evc as u8)) {
+                    // Pressing another key ends the tap-dance.
+                    self.finish_tap_dance();
+                }
+                self.cancel_macros();
+                for state in self.one_shots.iter_mut() {
+                    if state.used_by.is_none() && !state.locked && state.coord != (0, evc as u8) {
+                        state.used_by = Some(evc as u8);
+                    }
+                }
+                Event::Press(0, evc as u8)
+            }
+            KeyValue::Release => {
+                let kc = KeyCode::from(event.code);
+                self.held_physical_keys.retain(|k| *k != kc);
+                if self.ignored_releases.remove(&(evc as u8)) {
+                    return Ok(());
+                }
+                if matches!(&self.key_repeat_state, Some(state) if state.coord == evc as u8) {
+                    self.key_repeat_state = None;
+                }
+                if let Some(output) = self.zippy.release(evc as u8) {
+                    for _ in 0..output.backspaces {
+                        self.kbd_out.press_key(OsCode::KEY_BACKSPACE)?;
+                        self.kbd_out.release_key(OsCode::KEY_BACKSPACE)?;
+                    }
+                    self.type_text(&output.text)?;
+                }
+                Event::Release(0, evc as u8)
+            }
             KeyValue::Repeat => return self.handle_repeat(event),
         };
-        self.layout.event(kbrn_ev);
+        let consumed_by_chords = match kbrn_ev {
+            Event::Press(_, coord) => self.chords.press(coord, &mut self.layout),
+            Event::Release(_, coord) => self.chords.release(coord, &mut self.layout),
+        };
+        if !consumed_by_chords {
+            self.layout.event(kbrn_ev);
+        }
+        if let Event::Release(_, coord) = kbrn_ev {
+            self.release_one_shots(|state| !state.pressed && state.used_by == Some(coord));
+        }
         Ok(())
     }
 
+    /// Return the event of the mirrored key for an event of a physical key if the key is pressed
+    /// while a mirror action is held. The release and repeats of the key are mirrored like its
+    /// press, even if the mirror action is released in the meantime.
+    fn mirror_event(&mut self, event: &KeyEvent) -> KeyEvent {
+        let coord = u32::from(event.code) as u8;
+        let mirrored = match event.value {
+            KeyValue::Press => {
+                let mirrored = self.mirror.and_then(|m| m.mirrored(coord));
+                if let Some(mirrored) = mirrored {
+                    self.mirrored_keys.insert(coord, mirrored);
+                }
+                mirrored
+            }
+            KeyValue::Release => self.mirrored_keys.remove(&coord),
+            KeyValue::Repeat => self.mirrored_keys.get(&coord).copied(),
+        };
+        match mirrored {
+            Some(mirrored) => KeyEvent::new(OsCode::from(u32::from(mirrored)), event.value),
+            None => KeyEvent::new(event.code, event.value),
+        }
+    }
+
+    /// Handle a press of the one-shot key at `coord`. Pressing an active one-shot key again
+    /// either locks it or cancels it, depending on its configuration.
+    fn press_one_shot(&mut self, coord: KeyCoord, vkey: u8, cfg: OneShotConfig) {
+        if let Some(state) = self.one_shots.iter_mut().find(|state| state.coord == coord) {
+            if cfg.double_tap_lock && !state.locked {
+                state.locked = true;
+                state.pressed = true;
+                state.used_by = None;
+            } else {
+                self.release_one_shots(|state| state.coord == coord);
+            }
+            return;
+        }
+        if cfg.stack {
+            // One-shot keys pressed in a row are all applied to the next key.
+            for state in self.one_shots.iter_mut() {
+                if state.used_by.map(|used_by| (0, used_by)) == Some(coord) {
+                    state.used_by = None;
+                }
+            }
+        } else {
+            self.release_one_shots(|state| !state.pressed && !state.locked);
+        }
+        self.layout.event(Event::Press(VIRTUAL_ROW, vkey));
+        self.one_shots.push(OneShotState {
+            coord,
+            vkey,
+            ticks_remaining: cfg.timeout,
+            pressed: true,
+            used_by: None,
+            locked: false,
+        });
+    }
+
+    /// Handle a release of the one-shot key at `coord`. If another key was pressed while the
+    /// one-shot key was held, the one-shot key behaves like a regular held key and ends now.
+    fn release_one_shot(&mut self, coord: KeyCoord) {
+        for state in self.one_shots.iter_mut() {
+            if state.coord == coord {
+                state.pressed = false;
+            }
+        }
+        self.release_one_shots(|state| state.coord == coord && state.used_by.is_some());
+    }
+
+    /// End the active one-shots that match `pred` and release their actions.
+    fn release_one_shots(&mut self, mut pred: impl FnMut(&OneShotState) -> bool) {
+        let layout = &mut self.layout;
+        self.one_shots.retain(|state| {
+            if !pred(state) {
+                return true;
+            }
+            layout.event(Event::Release(VIRTUAL_ROW, state.vkey));
+            false
+        });
+    }
+
+    /// Handle the result of a key press while a sequence is being entered. Returns true if the key
+    /// press should be hidden.
+    fn handle_sequence_event(&mut self, seq_event: SequenceEvent) -> bool {
+        match seq_event {
+            SequenceEvent::Pending { hidden } => hidden,
+            SequenceEvent::NoMatch => false,
+            SequenceEvent::Matched {
+                vkey,
+                hidden,
+                backspaces,
+                matched_keys,
+            } => {
+                self.sequence_matched_keys = matched_keys;
+                if let Some(m) = backspaces {
+                    self.macros.push_back(ActiveMacro {
+                        m,
+                        events: m.events,
+                        repeats_remaining: 0,
+                        looping: false,
+                        ticks_to_wait: 0,
+                    });
+                }
+                self.sequence_action = Some(vkey);
+                hidden
+            }
+        }
+    }
+
+    /// If a tap-hold key with except-keys is waiting for its tap/hold decision and `pressed` is one
+    /// of its except-keys, release the tap-hold key in the layout so that the tap action is chosen.
+    /// The physical release of the tap-hold key that comes later is ignored.
+    fn release_tap_hold_on_except_key(&mut self, pressed: OsCode) {
+        let coord = match &self.tap_hold_except {
+            Some(state) if state.keys.contains(&pressed) => state.coord,
+            _ => return,
+        };
+        log::debug!("except key {:?} pressed, forcing tap", pressed);
+        self.layout.event(Event::Release(coord.0, coord.1));
+        if coord.0 == 0 {
+            self.ignored_releases.insert(coord.1);
+        }
+        self.tap_hold_except = None;
+    }
+
+    /// Count a press of the tap-dance key at `coord`. This starts a new dance if no dance for
+    /// this key is ongoing.
+    fn press_tap_dance(&mut self, coord: KeyCoord, td: &'static TapDance) {
+        let num_taps = match self.tap_dance.as_mut() {
+            Some(state) if state.coord == coord => {
+                state.num_taps += 1;
+                state.ticks_since_press = 0;
+                state.pressed = true;
+                state.num_taps
+            }
+            _ => {
+                self.finish_tap_dance();
+                self.tap_dance = Some(TapDanceState {
+                    coord,
+                    td,
+                    num_taps: 1,
+                    ticks_since_press: 0,
+                    pressed: true,
+                });
+                1
+            }
+        };
+        if td.config == TapDanceConfig::Eager {
+            // The action for the current tap count is held until the key is released.
+            let vkey = td.actions[num_taps - 1];
+            self.layout.event(Event::Press(VIRTUAL_ROW, vkey));
+            self.held_virtual_keys.push((coord, vkey));
+        }
+        if num_taps >= td.actions.len() {
+            self.finish_tap_dance();
+        }
+    }
+
+    /// End the ongoing tap-dance. For a lazy tap-dance, activate the action for the current
+    /// number of taps. The action is held if the tap-dance key is still pressed, otherwise it is
+    /// tapped. An eager tap-dance has already activated its actions.
+    fn finish_tap_dance(&mut self) {
+        let state = match self.tap_dance.take() {
+            Some(state) => state,
+            None => return,
+        };
+        if state.td.config == TapDanceConfig::Eager {
+            return;
+        }
+        let vkey = state.td.actions[state.num_taps.min(state.td.actions.len()) - 1];
+        log::debug!("tap-dance finished with {} taps", state.num_taps);
+        self.layout.event(Event::Press(VIRTUAL_ROW, vkey));
+        if state.pressed {
+            self.held_virtual_keys.push((state.coord, vkey));
+        } else {
+            self.layout.event(Event::Release(VIRTUAL_ROW, vkey));
+        }
+    }
+
+    /// Activate the action of the ongoing timeout action, if any. The action is held if the key is
+    /// still pressed, otherwise it is tapped.
+    fn finish_timeout(&mut self, interrupted: bool) {
+        let state = match self.timeout.take() {
+            Some(state) => state,
+            None => return,
+        };
+        let vkey = match interrupted {
+            true => state.timeout.interrupted,
+            false => state.timeout.timed_out,
+        };
+        log::debug!("timeout   interrupted: {}", interrupted);
+        self.layout.event(Event::Press(VIRTUAL_ROW, vkey));
+        if state.pressed {
+            self.held_virtual_keys.push((state.coord, vkey));
+        } else {
+            self.layout.event(Event::Release(VIRTUAL_ROW, vkey));
+        }
+    }
+
+    /// Release the virtual keys that are held for the key at `coord`.
+    fn release_virtual_keys(&mut self, coord: KeyCoord) {
+        let layout = &mut self.layout;
+        self.held_virtual_keys.retain(|(phys, vkey)| {
+            if *phys != coord {
+                return true;
+            }
+            layout.event(Event::Release(VIRTUAL_ROW, *vkey));
+            false
+        });
+    }
+
+    /// Press or release a virtual key declared in defvirtualkeys.
+    fn change_virtual_key(&mut self, vkey: u8, op: VirtualKeyOp) {
+        let pressed = self.pressed_virtual_keys.contains(&vkey);
+        let (press, release) = match op {
+            VirtualKeyOp::Press => (!pressed, false),
+            VirtualKeyOp::Release => (false, pressed),
+            VirtualKeyOp::Tap => (!pressed, true),
+            VirtualKeyOp::Toggle => (!pressed, pressed),
+        };
+        log::debug!("vkey      {} {:?}", vkey, op);
+        if press {
+            self.layout.event(Event::Press(VIRTUAL_ROW, vkey));
+            self.pressed_virtual_keys.insert(vkey);
+        }
+        if release {
+            self.layout.event(Event::Release(VIRTUAL_ROW, vkey));
+            self.pressed_virtual_keys.remove(&vkey);
+        }
+    }
+
+    /// Cancel the macros that are cancelled by a physical key press. If the active macro is
+    /// cancelled, the keys it is holding are released.
+    fn cancel_macros(&mut self) {
+        if matches!(self.macros.front(), Some(m) if m.m.cancel_on_press) {
+            log::debug!("cancelling macro");
+            self.macro_keys.clear();
+        }
+        self.macros.retain(|m| !m.m.cancel_on_press);
+    }
+
+    /// Play back the next event of the active macro. Only one key event is played back per tick so
+    /// that every event is visible in the difference between the previous and current keys.
+    fn tick_macro(&mut self) {
+        let active = match self.macros.front_mut() {
+            Some(active) => active,
+            None => return,
+        };
+        if active.ticks_to_wait > 0 {
+            active.ticks_to_wait -= 1;
+            return;
+        }
+        match active.events.split_first() {
+            Some((event, rest)) => {
+                active.events = rest;
+                match *event {
+                    MacroEvent::Press(kc) => self.macro_keys.push(kc),
+                    MacroEvent::Release(kc) => self.macro_keys.retain(|k| *k != kc),
+                    MacroEvent::Delay(ms) => active.ticks_to_wait = ms,
+                    MacroEvent::PressMatched(i) => {
+                        if let Some(kc) = self.sequence_matched_keys.get(usize::from(i)) {
+                            self.macro_keys.push(*kc);
+                        }
+                    }
+                    MacroEvent::ReleaseMatched(i) => {
+                        if let Some(kc) = self.sequence_matched_keys.get(usize::from(i)) {
+                            self.macro_keys.retain(|k| k != kc);
+                        }
+                    }
+                }
+            }
+            None if active.looping => active.events = active.m.events,
+            None if active.repeats_remaining > 0 => {
+                active.repeats_remaining -= 1;
+                active.events = active.m.events;
+            }
+            None => {
+                self.macros.pop_front();
+            }
+        }
+    }
+
+    /// Type the text with key presses where the text layout has a key for the character, and as
+    /// unicode otherwise.
+    fn type_text(&mut self, text: &str) -> Result<()> {
+        // Consecutive characters without a key are sent together, which is faster with wtype.
+        let mut unicode = String::new();
+        for c in text.chars() {
+            match char_to_oscode(c, self.text_layout) {
+                Some((oscode, mods)) => {
+                    self.kbd_out
+                        .send_unicode_str(&std::mem::take(&mut unicode), self.text_layout)?;
+                    for m in mods {
+                        self.kbd_out.press_key(*m)?;
+                    }
+                    self.kbd_out.press_key(oscode)?;
+                    self.kbd_out.release_key(oscode)?;
+                    for m in mods {
+                        self.kbd_out.release_key(*m)?;
+                    }
+                }
+                None => unicode.push(c),
+            }
+        }
+        self.kbd_out.send_unicode_str(&unicode, self.text_layout)?;
+        Ok(())
+    }
+
+    fn play_macro(&mut self, m: &'static Macro) {
+        let (repeats_remaining, looping) = match m.repeat {
+            MacroRepeat::Times(n) => (n - 1, false),
+            MacroRepeat::WhileHeld => (0, true),
+        };
+        self.macros.push_back(ActiveMacro {
+            m,
+            events: m.events,
+            repeats_remaining,
+            looping,
+            ticks_to_wait: 0,
+        });
+    }
+
+    /// Store a recorded dynamic macro, replacing the macro previously recorded with its number.
+    fn save_dynamic_macro(&mut self, mut recording: DynamicMacroRecording) {
+        // Release the keys that are still held when the recording stops so that playing the
+        // macro back doesn't leave them pressed.
+        let mut held = Vec::new();
+        for event in recording.events.iter() {
+            match event {
+                MacroEvent::Press(kc) => held.push(*kc),
+                MacroEvent::Release(kc) => held.retain(|k| k != kc),
+                _ => {}
+            }
+        }
+        recording
+            .events
+            .extend(held.into_iter().rev().map(MacroEvent::Release));
+        log::info!(
+            "Saved dynamic macro {} with {} events",
+            recording.slot,
+            recording.events.len()
+        );
+        // The macro is leaked like the macros of the configuration because macros are played
+        // back by reference. Recording happens rarely, so the leak is small.
+        let m = Box::leak(Box::new(Macro {
+            events: Box::leak(recording.events.into_boxed_slice()),
+            cancel_on_press: false,
+            repeat: MacroRepeat::Times(1),
+        }));
+        self.dynamic_macros.insert(recording.slot, m);
+    }
+
     /// Advance keyberon layout state and send events based on changes to its state.
     fn handle_time_ticks(&mut self) -> Result<()> {
         let now = time::Instant::now();
@@ -97,39 +1054,415 @@ impl Kanata {
         if ms_elapsed > 0 {
             self.last_tick = now;
         }
-        let mut live_reload_requested = false;
+
+        while let Ok(output) = self.cmd_output_rx.try_recv() {
+            self.type_text(&output)?;
+        }
 
         for _ in 0..ms_elapsed {
+            for (_, ticks_remaining) in self.pending_slow_keys.iter_mut() {
+                *ticks_remaining = ticks_remaining.saturating_sub(1);
+            }
+            while let Some(i) = self
+                .pending_slow_keys
+                .iter()
+                .position(|(_, ticks_remaining)| *ticks_remaining == 0)
+            {
+                let (code, _) = self.pending_slow_keys.remove(i);
+                self.process_key_event(&KeyEvent::new(code, KeyValue::Press))?;
+            }
+            self.tick_mwheel_keys()?;
+            if let Some(state) = self.tap_hold_except.as_mut() {
+                state.ticks_remaining = state.ticks_remaining.saturating_sub(1);
+                if state.ticks_remaining == 0 {
+                    // The hold action has been activated by keyberon.
+                    self.tap_hold_except = None;
+                }
+            }
+            for state in self.one_shots.iter_mut() {
+                if !state.pressed && !state.locked && state.used_by.is_none() {
+                    state.ticks_remaining = state.ticks_remaining.saturating_sub(1);
+                }
+            }
+            self.release_one_shots(|state| state.ticks_remaining == 0);
+            for (turbo, ticks_remaining) in self.turbos.iter_mut() {
+                *ticks_remaining = ticks_remaining.saturating_sub(1);
+                if *ticks_remaining == 0 {
+                    *ticks_remaining = turbo.interval;
+                    self.layout.event(Event::Press(VIRTUAL_ROW, turbo.vkey));
+                    self.layout.event(Event::Release(VIRTUAL_ROW, turbo.vkey));
+                }
+            }
+            if let Some(state) = self.timeout.as_mut() {
+                state.ticks_remaining = state.ticks_remaining.saturating_sub(1);
+                if state.ticks_remaining == 0 {
+                    self.finish_timeout(false);
+                }
+            }
+            if let Some(state) = self.tap_dance.as_mut() {
+                state.ticks_since_press = state.ticks_since_press.saturating_add(1);
+                if state.ticks_since_press >= state.td.timeout {
+                    self.finish_tap_dance();
+                }
+            }
+
+            // The custom action is copied out of the layout so that the layout can receive events
+            // while the action is handled.
+            let custom_event = match self.layout.tick() {
+                CustomEvent::Press(custact) => Some((KeyValue::Press, *custact)),
+                CustomEvent::Release(custact) => Some((KeyValue::Release, *custact)),
+                _ => None,
+            };
+
             // Only send on the press. No repeat action is supported for this for the time being.
-            match self.layout.tick() {
-                CustomEvent::Press(custact) => match custact {
-                    CustomAction::Unicode(c) => self.kbd_out.send_unicode(*c)?,
+            match custom_event {
+                Some((KeyValue::Press, custact)) => match custact {
+                    CustomAction::Unicode(c) => {
+                        self.kbd_out.send_unicode(c, self.text_layout)?;
+                        self.last_action = Some(LastAction::Unicode(c));
+                        self.set_key_repeat_action(LastAction::Unicode(c));
+                    }
+                    CustomAction::Repeat => match self.last_action {
+                        Some(LastAction::Keys(ref keys)) => {
+                            log::debug!("repeat    {:?}", keys);
+                            self.repeat_keys = keys.clone();
+                        }
+                        Some(LastAction::Macro(m)) => self.play_macro(m),
+                        Some(LastAction::Unicode(c)) => {
+                            self.kbd_out.send_unicode(c, self.text_layout)?
+                        }
+                        None => {}
+                    },
+                    CustomAction::RawKey(oscode) => {
+                        log::debug!("press     {:?}", oscode);
+                        self.kbd_out.press_key(oscode)?;
+                    }
                     CustomAction::LiveReload => {
-                        live_reload_requested = true;
+                        self.live_reload_requested = true;
+                        // Locked keys would otherwise prevent the reload from happening.
+                        self.locked_keys.clear();
                         log::info!("Requested live reload")
                     }
-                    CustomAction::Mouse(btn) => {
-                        log::debug!("press     {:?}", btn);
-                        self.kbd_out.click_btn(*btn)?;
+                    CustomAction::MWheel { direction, reverse } => {
+                        let direction = self.mwheel_direction(direction, reverse);
+                        log::debug!("scroll    {:?}", direction);
+                        match self.mouse_wheel.interval {
+                            0 => self.kbd_out.scroll(direction, 120)?,
+                            interval => {
+                                self.kbd_out.scroll(direction, self.mouse_wheel.distance)?;
+                                if self.mwheel_directions.is_empty() {
+                                    self.mwheel_ticks = interval;
+                                }
+                                self.mwheel_directions.push(direction);
+                            }
+                        }
+                    }
+                    CustomAction::MoveMouse(direction) => self.move_directions.push(direction),
+                    CustomAction::GamepadAxis { axis, value } => {
+                        log::debug!("gamepad   {:?} {}", axis, value);
+                        self.gamepad_axes.push((axis, value));
+                        self.kbd_out.set_gamepad_axis(axis, value)?;
+                    }
+                    CustomAction::SetMouse { x, y } => {
+                        log::debug!("set mouse {} {}", x, y);
+                        self.kbd_out.set_mouse(x, y)?;
+                    }
+                    CustomAction::MoveMouseSpeed(percentage) => self.move_speeds.push(percentage),
+                    CustomAction::TapHoldExcept {
+                        coord,
+                        timeout,
+                        keys,
+                    } => {
+                        self.tap_hold_except = Some(TapHoldExceptState {
+                            coord,
+                            ticks_remaining: timeout,
+                            keys,
+                        });
+                    }
+                    CustomAction::TapDance { coord, td } => self.press_tap_dance(coord, td),
+                    CustomAction::Turbo(turbo) => {
+                        // The first tap happens on the next tick.
+                        self.turbos.push((turbo, 1));
+                    }
+                    CustomAction::Mirror(mirror) => self.mirror = Some(mirror),
+                    CustomAction::Timeout { coord, timeout } => {
+                        self.timeout = Some(TimeoutState {
+                            coord,
+                            timeout,
+                            ticks_remaining: timeout.timeout,
+                            pressed: true,
+                        });
+                    }
+                    CustomAction::OneShot { coord, vkey, cfg } => {
+                        self.press_one_shot(coord, vkey, cfg)
+                    }
+                    CustomAction::Macro(m) => {
+                        self.play_macro(m);
+                        self.last_action = Some(LastAction::Macro(m));
+                        self.set_key_repeat_action(LastAction::Macro(m));
+                    }
+                    CustomAction::DynamicMacroRecord(slot) => {
+                        match self.dynamic_macro_recording.take() {
+                            Some(recording) => self.save_dynamic_macro(recording),
+                            None => {
+                                log::info!("Recording dynamic macro {}", slot);
+                                self.dynamic_macro_recording = Some(DynamicMacroRecording {
+                                    slot,
+                                    events: Vec::new(),
+                                });
+                            }
+                        }
+                    }
+                    CustomAction::DynamicMacroRecordStop => {
+                        if let Some(recording) = self.dynamic_macro_recording.take() {
+                            self.save_dynamic_macro(recording);
+                        }
+                    }
+                    CustomAction::DynamicMacroPlay(slot) => match self.dynamic_macros.get(&slot) {
+                        Some(&m) => {
+                            self.play_macro(m);
+                            self.last_action = Some(LastAction::Macro(m));
+                        }
+                        None => log::warn!("Dynamic macro {} has not been recorded", slot),
+                    },
+                    CustomAction::SequenceLeader => self.sequences.start(),
+                    CustomAction::MouseJiggler(seconds) => match self.jiggler {
+                        Some(_) => {
+                            self.jiggler = None;
+                            log::info!("mouse jiggler off");
+                        }
+                        None => {
+                            let interval = u32::from(seconds) * 1000;
+                            self.jiggler = Some(JigglerState {
+                                interval,
+                                ticks_remaining: interval,
+                                moved: false,
+                            });
+                            log::info!("mouse jiggler on, moving every {} seconds", seconds);
+                        }
+                    },
+                    CustomAction::BounceKeysToggle => {
+                        self.bounce_keys = !self.bounce_keys;
+                        log::info!(
+                            "bounce-keys {}",
+                            if self.bounce_keys { "on" } else { "off" }
+                        );
+                    }
+                    CustomAction::LayerSwitch(layer) => {
+                        self.base_layer = layer;
+                        self.toggled_layer = None;
+                        self.layout.set_default_layer(layer);
+                    }
+                    CustomAction::LayerLock => {
+                        let active = self.layout.current_layer();
+                        if active != self.toggled_layer.unwrap_or(self.base_layer) {
+                            // A layer-while-held layer is active: keep it after its key is
+                            // released by making it the base layer.
+                            log::debug!("lock layer {}", active);
+                            self.toggled_layer = Some(active);
+                            self.layout.set_default_layer(active);
+                        } else if self.toggled_layer.take().is_some() {
+                            log::debug!("unlock layer {}", active);
+                            self.layout.set_default_layer(self.base_layer);
+                        }
+                    }
+                    CustomAction::LayerToggle(layer) => {
+                        if self.toggled_layer == Some(layer) {
+                            self.toggled_layer = None;
+                            self.layout.set_default_layer(self.base_layer);
+                        } else {
+                            self.toggled_layer = Some(layer);
+                            self.layout.set_default_layer(layer);
+                        }
+                    }
+                    CustomAction::Switch { coord, switch } => {
+                        let branch = switch.branches.iter().find(|b| {
+                            switch_cond_matches(
+                                &b.cond,
+                                &self.held_physical_keys,
+                                &self.pressed_virtual_keys,
+                            )
+                        });
+                        if let Some(branch) = branch {
+                            log::debug!("switch    {:?}", branch.cond);
+                            self.layout.event(Event::Press(VIRTUAL_ROW, branch.vkey));
+                            self.held_virtual_keys.push((coord, branch.vkey));
+                        }
+                    }
+                    CustomAction::VirtualKeyOnPress(vkey, op) => self.change_virtual_key(vkey, op),
+                    CustomAction::VirtualKeyOnRelease(..) => {}
+                    CustomAction::CapsWord(timeout) => {
+                        self.caps_word = match self.caps_word {
+                            Some(_) => None,
+                            None => Some(CapsWordState {
+                                timeout,
+                                ticks_remaining: timeout,
+                                shifted: false,
+                            }),
+                        };
+                    }
+                    CustomAction::Cmd(cmd) => run_cmd(cmd),
+                    CustomAction::CmdOutputKeys(cmd) => {
+                        run_cmd_output(cmd, self.cmd_output_tx.clone())
+                    }
+                    CustomAction::Unmod { keys, mods } => {
+                        self.unmod_keys.push((keys, mods));
+                    }
+                    CustomAction::KeyLock(kc) => {
+                        if let Some(i) = self.locked_keys.iter().position(|k| *k == kc) {
+                            log::debug!("unlock    {:?}", kc);
+                            self.locked_keys.remove(i);
+                        } else {
+                            log::debug!("lock      {:?}", kc);
+                            self.locked_keys.push(kc);
+                        }
+                    }
+                },
+                Some((KeyValue::Release, CustomAction::RawKey(oscode))) => {
+                    log::debug!("release   {:?}", oscode);
+                    self.kbd_out.release_key(oscode)?;
+                }
+                Some((KeyValue::Release, CustomAction::TapHoldExcept { coord, .. })) => {
+                    if matches!(&self.tap_hold_except, Some(state) if state.coord == coord) {
+                        self.tap_hold_except = None;
+                    }
+                }
+                Some((KeyValue::Release, CustomAction::MWheel { direction, reverse })) => {
+                    let direction = self.mwheel_direction(direction, reverse);
+                    if let Some(i) = self.mwheel_directions.iter().position(|d| *d == direction) {
+                        self.mwheel_directions.remove(i);
+                    }
+                }
+                Some((KeyValue::Release, CustomAction::MoveMouse(direction))) => {
+                    if let Some(i) = self.move_directions.iter().position(|d| *d == direction) {
+                        self.move_directions.remove(i);
+                    }
+                }
+                Some((KeyValue::Release, CustomAction::GamepadAxis { axis, value })) => {
+                    if let Some(i) = self.gamepad_axes.iter().rposition(|a| *a == (axis, value)) {
+                        self.gamepad_axes.remove(i);
+                    }
+                    let value = self
+                        .gamepad_axes
+                        .iter()
+                        .rev()
+                        .find(|(a, _)| *a == axis)
+                        .map_or(0, |(_, v)| *v);
+                    self.kbd_out.set_gamepad_axis(axis, value)?;
+                }
+                Some((KeyValue::Release, CustomAction::MoveMouseSpeed(percentage))) => {
+                    if let Some(i) = self.move_speeds.iter().position(|p| *p == percentage) {
+                        self.move_speeds.remove(i);
+                    }
+                }
+                Some((KeyValue::Release, CustomAction::Mirror(mirror))) => {
+                    if matches!(self.mirror, Some(m) if std::ptr::eq(m, mirror)) {
+                        self.mirror = None;
+                    }
+                }
+                Some((KeyValue::Release, CustomAction::Turbo(turbo))) => {
+                    if let Some(i) = self
+                        .turbos
+                        .iter()
+                        .position(|(t, _)| std::ptr::eq(*t, turbo))
+                    {
+                        self.turbos.remove(i);
+                    }
+                }
+                Some((KeyValue::Release, CustomAction::Timeout { coord, .. })) => {
+                    if let Some(state) = self.timeout.as_mut() {
+                        if state.coord == coord {
+                            state.pressed = false;
+                        }
+                    }
+                    self.release_virtual_keys(coord);
+                }
+                Some((KeyValue::Release, CustomAction::TapDance { coord, .. })) => {
+                    if let Some(state) = self.tap_dance.as_mut() {
+                        if state.coord == coord {
+                            state.pressed = false;
+                        }
+                    }
+                    self.release_virtual_keys(coord);
+                }
+                Some((KeyValue::Release, CustomAction::Unmod { keys, mods })) => {
+                    if let Some(i) = self
+                        .unmod_keys
+                        .iter()
+                        .position(|(k, m)| std::ptr::eq(*k, keys) && std::ptr::eq(*m, mods))
+                    {
+                        self.unmod_keys.remove(i);
+                    }
+                }
+                Some((KeyValue::Release, CustomAction::Repeat)) => self.repeat_keys.clear(),
+                Some((KeyValue::Release, CustomAction::Switch { coord, .. })) => {
+                    self.release_virtual_keys(coord);
+                }
+                Some((KeyValue::Release, CustomAction::Macro(m))) => {
+                    // Let a looping macro finish its current repetition.
+                    for active in self.macros.iter_mut() {
+                        if std::ptr::eq(active.m, m) {
+                            active.looping = false;
+                        }
+                    }
+                }
+                Some((KeyValue::Release, CustomAction::OneShot { coord, .. })) => {
+                    self.release_one_shot(coord);
+                }
+                Some((KeyValue::Release, CustomAction::VirtualKeyOnRelease(vkey, op))) => {
+                    self.change_virtual_key(vkey, op)
+                }
+                _ => {}
+            }
+
+            self.chords.tick(&mut self.layout);
+            self.sequences.tick();
+            self.zippy.tick();
+            self.tick_key_repeat()?;
+            self.tick_mwheel()?;
+            self.tick_mouse_move()?;
+            self.tick_jiggler()?;
+            self.tick_macro();
+            if self.macros.is_empty() {
+                if let Some(vkey) = self.sequence_action.take() {
+                    self.layout.event(Event::Press(VIRTUAL_ROW, vkey));
+                    self.layout.event(Event::Release(VIRTUAL_ROW, vkey));
+                }
+            }
+
+            let mut cur_keys: Vec<KeyCode> = self.layout.keycodes().collect();
+            for k in self
+                .locked_keys
+                .iter()
+                .chain(self.macro_keys.iter())
+                .chain(self.repeat_keys.iter())
+            {
+                if !cur_keys.contains(k) {
+                    cur_keys.push(*k);
+                }
+            }
+            for (keys, mods) in self.unmod_keys.iter() {
+                cur_keys.retain(|k| !mods.contains(k));
+                for k in keys.iter() {
+                    if !cur_keys.contains(k) {
+                        cur_keys.push(*k);
                     }
-                },
-                CustomEvent::Release(CustomAction::Mouse(btn)) => {
-                    log::debug!("release   {:?}", btn);
-                    self.kbd_out.release_btn(*btn)?;
                 }
-                _ => {}
             }
-
-            let cur_keys: Vec<KeyCode> = self.layout.keycodes().collect();
+            if let Some(state) = self.caps_word.as_mut() {
+                if !state.apply(&mut cur_keys, &self.prev_keys) {
+                    self.caps_word = None;
+                }
+            }
+            self.overrides.override_keys(&mut cur_keys);
+            self.socd.clean(&mut cur_keys);
 
             // Release keys that are missing from the current state but exist in the previous
             // state. It's important to iterate using a Vec because the order matters. This used to
             // use HashSet force computing `difference` but that iteration order is random which is
-            // not what we want.
-            for k in &self.prev_keys {
-                if cur_keys.contains(k) {
-                    continue;
-                }
+            // not what we want. Keys are released in the reverse order of how they were pressed so
+            // that e.g. the modifiers of a multi action are released last.
+            for k in released_keys(&self.prev_keys, &cur_keys) {
                 log::debug!("release   {:?}", k);
                 if let Err(e) = self.kbd_out.release_key(k.into()) {
                     bail!("failed to release key: {:?}", e);
@@ -137,27 +1470,116 @@ impl Kanata {
             }
             // Press keys that exist in the current state but are missing from the previous state.
             // Comment above regarding Vec/HashSet also applies here.
-            for k in &cur_keys {
-                if self.prev_keys.contains(k) {
-                    continue;
-                }
+            for k in pressed_keys(&self.prev_keys, &cur_keys) {
                 log::debug!("press     {:?}", k);
                 if let Err(e) = self.kbd_out.press_key(k.into()) {
                     bail!("failed to press key: {:?}", e);
                 }
             }
 
-            if live_reload_requested && self.prev_keys.is_empty() && cur_keys.is_empty() {
-                live_reload_requested = false;
+            // Remember newly pressed keys for the repeat action, except for the keys of macros,
+            // which are remembered as a whole, and the keys of the repeat action itself.
+            if self.macros.is_empty() && self.repeat_keys.is_empty() {
+                let mut new_keys = cur_keys
+                    .iter()
+                    .filter(|k| !self.prev_keys.contains(k) && !is_modifier(k))
+                    .peekable();
+                if new_keys.peek().is_some() {
+                    let mut keys: Vec<KeyCode> = cur_keys
+                        .iter()
+                        .filter(|k| is_modifier(k))
+                        .copied()
+                        .collect();
+                    keys.extend(new_keys);
+                    self.last_action = Some(LastAction::Keys(keys));
+                }
+            }
+
+            if let Some(recording) = self.dynamic_macro_recording.as_mut() {
+                for k in self.prev_keys.iter().rev() {
+                    if !cur_keys.contains(k) {
+                        recording.events.push(MacroEvent::Release(*k));
+                    }
+                }
+                for k in cur_keys.iter() {
+                    if !self.prev_keys.contains(k) {
+                        recording.events.push(MacroEvent::Press(*k));
+                    }
+                }
+            }
+
+            if self.live_reload_requested && self.prev_keys.is_empty() && cur_keys.is_empty() {
+                self.live_reload_requested = false;
                 match cfg::Cfg::new_from_file(&self.cfg_path) {
                     Err(e) => {
                         log::error!("Could not reload configuration:\n{}", e);
                     }
                     Ok(cfg) => {
                         self.layout = cfg.layout;
-                        let mut mapped_keys = MAPPED_KEYS.lock();
-                        *mapped_keys = cfg.mapped_keys;
+                        self.layer_names = cfg.layer_names;
+                        self.mapped_keys = cfg.mapped_keys;
+                        #[cfg(target_os = "linux")]
+                        {
+                            self.device_keys = cfg.device_keys;
+                            self.event_policies = cfg.event_policies;
+                        }
+                        self.kbd_out.unicode_method = cfg.unicode_method;
+                        #[cfg(target_os = "windows")]
+                        {
+                            *MAPPED_KEYS.lock() = cfg.mapped_keys;
+                            set_scancode_mode(cfg.scancodes);
+                            set_injected_events(cfg.injected_events);
+                        }
                         self.key_outputs = cfg.key_outputs;
+                        self.chords = cfg.chords;
+                        self.sequences = cfg.sequences;
+                        self.overrides = cfg.overrides;
+                        self.socd = cfg.socd;
+                        self.zippy = cfg.zippy;
+                        self.sequence_action = None;
+                        self.base_layer = 0;
+                        self.toggled_layer = None;
+                        self.tap_hold_except = None;
+                        self.ignored_releases.clear();
+                        self.tap_dance = None;
+                        self.timeout = None;
+                        self.turbos.clear();
+                        self.held_virtual_keys.clear();
+                        self.one_shots.clear();
+                        self.macros.clear();
+                        self.macro_keys.clear();
+                        self.pressed_virtual_keys.clear();
+                        self.caps_word = None;
+                        self.unmod_keys.clear();
+                        self.dynamic_macro_recording = None;
+                        self.repeat_keys.clear();
+                        self.key_repeat = cfg.key_repeat;
+                        self.key_repeat_state = None;
+                        self.debounce = cfg.debounce;
+                        self.slow_keys = cfg.slow_keys;
+                        self.bounce_keys = cfg.bounce_keys;
+                        self.bounce_keys_time = cfg.bounce_keys_time;
+                        self.mirror = None;
+                        self.mirrored_keys.clear();
+                        self.mouse_move = cfg.mouse_move;
+                        self.reverse_scroll = cfg.reverse_scroll;
+                        self.text_layout = cfg.text_layout;
+                        #[cfg(target_os = "windows")]
+                        {
+                            self.altgr = cfg.altgr;
+                        }
+                        self.mouse_wheel = cfg.mouse_wheel;
+                        self.mwheel_directions.clear();
+                        self.mwheel_keys.clear();
+                        self.move_directions.clear();
+                        self.move_speeds.clear();
+                        for (axis, _) in std::mem::take(&mut self.gamepad_axes) {
+                            self.kbd_out.set_gamepad_axis(axis, 0)?;
+                        }
+                        if let Some(JigglerState { moved: true, .. }) = self.jiggler.take() {
+                            // Move the pointer back to where the jiggler found it.
+                            self.kbd_out.move_mouse(-1, 0)?;
+                        }
                         log::info!("Live reload successful")
                     }
                 };
@@ -165,15 +1587,65 @@ impl Kanata {
 
             self.prev_keys = cur_keys;
         }
+
+        let layer = self.layout.current_layer();
+        if layer != self.prev_layer {
+            self.prev_layer = layer;
+            log::info!("Entered layer: {}", self.current_layer_name());
+            #[cfg(target_os = "windows")]
+            if let Some(notice) = self.layer_change_notice {
+                notice.notice();
+            }
+            if let Some(tx) = &self.layer_change_tx {
+                let _ = tx.send(self.current_layer_name().to_string());
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns the name of the active layer.
+    pub fn current_layer_name(&self) -> &str {
+        &self.layer_names[self.layout.current_layer()]
+    }
+
+    /// Switch the base layer to the layer with the given name like layer-switch.
+    pub fn change_layer(&mut self, name: &str) -> Result<()> {
+        let layer = match self.layer_names.iter().position(|n| n == name) {
+            Some(layer) => layer,
+            None => bail!("unknown layer: {}", name),
+        };
+        self.base_layer = layer;
+        self.toggled_layer = None;
+        self.layout.set_default_layer(layer);
         Ok(())
     }
 
+    /// Reload the configuration file like the live-reload action.
+    #[cfg(target_os = "windows")]
+    pub fn request_live_reload(&mut self) {
+        self.live_reload_requested = true;
+    }
+
     /// This compares the active keys in the keyberon layout against the potential key outputs for
     /// corresponding physical key in the configuration. If any of keyberon active keys match any
     /// potential physical key output, write the repeat event to the OS.
     fn handle_repeat(&mut self, event: &KeyEvent) -> Result<()> {
-        let active_keycodes: HashSet<KeyCode> = self.layout.keycodes().collect();
         let idx: usize = event.code.into();
+        if idx >= cfg::MAPPED_KEYS_LEN || self.key_repeat.mode(idx as u8) != cfg::RepeatMode::Os {
+            return Ok(());
+        }
+        // The virtual keyboard repeats the held keys itself.
+        #[cfg(target_os = "linux")]
+        if self.kbd_out.repeats_keys() {
+            return Ok(());
+        }
+        self.repeat_key_output(idx)
+    }
+
+    /// Write a repeat event for the output of the physical key at index `idx`, if the output is
+    /// active.
+    fn repeat_key_output(&mut self, idx: usize) -> Result<()> {
+        let active_keycodes: HashSet<KeyCode> = self.layout.keycodes().collect();
         let outputs_for_key: &Vec<OsCode> = match &self.key_outputs[idx] {
             None => return Ok(()),
             Some(v) => v,
@@ -194,11 +1666,183 @@ impl Kanata {
         Ok(())
     }
 
+    /// Release the keys of the scrolled mouse wheel directions once they were held long enough, and
+    /// press them again for the queued scrolls.
+    fn tick_mwheel_keys(&mut self) -> Result<()> {
+        let mut events = Vec::new();
+        for state in self.mwheel_keys.iter_mut() {
+            state.ticks_remaining = state.ticks_remaining.saturating_sub(1);
+            if state.ticks_remaining > 0 {
+                continue;
+            }
+            // The key stays released for as long as it is held, so that the layout sees a new
+            // press.
+            state.ticks_remaining = MWHEEL_KEY_HOLD_TIME;
+            if state.held {
+                state.held = false;
+                events.push(KeyEvent::new(state.code, KeyValue::Release));
+            } else if state.queued > 0 {
+                state.queued -= 1;
+                state.held = true;
+                events.push(KeyEvent::new(state.code, KeyValue::Press));
+            }
+        }
+        self.mwheel_keys.retain(|s| s.held || s.queued > 0);
+        for event in events.iter() {
+            self.process_key_event(event)?;
+        }
+        Ok(())
+    }
+
+    /// Returns the direction that a mwheel action scrolls in, taking reverse-scroll into account.
+    fn mwheel_direction(
+        &self,
+        direction: MWheelDirection,
+        reverse: Option<bool>,
+    ) -> MWheelDirection {
+        match reverse.unwrap_or(self.reverse_scroll) {
+            true => direction.reversed(),
+            false => direction,
+        }
+    }
+
+    /// Scroll in the directions of the held mwheel actions once every mwheel-interval.
+    fn tick_mwheel(&mut self) -> Result<()> {
+        if self.mwheel_directions.is_empty() {
+            return Ok(());
+        }
+        self.mwheel_ticks = self.mwheel_ticks.saturating_sub(1);
+        if self.mwheel_ticks > 0 {
+            return Ok(());
+        }
+        self.mwheel_ticks = self.mouse_wheel.interval;
+        for direction in self.mwheel_directions.iter() {
+            self.kbd_out.scroll(*direction, self.mouse_wheel.distance)?;
+        }
+        Ok(())
+    }
+
+    /// Move the mouse pointer in the held movemouse directions by the distance of one millisecond.
+    fn tick_mouse_move(&mut self) -> Result<()> {
+        if self.move_directions.is_empty() {
+            self.move_ticks = 0;
+            self.move_remainder = (0.0, 0.0);
+            return Ok(());
+        }
+        let cfg = &self.mouse_move;
+        let ramp = match cfg.ramp_time {
+            0 => 1.0,
+            t => f64::from(self.move_ticks.min(t)) / f64::from(t),
+        };
+        self.move_ticks = self.move_ticks.saturating_add(1);
+        let mut speed =
+            f64::from(cfg.start_speed) + f64::from(cfg.max_speed - cfg.start_speed) * ramp;
+        for percentage in self.move_speeds.iter() {
+            speed *= f64::from(*percentage) / 100.0;
+        }
+        let distance = speed / 1000.0;
+        let (mut dx, mut dy) = self.move_remainder;
+        for direction in self.move_directions.iter() {
+            match direction {
+                MoveDirection::Up => dy -= distance,
+                MoveDirection::Down => dy += distance,
+                MoveDirection::Left => dx -= distance,
+                MoveDirection::Right => dx += distance,
+            }
+        }
+        let (x, y) = (dx.trunc(), dy.trunc());
+        self.move_remainder = (dx - x, dy - y);
+        if x as i32 != 0 || y as i32 != 0 {
+            self.kbd_out.move_mouse(x as i32, y as i32)?;
+        }
+        Ok(())
+    }
+
+    /// Move the mouse pointer by one pixel or back once every interval of the mouse jiggler.
+    fn tick_jiggler(&mut self) -> Result<()> {
+        let state = match self.jiggler.as_mut() {
+            Some(state) => state,
+            None => return Ok(()),
+        };
+        state.ticks_remaining = state.ticks_remaining.saturating_sub(1);
+        if state.ticks_remaining > 0 {
+            return Ok(());
+        }
+        state.ticks_remaining = state.interval;
+        state.moved = !state.moved;
+        let dx = if state.moved { 1 } else { -1 };
+        log::info!("mouse jiggler: moving the mouse pointer by {} pixel", dx);
+        self.kbd_out.move_mouse(dx, 0)?;
+        Ok(())
+    }
+
+    /// Returns the number of milliseconds after a release of the physical key at `coord` during
+    /// which another press of the key is discarded, because of debounce or bounce-keys.
+    fn bounce_time(&self, coord: u8) -> u16 {
+        let bounce_keys_time = match self.bounce_keys {
+            true => self.bounce_keys_time,
+            false => 0,
+        };
+        self.debounce.time(coord).max(bounce_keys_time)
+    }
+
+    /// Returns whether a press of the physical key at `coord` arrives within the bounce time of its
+    /// previous release.
+    fn is_bounce(&self, coord: u8) -> bool {
+        let bounce_time = self.bounce_time(coord);
+        bounce_time > 0
+            && matches!(self.last_releases.get(&coord),
+                Some(t) if t.elapsed() < time::Duration::from_millis(bounce_time.into()))
+    }
+
+    /// Remember an output that the OS can't repeat as the output of the key repeated by kanata, if
+    /// the key has no such output yet.
+    fn set_key_repeat_action(&mut self, action: LastAction) {
+        if let Some(state) = self.key_repeat_state.as_mut() {
+            if state.action.is_none() {
+                state.action = Some(action);
+            }
+        }
+    }
+
+    /// Advance the key repeat done by kanata by one millisecond and repeat the output of the held
+    /// key when it is time to.
+    fn tick_key_repeat(&mut self) -> Result<()> {
+        let state = match self.key_repeat_state.as_mut() {
+            Some(state) => state,
+            None => return Ok(()),
+        };
+        state.ticks_remaining = state.ticks_remaining.saturating_sub(1);
+        if state.ticks_remaining > 0 {
+            return Ok(());
+        }
+        state.ticks_remaining = self.key_repeat.interval;
+        match state.action {
+            Some(LastAction::Unicode(c)) => self.kbd_out.send_unicode(c, self.text_layout)?,
+            Some(LastAction::Macro(m)) => {
+                // Let the previous repetition finish first.
+                if self.macros.is_empty() {
+                    self.play_macro(m);
+                }
+            }
+            Some(LastAction::Keys(_)) | None => {
+                let coord = state.coord;
+                self.repeat_key_output(coord.into())?;
+            }
+        }
+        Ok(())
+    }
+
     /// Starts a new thread that processes OS key events and advances the keyberon layout's state.
     pub fn start_processing_loop(kanata: Arc<Mutex<Self>>, rx: Receiver<KeyEvent>) {
         info!("Kanata: entering the processing loop");
         std::thread::spawn(move || {
+            // The input devices on Linux are grabbed once their keys are released, but the keyboard
+            // hook on Windows receives the releases of keys that were pressed before kanata
+            // started, which are passed to the OS.
+            #[cfg(target_os = "windows")]
             info!("Init: catching only releases and sending immediately");
+            #[cfg(target_os = "windows")]
             for _ in 0..500 {
                 if let Ok(kev) = rx.try_recv() {
                     if kev.value == KeyValue::Release {
@@ -240,43 +1884,265 @@ impl Kanata {
     }
 
     /// Enter an infinite loop that listens for OS key events and sends them to the processing
-    /// thread.
+    /// thread. The loop ends when kanata is asked to quit by SIGINT or SIGTERM.
     #[cfg(target_os = "linux")]
     pub fn event_loop(kanata: Arc<Mutex<Self>>, tx: Sender<KeyEvent>) -> Result<()> {
         info!("Kanata: entering the event loop");
-        {
-            let mut mapped_keys = MAPPED_KEYS.lock();
-            *mapped_keys = kanata.lock().mapped_keys;
+
+        // All input devices are read by this thread, which waits for their events with epoll. The
+        // devices are checked periodically so that a device that was unplugged is read again once
+        // it is plugged back in, and so that a newly plugged in device is read if it is detected or
+        // matches linux-dev-names.
+        let poll = InputPoll::new()?;
+        let quit = quit_signal()?;
+        poll.add(quit, QUIT_TOKEN)?;
+        poll.add(&kanata.lock().kbd_out, LED_TOKEN)?;
+        // The LED states of the virtual keyboard, which are set on devices when they are opened.
+        let mut leds: HashMap<u16, i32> = HashMap::new();
+        let mut devices: HashMap<u64, InputDevice> = HashMap::new();
+        // Devices that are not grabbed yet because some of their keys are held.
+        let mut pending: HashMap<u64, (PathBuf, PendingKbdIn)> = HashMap::new();
+        let mut next_token = 0;
+        let (paths, selection) = {
+            let kanata = kanata.lock();
+            (kanata.kbd_in_paths.clone(), kanata.kbd_in_selection.clone())
+        };
+        for path in paths {
+            let kbd_in = match PendingKbdIn::new(&path) {
+                Ok(kbd_in) => kbd_in,
+                Err(e) => bail!("failed to open keyboard device {}: {}", path.display(), e),
+            };
+            poll.add(&kbd_in, next_token)?;
+            pending.insert(next_token, (path, kbd_in));
+            next_token += 1;
         }
+        Self::grab_pending_devices(&mut pending, &mut devices, &leds);
 
-        let kbd_in = match KbdIn::new(&kanata.lock().kbd_in_path) {
-            Ok(kbd_in) => kbd_in,
-            Err(e) => {
-                bail!("failed to open keyboard device: {}", e)
+        let mut suspend = SuspendDetector::new();
+        let mut last_rescan = time::Instant::now();
+        loop {
+            let timeout = DEVICE_RESCAN_INTERVAL.saturating_sub(last_rescan.elapsed());
+            for token in poll.wait(timeout)? {
+                if token == QUIT_TOKEN {
+                    info!("Kanata: quitting");
+                    return Ok(());
+                }
+                if token == LED_TOKEN {
+                    for (code, value) in kanata.lock().kbd_out.read_leds()? {
+                        leds.insert(code, value);
+                        for device in devices.values() {
+                            if let Err(e) = device.kbd_in.set_led(code, value) {
+                                log::debug!(
+                                    "failed to set LED of {}: {}",
+                                    device.path.display(),
+                                    e
+                                );
+                            }
+                        }
+                    }
+                    continue;
+                }
+                if pending.contains_key(&token) {
+                    Self::grab_pending_devices(&mut pending, &mut devices, &leds);
+                    continue;
+                }
+                let device = match devices.get_mut(&token) {
+                    Some(device) => device,
+                    None => continue,
+                };
+                if !Self::read_device(&kanata, device, &tx)? {
+                    // Closing the device removes it from the epoll set.
+                    devices.remove(&token);
+                }
             }
-        };
 
+            if last_rescan.elapsed() < DEVICE_RESCAN_INTERVAL {
+                continue;
+            }
+            last_rescan = time::Instant::now();
+            // Devices can come back from a suspend ungrabbed or with a stale file descriptor that
+            // reports no error, so all of them are opened again. The key that woke the system up
+            // may still be held, so they are opened as pending like newly plugged in devices.
+            if suspend.resumed() {
+                info!("Kanata: resumed from suspend, opening the input devices again");
+                for (_, mut device) in devices.drain() {
+                    Self::release_device_keys(&kanata, &mut device, &tx)?;
+                }
+                pending.clear();
+            }
+            for path in selection.devices() {
+                if !path.exists()
+                    || devices.values().any(|device| device.path == path)
+                    || pending.values().any(|(p, _)| *p == path)
+                {
+                    continue;
+                }
+                match PendingKbdIn::open(&path) {
+                    Ok(kbd_in) => {
+                        poll.add(&kbd_in, next_token)?;
+                        pending.insert(next_token, (path, kbd_in));
+                        next_token += 1;
+                    }
+                    Err(e) => log::debug!("failed to open {}: {}", path.display(), e),
+                }
+            }
+            // Also grab the devices whose keys have been held for too long, which send no events.
+            Self::grab_pending_devices(&mut pending, &mut devices, &leds);
+        }
+    }
+
+    /// Grab the pending input devices whose keys are no longer held and start reading them. A
+    /// device that can't be grabbed is closed, which removes it from the epoll set, and is opened
+    /// again by the next scan for devices.
+    #[cfg(target_os = "linux")]
+    fn grab_pending_devices(
+        pending: &mut HashMap<u64, (PathBuf, PendingKbdIn)>,
+        devices: &mut HashMap<u64, InputDevice>,
+        leds: &HashMap<u16, i32>,
+    ) {
+        for (token, (path, kbd_in)) in std::mem::take(pending) {
+            match kbd_in.try_grab() {
+                Ok(Ok(kbd_in)) => {
+                    for (code, value) in leds.iter() {
+                        let _ = kbd_in.set_led(*code, *value);
+                    }
+                    info!("Kanata: reading {} ({})", path.display(), kbd_in.name());
+                    devices.insert(token, InputDevice::new(path, kbd_in));
+                }
+                Ok(Err(kbd_in)) => {
+                    pending.insert(token, (path, kbd_in));
+                }
+                Err(e) => log::error!("failed to grab {}: {}", path.display(), e),
+            }
+        }
+    }
+
+    /// Returns the key that the key of the input device is treated as according to defdevice.
+    #[cfg(target_os = "linux")]
+    fn device_key(&self, path: &std::path::Path, name: &str, code: OsCode) -> OsCode {
+        self.device_keys
+            .iter()
+            .filter(|dk| {
+                dk.devices
+                    .iter()
+                    .any(|item| device_matches(item, path, name))
+            })
+            .find_map(|dk| dk.keys.get(&code).copied())
+            .unwrap_or(code)
+    }
+
+    /// Release the keys that are held on an input device, e.g. when it is unplugged.
+    #[cfg(target_os = "linux")]
+    fn release_device_keys(
+        kanata: &Arc<Mutex<Self>>,
+        device: &mut InputDevice,
+        tx: &Sender<KeyEvent>,
+    ) -> Result<()> {
+        for code in device.held_mapped.drain() {
+            if let Err(e) = tx.send(KeyEvent::new(code, KeyValue::Release)) {
+                bail!("failed to send on channel: {}", e)
+            }
+        }
+        let mut kanata = kanata.lock();
+        for code in device.held_passed.drain() {
+            kanata.kbd_out.release_key(code)?;
+        }
+        Ok(())
+    }
+
+    /// Read the pending events of an input device, passing through the events of keys that are not
+    /// mapped and sending the rest to the processing loop. Returns `false` if the device was
+    /// unplugged, after releasing the keys that were held on it.
+    #[cfg(target_os = "linux")]
+    fn read_device(
+        kanata: &Arc<Mutex<Self>>,
+        device: &mut InputDevice,
+        tx: &Sender<KeyEvent>,
+    ) -> Result<bool> {
         loop {
-            let in_event = kbd_in.read()?;
+            let in_event = match device.kbd_in.read() {
+                Ok(Some(in_event)) => in_event,
+                Ok(None) => return Ok(true),
+                Err(e) => {
+                    log::warn!(
+                        "Kanata: input device {} disconnected: {}",
+                        device.path.display(),
+                        e
+                    );
+                    Self::release_device_keys(kanata, device, tx)?;
+                    return Ok(false);
+                }
+            };
+
+            // A new touch starts at a new position, which is not a movement of the pointer.
+            if let EventCode::EV_KEY(EV_KEY::BTN_TOUCH) = in_event.event_code {
+                device.abs_position = [None, None];
+            }
 
             // Pass-through non-key events
             let key_event = match KeyEvent::try_from(in_event.clone()) {
                 Ok(ev) => ev,
                 _ => {
+                    // Scrolls of the mouse wheel are key presses if their direction is mapped.
+                    if let Some((code, notches)) = mwheel_event_code(&in_event) {
+                        if kanata.lock().mapped_keys[usize::from(code)] {
+                            for _ in 0..notches {
+                                if let Err(e) = tx.send(KeyEvent::new(code, KeyValue::Press)) {
+                                    bail!("failed to send on channel: {}", e)
+                                }
+                            }
+                            continue;
+                        }
+                    }
                     let mut kanata = kanata.lock();
+                    let policy = match in_event.event_type {
+                        EventType::EV_REL => kanata.event_policies.rel,
+                        EventType::EV_ABS => kanata.event_policies.abs,
+                        EventType::EV_MSC => kanata.event_policies.msc,
+                        EventType::EV_SW => kanata.event_policies.sw,
+                        _ => cfg::EventPolicy::Forward,
+                    };
+                    let in_event = match policy {
+                        cfg::EventPolicy::Forward => in_event,
+                        cfg::EventPolicy::Drop => continue,
+                        cfg::EventPolicy::Relative => {
+                            match abs_to_rel(&in_event, &mut device.abs_position) {
+                                Some(ev) => ev,
+                                None => continue,
+                            }
+                        }
+                    };
+                    let in_event = match kanata.reverse_scroll {
+                        true => reverse_wheel(in_event),
+                        false => in_event,
+                    };
                     kanata.kbd_out.write(in_event)?;
                     continue;
                 }
             };
 
+            // Keys of a device in defdevice are treated as other keys.
+            let key_event = KeyEvent {
+                code: kanata
+                    .lock()
+                    .device_key(&device.path, &device.name, key_event.code),
+                ..key_event
+            };
+
             // Check if this keycode is mapped in the configuration. If it hasn't been mapped, send
             // it immediately.
-            let kc: usize = key_event.code.into();
-            if kc >= cfg::MAPPED_KEYS_LEN || !MAPPED_KEYS.lock()[kc] {
+            let kc: usize = key_event.code.to_remappable().into();
+            if kc >= cfg::MAPPED_KEYS_LEN || !kanata.lock().mapped_keys[kc] {
+                track_held(&mut device.held_passed, &key_event);
                 let mut kanata = kanata.lock();
                 kanata.kbd_out.write_key(key_event.code, key_event.value)?;
                 continue;
             }
+            let key_event = KeyEvent {
+                code: key_event.code.to_remappable(),
+                ..key_event
+            };
+            track_held(&mut device.held_mapped, &key_event);
 
             // Send key events to the processing loop
             if let Err(e) = tx.send(key_event) {
@@ -286,69 +2152,469 @@ impl Kanata {
     }
 
     /// Initialize the callback that is passed to the Windows low level hook to receive key events
-    /// and run the native_windows_gui event loop.
+    /// and run the native_windows_gui event loop. Multiple configurations require the
+    /// Interception driver, which tells the keyboards apart.
     #[cfg(target_os = "windows")]
-    pub fn event_loop(kanata: Arc<Mutex<Self>>, tx: Sender<KeyEvent>) -> Result<()> {
-        // Display debug and panic output when launched from a terminal.
-        unsafe {
-            use winapi::um::wincon::*;
-            if AttachConsole(ATTACH_PARENT_PROCESS) != 0 {
-                panic!("Could not attach to console");
-            }
-        };
-        native_windows_gui::init()?;
+    pub fn event_loop(kanatas: Vec<(Arc<Mutex<Self>>, Sender<KeyEvent>)>) -> Result<()> {
+        let (kanata, tx) = kanatas[0].clone();
         {
             let mut mapped_keys = MAPPED_KEYS.lock();
             *mapped_keys = kanata.lock().mapped_keys;
         }
+        native_windows_gui::init()?;
+        let interception = kanata.lock().kbd_out.interception.clone();
+        if kanatas.len() > 1
+            && kanatas
+                .iter()
+                .any(|(k, _)| k.lock().kbd_out.interception.is_none())
+        {
+            bail!("every configuration needs windows-interception yes when multiple configurations are used")
+        }
+        // The service has no desktop for the tray icon, and the keyboard hook receives no keys
+        // there. The tray icon shows the layer of the first configuration.
+        let _tray = match crate::service::is_running() {
+            true if interception.is_none() => {
+                bail!("the Windows service requires windows-interception yes in defcfg")
+            }
+            true => None,
+            false => Some(crate::tray::Tray::build(kanata)?),
+        };
 
-        // This callback should return `false` if the input event is **not** handled by the
-        // callback and `true` if the input event **is** handled by the callback. Returning false
-        // informs the callback caller that the input event should be handed back to the OS for
-        // normal processing.
-        let _kbhook = KeyboardHook::set_input_cb(move |input_event| {
-            if input_event.code as usize >= cfg::MAPPED_KEYS_LEN {
-                return false;
+        let kbhook = match interception {
+            Some(interception) => {
+                std::thread::spawn(move || {
+                    if let Err(e) = Self::interception_event_loop(kanatas, interception) {
+                        log::error!("Interception event loop failed: {}", e);
+                    }
+                });
+                None
             }
-            if !MAPPED_KEYS.lock()[input_event.code as usize] {
-                return false;
+            // This callback should return `false` if the input event is **not** handled by the
+            // callback and `true` if the input event **is** handled by the callback. Returning
+            // false informs the callback caller that the input event should be handed back to the
+            // OS for normal processing.
+            None => {
+                let hook_tx = tx.clone();
+                Some(KeyboardHook::set_input_cb(move |input_event| {
+                    send_input_event(
+                        input_event,
+                        &hook_tx,
+                        &MAPPED_KEYS.lock(),
+                        &mut PRESSED_KEYS.lock(),
+                    )
+                }))
             }
+        };
+        let _unlock_listener = match kbhook {
+            Some(_) => Some(UnlockListener::new(tx)?),
+            None => None,
+        };
 
-            let mut key_event = match KeyEvent::try_from(input_event) {
-                Ok(ev) => ev,
-                _ => return false,
-            };
+        // The event loop runs the tray icon and is also required for the low-level keyboard hook
+        // to work. It ends when exit is selected in the menu of the tray icon.
+        native_windows_gui::dispatch_thread_events();
+        Ok(())
+    }
 
-            // Unlike Linux, Windows does not use a separate value for repeat. However, our code
-            // needs to differentiate between initial press and repeat press.
-            log::debug!("event loop: {:?}", key_event);
-            match key_event.value {
-                KeyValue::Release => {
-                    PRESSED_KEYS.lock().remove(&key_event.code);
+    /// Enter an infinite loop that receives the keys of the keyboards from the Interception
+    /// driver and sends them to the processing thread of the first configuration whose
+    /// windows-interception-hwids match the keyboard. The keys of keyboards that match no
+    /// configuration and keys that are not mapped are passed through.
+    #[cfg(target_os = "windows")]
+    fn interception_event_loop(
+        kanatas: Vec<(Arc<Mutex<Self>>, Sender<KeyEvent>)>,
+        interception: Arc<Interception>,
+    ) -> Result<()> {
+        info!("Kanata: reading keyboards with the Interception driver");
+        let hwids: Vec<Vec<String>> = kanatas
+            .iter()
+            .map(|(k, _)| k.lock().interception_hwids.clone())
+            .collect();
+        let mut device_kanatas = HashMap::new();
+        let mut pressed_keys = vec![HashSet::new(); kanatas.len()];
+        loop {
+            let device = match interception.wait(u32::MAX) {
+                Some(device) => device,
+                None => continue,
+            };
+            let stroke = match interception.receive(device) {
+                Some(stroke) => stroke,
+                None => continue,
+            };
+            let idx = *device_kanatas.entry(device).or_insert_with(|| {
+                let ids = interception.hardware_ids(device);
+                let idx = hwids.iter().position(|hwids| {
+                    hwids.is_empty()
+                        || ids.iter().any(|id| {
+                            hwids
+                                .iter()
+                                .any(|hwid| id.to_uppercase().contains(&hwid.to_uppercase()))
+                        })
+                });
+                match idx {
+                    Some(i) => info!(
+                        "Interception: keyboard {} {:?} is remapped by {}",
+                        device,
+                        ids,
+                        kanatas[i].0.lock().cfg_path.display()
+                    ),
+                    None => info!(
+                        "Interception: keyboard {} {:?} is not remapped",
+                        device, ids
+                    ),
                 }
-                KeyValue::Press => {
-                    if PRESSED_KEYS.lock().contains(&key_event.code) {
-                        key_event.value = KeyValue::Repeat;
-                    } else {
-                        PRESSED_KEYS.lock().insert(key_event.code);
-                    }
+                idx
+            });
+            let handled = match (idx, event_from_stroke(stroke)) {
+                (Some(i), Some(input_event)) => {
+                    let (kanata, tx) = &kanatas[i];
+                    let mapped_keys = {
+                        let mut k = kanata.lock();
+                        // The output of the configuration goes to the keyboard that it last read.
+                        k.kbd_out.interception_device = device;
+                        k.mapped_keys
+                    };
+                    send_input_event(input_event, tx, &mapped_keys, &mut pressed_keys[i])
                 }
-                _ => {}
+                _ => false,
+            };
+            if !handled {
+                interception.send(device, stroke);
             }
+        }
+    }
+}
+
+/// Releases the held keys and reinstalls the keyboard hook when the session is unlocked, e.g.
+/// after Win+L or switching users. Windows doesn't deliver the releases of the keys that were held
+/// while locking, which would stay held in kanata, and may remove the hook while it is locked.
+#[cfg(target_os = "windows")]
+struct UnlockListener {
+    window: native_windows_gui::MessageWindow,
+    handler: native_windows_gui::RawEventHandler,
+}
+
+#[cfg(target_os = "windows")]
+impl UnlockListener {
+    fn new(tx: Sender<KeyEvent>) -> Result<Self> {
+        use winapi::um::wtsapi32::*;
+        const WM_WTSSESSION_CHANGE: u32 = 0x02B1;
+        const WTS_SESSION_UNLOCK: usize = 0x8;
+        // The id of a raw event handler must be larger than 0xFFFF.
+        const HANDLER_ID: usize = 0x10000;
+
+        let mut window = Default::default();
+        native_windows_gui::MessageWindow::builder().build(&mut window)?;
+        let hwnd = window
+            .handle
+            .hwnd()
+            .expect("the message window has a window handle");
+        if unsafe { WTSRegisterSessionNotification(hwnd, NOTIFY_FOR_THIS_SESSION) } == 0 {
+            bail!(
+                "failed to register for session notifications: {}",
+                std::io::Error::last_os_error()
+            )
+        }
+        let handler = native_windows_gui::bind_raw_event_handler(
+            &window.handle,
+            HANDLER_ID,
+            move |_, msg, wparam, _| {
+                if msg == WM_WTSSESSION_CHANGE && wparam == WTS_SESSION_UNLOCK {
+                    info!("Kanata: session unlocked, releasing the held keys");
+                    let held: Vec<OsCode> = PRESSED_KEYS.lock().drain().collect();
+                    for code in held {
+                        if let Err(e) = tx.send(KeyEvent::new(code, KeyValue::Release)) {
+                            log::error!("failed to send on channel: {}", e);
+                        }
+                    }
+                    reinstall_keyboard_hook();
+                }
+                None
+            },
+        )?;
+        Ok(Self { window, handler })
+    }
+}
+
+#[cfg(target_os = "windows")]
+impl Drop for UnlockListener {
+    fn drop(&mut self) {
+        let _ = native_windows_gui::unbind_raw_event_handler(&self.handler);
+        if let Some(hwnd) = self.window.handle.hwnd() {
+            unsafe { winapi::um::wtsapi32::WTSUnRegisterSessionNotification(hwnd) };
+        }
+    }
+}
+
+/// Send a key event of Windows to the processing loop if its key is mapped. Returns whether the
+/// event is handled by kanata, otherwise it should be handed back to the OS. The pressed keys are
+/// tracked to tell repeats from presses.
+#[cfg(target_os = "windows")]
+fn send_input_event(
+    input_event: InputEvent,
+    tx: &Sender<KeyEvent>,
+    mapped_keys: &cfg::MappedKeys,
+    pressed_keys: &mut HashSet<OsCode>,
+) -> bool {
+    if input_event.code as usize >= cfg::MAPPED_KEYS_LEN {
+        return false;
+    }
+    if !mapped_keys[input_event.code as usize] {
+        return false;
+    }
+
+    let mut key_event = match KeyEvent::try_from(input_event) {
+        Ok(ev) => ev,
+        _ => return false,
+    };
+    // The releases of the keys that were pressed before pausing are still handled.
+    if PAUSED.load(std::sync::atomic::Ordering::SeqCst)
+        && !(key_event.value == KeyValue::Release && pressed_keys.contains(&key_event.code))
+    {
+        return false;
+    }
 
-            // Send input_events to the processing loop. Panic if channel somehow gets full or if
-            // channel disconnects. Typing input should never trigger a panic based on the channel
-            // getting full, assuming regular operation of the program and some other bug isn't the
-            // problem. I've tried to crash the program by pressing as many keys on my keyboard at
-            // the same time as I could, but was unable to.
-            if let Err(e) = tx.try_send(key_event) {
-                panic!("failed to send on channel: {:?}", e)
+    // Unlike Linux, Windows does not use a separate value for repeat. However, our code
+    // needs to differentiate between initial press and repeat press.
+    log::debug!("event loop: {:?}", key_event);
+    match key_event.value {
+        KeyValue::Release => {
+            pressed_keys.remove(&key_event.code);
+        }
+        KeyValue::Press => {
+            if pressed_keys.contains(&key_event.code) {
+                key_event.value = KeyValue::Repeat;
+            } else {
+                pressed_keys.insert(key_event.code);
             }
-            true
-        });
+        }
+        _ => {}
+    }
 
-        // The event loop is also required for the low-level keyboard hook to work.
-        native_windows_gui::dispatch_thread_events();
-        Ok(())
+    // Send input_events to the processing loop. Panic if channel somehow gets full or if
+    // channel disconnects. Typing input should never trigger a panic based on the channel
+    // getting full, assuming regular operation of the program and some other bug isn't the
+    // problem. I've tried to crash the program by pressing as many keys on my keyboard at
+    // the same time as I could, but was unable to.
+    if let Err(e) = tx.try_send(key_event) {
+        panic!("failed to send on channel: {:?}", e)
+    }
+    true
+}
+
+/// Add the key of a press to the held keys or remove the key of a release.
+#[cfg(target_os = "linux")]
+fn track_held(held: &mut HashSet<OsCode>, event: &KeyEvent) {
+    match event.value {
+        KeyValue::Press => {
+            held.insert(event.code);
+        }
+        KeyValue::Release => {
+            held.remove(&event.code);
+        }
+        KeyValue::Repeat => {}
+    }
+}
+
+/// Returns whether the key is one of the ctrl, shift, alt, or meta keys.
+fn is_modifier(k: &KeyCode) -> bool {
+    matches!(
+        k,
+        KeyCode::LCtrl
+            | KeyCode::RCtrl
+            | KeyCode::LShift
+            | KeyCode::RShift
+            | KeyCode::LAlt
+            | KeyCode::RAlt
+            | KeyCode::LGui
+            | KeyCode::RGui
+    )
+}
+
+/// Returns whether the condition of a switch branch matches the output keys and the pressed
+/// virtual keys.
+/// Returns the keys of `prev_keys` that are missing from `cur_keys`, in the order that they are
+/// released.
+fn released_keys<'a>(
+    prev_keys: &'a [KeyCode],
+    cur_keys: &'a [KeyCode],
+) -> impl Iterator<Item = &'a KeyCode> {
+    prev_keys.iter().rev().filter(|k| !cur_keys.contains(k))
+}
+
+/// Returns the keys of `cur_keys` that are missing from `prev_keys`, in the order that they are
+/// pressed.
+fn pressed_keys<'a>(
+    prev_keys: &'a [KeyCode],
+    cur_keys: &'a [KeyCode],
+) -> impl Iterator<Item = &'a KeyCode> {
+    cur_keys.iter().filter(|k| !prev_keys.contains(k))
+}
+
+#[test]
+fn caps_word_presses_shift_before_letters_only() {
+    use KeyCode::*;
+    let mut state = CapsWordState {
+        timeout: 5000,
+        ticks_remaining: 5000,
+        shifted: false,
+    };
+    let mut prev_keys: Vec<KeyCode> = Vec::new();
+    let mut events = Vec::new();
+    // a, rolling onto 1 while a is held, then b after releasing both, then b and -.
+    for held in [&[A][..], &[A, Kb1], &[Kb1], &[], &[B], &[B, Minus]] {
+        let mut cur_keys = held.to_vec();
+        assert!(state.apply(&mut cur_keys, &prev_keys));
+        events.extend(released_keys(&prev_keys, &cur_keys).map(|k| (false, *k)));
+        events.extend(pressed_keys(&prev_keys, &cur_keys).map(|k| (true, *k)));
+        prev_keys = cur_keys;
     }
+    assert_eq!(
+        events,
+        [
+            (true, LShift),
+            (true, A),
+            (false, LShift),
+            (true, Kb1),
+            (false, A),
+            (false, Kb1),
+            (true, LShift),
+            (true, B),
+            (false, LShift),
+            (true, Minus),
+        ]
+    );
+    let mut cur_keys = vec![Space];
+    assert!(!state.apply(&mut cur_keys, &prev_keys));
+}
+
+fn switch_cond_matches(cond: &SwitchCond, keys: &[KeyCode], vkeys: &HashSet<u8>) -> bool {
+    match cond {
+        SwitchCond::Key(k) => keys.contains(k),
+        SwitchCond::VirtualKey(vkey) => vkeys.contains(vkey),
+        SwitchCond::And(conds) => conds.iter().all(|c| switch_cond_matches(c, keys, vkeys)),
+        SwitchCond::Or(conds) => conds.iter().any(|c| switch_cond_matches(c, keys, vkeys)),
+        SwitchCond::Not(cond) => !switch_cond_matches(cond, keys, vkeys),
+    }
+}
+
+/// Run a program in the background so that key processing is not blocked while it runs.
+#[cfg(feature = "cmd")]
+fn run_cmd(cmd: &'static [String]) {
+    std::thread::spawn(move || {
+        log::info!("Running cmd: {:?}", cmd);
+        match std::process::Command::new(&cmd[0]).args(&cmd[1..]).output() {
+            Ok(output) => {
+                if !output.status.success() {
+                    log::error!(
+                        "cmd {:?} failed with {}:\n{}",
+                        cmd,
+                        output.status,
+                        String::from_utf8_lossy(&output.stderr)
+                    );
+                }
+            }
+            Err(e) => log::error!("Failed to run cmd {:?}: {}", cmd, e),
+        }
+    });
+}
+
+/// Maximum number of seconds that the program of cmd-output-keys may run before it is killed.
+#[cfg(feature = "cmd")]
+const CMD_OUTPUT_TIMEOUT: time::Duration = time::Duration::from_secs(5);
+
+/// Run a program in the background and send its output without the trailing newline to `tx` once
+/// it finishes. Nothing is sent if the program fails or runs for longer than `CMD_OUTPUT_TIMEOUT`.
+#[cfg(feature = "cmd")]
+fn run_cmd_output(cmd: &'static [String], tx: Sender<String>) {
+    use std::process::{Command, Stdio};
+
+    std::thread::spawn(move || {
+        log::info!("Running cmd: {:?}", cmd);
+        let mut child = match Command::new(&cmd[0])
+            .args(&cmd[1..])
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+        {
+            Ok(child) => child,
+            Err(e) => {
+                log::error!("Failed to run cmd {:?}: {}", cmd, e);
+                return;
+            }
+        };
+        // The output is read while the program runs, so that it doesn't block on a full pipe. It is
+        // received with the same deadline as the exit, because a program started by the program
+        // can keep the pipes open after it exits.
+        let stdout = read_pipe(child.stdout.take().expect("stdout is piped"));
+        let stderr = read_pipe(child.stderr.take().expect("stderr is piped"));
+        let deadline = time::Instant::now() + CMD_OUTPUT_TIMEOUT;
+        let status = loop {
+            match child.try_wait() {
+                Ok(Some(status)) => break status,
+                Ok(None) if time::Instant::now() < deadline => {
+                    std::thread::sleep(time::Duration::from_millis(10));
+                }
+                Ok(None) => {
+                    log::error!(
+                        "cmd {:?} did not finish within {} seconds and was killed",
+                        cmd,
+                        CMD_OUTPUT_TIMEOUT.as_secs()
+                    );
+                    let _ = child.kill();
+                    let _ = child.wait();
+                    return;
+                }
+                Err(e) => {
+                    log::error!("Failed to wait for cmd {:?}: {}", cmd, e);
+                    return;
+                }
+            }
+        };
+        if !status.success() {
+            let stderr = stderr.recv_deadline(deadline).unwrap_or_default();
+            log::error!(
+                "cmd {:?} failed with {}:\n{}",
+                cmd,
+                status,
+                String::from_utf8_lossy(&stderr)
+            );
+            return;
+        }
+        let output = match stdout.recv_deadline(deadline) {
+            Ok(output) => output,
+            Err(_) => {
+                log::error!(
+                    "cmd {:?} exited, but its output was not closed within {} seconds",
+                    cmd,
+                    CMD_OUTPUT_TIMEOUT.as_secs()
+                );
+                return;
+            }
+        };
+        let stdout = String::from_utf8_lossy(&output);
+        let stdout = stdout.strip_suffix('\n').unwrap_or(&stdout);
+        let stdout = stdout.strip_suffix('\r').unwrap_or(stdout);
+        let _ = tx.send(stdout.to_owned());
+    });
 }
+
+/// Read a pipe to its end in a new thread and send the content once the pipe is closed.
+#[cfg(feature = "cmd")]
+fn read_pipe(mut pipe: impl std::io::Read + Send + 'static) -> Receiver<Vec<u8>> {
+    let (tx, rx) = crossbeam_channel::bounded(1);
+    std::thread::spawn(move || {
+        let mut content = Vec::new();
+        let _ = pipe.read_to_end(&mut content);
+        let _ = tx.send(content);
+    });
+    rx
+}
+
+/// The cmd actions can't be parsed without the cmd feature, so there is nothing to run.
+#[cfg(not(feature = "cmd"))]
+fn run_cmd(_cmd: &'static [String]) {}
+
+#[cfg(not(feature = "cmd"))]
+fn run_cmd_output(_cmd: &'static [String], _tx: Sender<String>) {}
@@ -1,8 +1,10 @@
 // This file is taken from the original ktrl project's keys.rs file with modifications.
 
-use evdev_rs::enums::{EventCode, EventType, EV_KEY};
+use evdev_rs::enums::{EventCode, EventType, EV_KEY, EV_REL};
 use evdev_rs::{InputEvent, TimeVal};
 use kanata_keyberon::key_code::*;
+
+use crate::custom_action::MWheelDirection;
 use std::convert::TryFrom;
 
 /// This is a shameless copy of evdev_rs::enums::EV_KEY.
@@ -205,6 +207,12 @@ pub enum OsCode {
     KEY_F22 = 192,
     KEY_F23 = 193,
     KEY_F24 = 194,
+    // The directions of the mouse wheel are keys in defsrc and the layout. These codes, which the
+    // kernel doesn't use, stand in for them.
+    MWHEEL_UP = 195,
+    MWHEEL_DOWN = 196,
+    MWHEEL_LEFT = 197,
+    MWHEEL_RIGHT = 198,
     KEY_PLAYCD = 200,
     KEY_PAUSECD = 201,
     KEY_PROG3 = 202,
@@ -254,6 +262,15 @@ pub enum OsCode {
     KEY_WWAN = 246,
     KEY_RFKILL = 247,
     KEY_MICMUTE = 248,
+    // Mouse buttons have codes above the keys that can be remapped, so these codes, which the
+    // kernel doesn't use, stand in for them in defsrc and the layout.
+    MAPPED_BTN_LEFT = 249,
+    MAPPED_BTN_RIGHT = 250,
+    MAPPED_BTN_MIDDLE = 251,
+    MAPPED_BTN_SIDE = 252,
+    MAPPED_BTN_EXTRA = 253,
+    MAPPED_BTN_FORWARD = 254,
+    MAPPED_BTN_BACK = 255,
     KEY_OK = 352,
     KEY_SELECT = 353,
     KEY_GOTO = 354,
@@ -758,6 +775,10 @@ impl OsCode {
             192 => Some(OsCode::KEY_F22),
             193 => Some(OsCode::KEY_F23),
             194 => Some(OsCode::KEY_F24),
+            195 => Some(OsCode::MWHEEL_UP),
+            196 => Some(OsCode::MWHEEL_DOWN),
+            197 => Some(OsCode::MWHEEL_LEFT),
+            198 => Some(OsCode::MWHEEL_RIGHT),
             200 => Some(OsCode::KEY_PLAYCD),
             201 => Some(OsCode::KEY_PAUSECD),
             202 => Some(OsCode::KEY_PROG3),
@@ -807,6 +828,13 @@ impl OsCode {
             246 => Some(OsCode::KEY_WWAN),
             247 => Some(OsCode::KEY_RFKILL),
             248 => Some(OsCode::KEY_MICMUTE),
+            249 => Some(OsCode::MAPPED_BTN_LEFT),
+            250 => Some(OsCode::MAPPED_BTN_RIGHT),
+            251 => Some(OsCode::MAPPED_BTN_MIDDLE),
+            252 => Some(OsCode::MAPPED_BTN_SIDE),
+            253 => Some(OsCode::MAPPED_BTN_EXTRA),
+            254 => Some(OsCode::MAPPED_BTN_FORWARD),
+            255 => Some(OsCode::MAPPED_BTN_BACK),
             352 => Some(OsCode::KEY_OK),
             353 => Some(OsCode::KEY_SELECT),
             354 => Some(OsCode::KEY_GOTO),
@@ -1115,6 +1143,46 @@ impl OsCode {
             _ => None,
         }
     }
+
+    /// Returns the code that stands in for a mouse button in defsrc and the layout, or the code
+    /// itself for other keys.
+    pub fn to_remappable(self) -> Self {
+        match self {
+            OsCode::BTN_LEFT => OsCode::MAPPED_BTN_LEFT,
+            OsCode::BTN_RIGHT => OsCode::MAPPED_BTN_RIGHT,
+            OsCode::BTN_MIDDLE => OsCode::MAPPED_BTN_MIDDLE,
+            OsCode::BTN_SIDE => OsCode::MAPPED_BTN_SIDE,
+            OsCode::BTN_EXTRA => OsCode::MAPPED_BTN_EXTRA,
+            OsCode::BTN_FORWARD => OsCode::MAPPED_BTN_FORWARD,
+            OsCode::BTN_BACK => OsCode::MAPPED_BTN_BACK,
+            _ => self,
+        }
+    }
+
+    /// Returns the direction of the mouse wheel that the code stands in for, if any.
+    pub fn mwheel_direction(self) -> Option<MWheelDirection> {
+        match self {
+            OsCode::MWHEEL_UP => Some(MWheelDirection::Up),
+            OsCode::MWHEEL_DOWN => Some(MWheelDirection::Down),
+            OsCode::MWHEEL_LEFT => Some(MWheelDirection::Left),
+            OsCode::MWHEEL_RIGHT => Some(MWheelDirection::Right),
+            _ => None,
+        }
+    }
+
+    /// Returns the mouse button that the code stands in for, or the code itself for other keys.
+    pub fn to_original(self) -> Self {
+        match self {
+            OsCode::MAPPED_BTN_LEFT => OsCode::BTN_LEFT,
+            OsCode::MAPPED_BTN_RIGHT => OsCode::BTN_RIGHT,
+            OsCode::MAPPED_BTN_MIDDLE => OsCode::BTN_MIDDLE,
+            OsCode::MAPPED_BTN_SIDE => OsCode::BTN_SIDE,
+            OsCode::MAPPED_BTN_EXTRA => OsCode::BTN_EXTRA,
+            OsCode::MAPPED_BTN_FORWARD => OsCode::BTN_FORWARD,
+            OsCode::MAPPED_BTN_BACK => OsCode::BTN_BACK,
+            _ => self,
+        }
+    }
 }
 
 impl TryFrom<usize> for OsCode {
@@ -1297,6 +1365,13 @@ impl From<KeyCode> for OsCode {
             KeyCode::AltErase => OsCode::KEY_ALTERASE,
             KeyCode::Cancel => OsCode::KEY_CANCEL,
             KeyCode::MediaMute => OsCode::KEY_MICMUTE,
+            // Keyberon has no key codes for mouse buttons, so the key codes that keyboards only
+            // use to report errors and obscure key codes that no OS maps stand in for them.
+            KeyCode::ErrorRollOver => OsCode::BTN_LEFT,
+            KeyCode::PostFail => OsCode::BTN_RIGHT,
+            KeyCode::ErrorUndefined => OsCode::BTN_MIDDLE,
+            KeyCode::Out => OsCode::BTN_SIDE,
+            KeyCode::Oper => OsCode::BTN_EXTRA,
             _ => OsCode::KEY_UNKNOWN,
         }
     }
@@ -1310,7 +1385,7 @@ impl From<&KeyCode> for OsCode {
 
 impl From<OsCode> for KeyCode {
     fn from(item: OsCode) -> KeyCode {
-        match item {
+        match item.to_original() {
             OsCode::KEY_ESC => KeyCode::Escape,
             OsCode::KEY_1 => KeyCode::Kb1,
             OsCode::KEY_2 => KeyCode::Kb2,
@@ -1460,6 +1535,11 @@ impl From<OsCode> for KeyCode {
             OsCode::KEY_ALTERASE => KeyCode::AltErase,
             OsCode::KEY_CANCEL => KeyCode::Cancel,
             OsCode::KEY_MICMUTE => KeyCode::MediaMute,
+            OsCode::BTN_LEFT => KeyCode::ErrorRollOver,
+            OsCode::BTN_RIGHT => KeyCode::PostFail,
+            OsCode::BTN_MIDDLE => KeyCode::ErrorUndefined,
+            OsCode::BTN_SIDE => KeyCode::Out,
+            OsCode::BTN_EXTRA => KeyCode::Oper,
             _ => KeyCode::No,
         }
     }
@@ -1495,6 +1575,7 @@ impl From<EV_KEY> for OsCode {
 
 impl From<OsCode> for EV_KEY {
     fn from(item: OsCode) -> Self {
+        let item = item.to_original();
         evdev_rs::enums::int_to_ev_key(item as u32)
             .unwrap_or_else(|| panic!("Invalid KeyCode: {}", item as u32))
     }
@@ -1548,6 +1629,24 @@ impl TryFrom<InputEvent> for KeyEvent {
     }
 }
 
+/// Returns the code of the mouse wheel direction of a wheel event and its number of notches. High
+/// resolution wheel events have no notches, since the kernel also reports every notch of them as a
+/// normal wheel event.
+pub fn mwheel_event_code(event: &InputEvent) -> Option<(OsCode, u16)> {
+    let (code, value) = match (&event.event_code, event.value) {
+        (EventCode::EV_REL(EV_REL::REL_WHEEL), v) if v > 0 => (OsCode::MWHEEL_UP, v),
+        (EventCode::EV_REL(EV_REL::REL_WHEEL), v) => (OsCode::MWHEEL_DOWN, v),
+        (EventCode::EV_REL(EV_REL::REL_HWHEEL), v) if v > 0 => (OsCode::MWHEEL_RIGHT, v),
+        (EventCode::EV_REL(EV_REL::REL_HWHEEL), v) => (OsCode::MWHEEL_LEFT, v),
+        (EventCode::EV_REL(EV_REL::REL_WHEEL_HI_RES), v) if v > 0 => (OsCode::MWHEEL_UP, 0),
+        (EventCode::EV_REL(EV_REL::REL_WHEEL_HI_RES), _) => (OsCode::MWHEEL_DOWN, 0),
+        (EventCode::EV_REL(EV_REL::REL_HWHEEL_HI_RES), v) if v > 0 => (OsCode::MWHEEL_RIGHT, 0),
+        (EventCode::EV_REL(EV_REL::REL_HWHEEL_HI_RES), _) => (OsCode::MWHEEL_LEFT, 0),
+        _ => return None,
+    };
+    Some((code, value.unsigned_abs().try_into().unwrap_or(u16::MAX)))
+}
+
 impl From<KeyEvent> for InputEvent {
     fn from(item: KeyEvent) -> Self {
         Self {
@@ -131,6 +131,231 @@ pub fn str_to_oscode(s: &str) -> Option<OsCode> {
         "kp7" => OsCode::KEY_KP7,
         "kp8" => OsCode::KEY_KP8,
         "kp9" => OsCode::KEY_KP9,
+        "mlft" => OsCode::BTN_LEFT,
+        "mrgt" => OsCode::BTN_RIGHT,
+        "mmid" => OsCode::BTN_MIDDLE,
+        "mbck" => OsCode::BTN_SIDE,
+        "mfwd" => OsCode::BTN_EXTRA,
+        "gp-south" => OsCode::BTN_SOUTH,
+        "gp-east" => OsCode::BTN_EAST,
+        "gp-north" => OsCode::BTN_NORTH,
+        "gp-west" => OsCode::BTN_WEST,
+        "gp-tl" => OsCode::BTN_TL,
+        "gp-tr" => OsCode::BTN_TR,
+        "gp-tl2" => OsCode::BTN_TL2,
+        "gp-tr2" => OsCode::BTN_TR2,
+        "gp-select" => OsCode::BTN_SELECT,
+        "gp-start" => OsCode::BTN_START,
+        "gp-mode" => OsCode::BTN_MODE,
+        "gp-thumbl" => OsCode::BTN_THUMBL,
+        "gp-thumbr" => OsCode::BTN_THUMBR,
+        "gp-up" => OsCode::BTN_DPAD_UP,
+        "gp-down" => OsCode::BTN_DPAD_DOWN,
+        "gp-left" => OsCode::BTN_DPAD_LEFT,
+        "gp-right" => OsCode::BTN_DPAD_RIGHT,
+        "mwu" => OsCode::MWHEEL_UP,
+        "mwd" => OsCode::MWHEEL_DOWN,
+        "mwl" => OsCode::MWHEEL_LEFT,
+        "mwr" => OsCode::MWHEEL_RIGHT,
         _ => return None,
     })
 }
+
+/// The fixed set of keyboard layouts that text can be typed with. The OS turns the key codes into
+/// characters with its active layout, which kanata does not query, so the configured layout must
+/// match it for the text to come out right.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TextLayout {
+    Us,
+    Uk,
+    De,
+    Fr,
+}
+
+impl TextLayout {
+    pub fn from_name(name: &str) -> Option<Self> {
+        Some(match name {
+            "us" => Self::Us,
+            "uk" => Self::Uk,
+            "de" => Self::De,
+            "fr" => Self::Fr,
+            _ => return None,
+        })
+    }
+
+    /// Returns the letters of the letter keys a to z of the US layout, and the characters of the
+    /// other keys without modifiers, with shift, and with AltGr. `\0` marks a key or level that
+    /// doesn't type a character directly, e.g. a dead key.
+    fn keys(self) -> (&'static str, &'static [(OsCode, &'static str)]) {
+        match self {
+            Self::Us => ("abcdefghijklmnopqrstuvwxyz", US_KEYS),
+            Self::Uk => ("abcdefghijklmnopqrstuvwxyz", UK_KEYS),
+            Self::De => ("abcdefghijklmnopqrstuvwxzy", DE_KEYS),
+            Self::Fr => ("qbcdefghijkl\0noparstuvzxyw", FR_KEYS),
+        }
+    }
+}
+
+const LETTER_KEYS: [OsCode; 26] = [
+    OsCode::KEY_A,
+    OsCode::KEY_B,
+    OsCode::KEY_C,
+    OsCode::KEY_D,
+    OsCode::KEY_E,
+    OsCode::KEY_F,
+    OsCode::KEY_G,
+    OsCode::KEY_H,
+    OsCode::KEY_I,
+    OsCode::KEY_J,
+    OsCode::KEY_K,
+    OsCode::KEY_L,
+    OsCode::KEY_M,
+    OsCode::KEY_N,
+    OsCode::KEY_O,
+    OsCode::KEY_P,
+    OsCode::KEY_Q,
+    OsCode::KEY_R,
+    OsCode::KEY_S,
+    OsCode::KEY_T,
+    OsCode::KEY_U,
+    OsCode::KEY_V,
+    OsCode::KEY_W,
+    OsCode::KEY_X,
+    OsCode::KEY_Y,
+    OsCode::KEY_Z,
+];
+
+const US_KEYS: &[(OsCode, &str)] = &[
+    (OsCode::KEY_GRAVE, "`~"),
+    (OsCode::KEY_1, "1!"),
+    (OsCode::KEY_2, "2@"),
+    (OsCode::KEY_3, "3#"),
+    (OsCode::KEY_4, "4$"),
+    (OsCode::KEY_5, "5%"),
+    (OsCode::KEY_6, "6^"),
+    (OsCode::KEY_7, "7&"),
+    (OsCode::KEY_8, "8*"),
+    (OsCode::KEY_9, "9("),
+    (OsCode::KEY_0, "0)"),
+    (OsCode::KEY_MINUS, "-_"),
+    (OsCode::KEY_EQUAL, "=+"),
+    (OsCode::KEY_LEFTBRACE, "[{"),
+    (OsCode::KEY_RIGHTBRACE, "]}"),
+    (OsCode::KEY_BACKSLASH, "\\|"),
+    (OsCode::KEY_SEMICOLON, ";:"),
+    (OsCode::KEY_APOSTROPHE, "'\""),
+    (OsCode::KEY_COMMA, ",<"),
+    (OsCode::KEY_DOT, ".>"),
+    (OsCode::KEY_SLASH, "/?"),
+];
+
+const UK_KEYS: &[(OsCode, &str)] = &[
+    (OsCode::KEY_GRAVE, "`¬¦"),
+    (OsCode::KEY_1, "1!"),
+    (OsCode::KEY_2, "2\""),
+    (OsCode::KEY_3, "3£"),
+    (OsCode::KEY_4, "4$€"),
+    (OsCode::KEY_5, "5%"),
+    (OsCode::KEY_6, "6^"),
+    (OsCode::KEY_7, "7&"),
+    (OsCode::KEY_8, "8*"),
+    (OsCode::KEY_9, "9("),
+    (OsCode::KEY_0, "0)"),
+    (OsCode::KEY_MINUS, "-_"),
+    (OsCode::KEY_EQUAL, "=+"),
+    (OsCode::KEY_LEFTBRACE, "[{"),
+    (OsCode::KEY_RIGHTBRACE, "]}"),
+    (OsCode::KEY_SEMICOLON, ";:"),
+    (OsCode::KEY_APOSTROPHE, "'@"),
+    (OsCode::KEY_BACKSLASH, "#~"),
+    (OsCode::KEY_102ND, "\\|"),
+    (OsCode::KEY_COMMA, ",<"),
+    (OsCode::KEY_DOT, ".>"),
+    (OsCode::KEY_SLASH, "/?"),
+];
+
+const DE_KEYS: &[(OsCode, &str)] = &[
+    (OsCode::KEY_GRAVE, "\0°"),
+    (OsCode::KEY_1, "1!"),
+    (OsCode::KEY_2, "2\"²"),
+    (OsCode::KEY_3, "3§³"),
+    (OsCode::KEY_4, "4$"),
+    (OsCode::KEY_5, "5%"),
+    (OsCode::KEY_6, "6&"),
+    (OsCode::KEY_7, "7/{"),
+    (OsCode::KEY_8, "8(["),
+    (OsCode::KEY_9, "9)]"),
+    (OsCode::KEY_0, "0=}"),
+    (OsCode::KEY_MINUS, "ß?\\"),
+    (OsCode::KEY_Q, "qQ@"),
+    (OsCode::KEY_E, "eE€"),
+    (OsCode::KEY_M, "mMµ"),
+    (OsCode::KEY_LEFTBRACE, "üÜ"),
+    (OsCode::KEY_RIGHTBRACE, "+*~"),
+    (OsCode::KEY_SEMICOLON, "öÖ"),
+    (OsCode::KEY_APOSTROPHE, "äÄ"),
+    (OsCode::KEY_BACKSLASH, "#'"),
+    (OsCode::KEY_102ND, "<>|"),
+    (OsCode::KEY_COMMA, ",;"),
+    (OsCode::KEY_DOT, ".:"),
+    (OsCode::KEY_SLASH, "-_"),
+];
+
+const FR_KEYS: &[(OsCode, &str)] = &[
+    (OsCode::KEY_GRAVE, "²"),
+    (OsCode::KEY_1, "&1"),
+    (OsCode::KEY_2, "é2"),
+    (OsCode::KEY_3, "\"3#"),
+    (OsCode::KEY_4, "'4{"),
+    (OsCode::KEY_5, "(5["),
+    (OsCode::KEY_6, "-6|"),
+    (OsCode::KEY_7, "è7"),
+    (OsCode::KEY_8, "_8\\"),
+    (OsCode::KEY_9, "ç9^"),
+    (OsCode::KEY_0, "à0@"),
+    (OsCode::KEY_MINUS, ")°]"),
+    (OsCode::KEY_EQUAL, "=+}"),
+    (OsCode::KEY_E, "eE€"),
+    (OsCode::KEY_RIGHTBRACE, "$£¤"),
+    (OsCode::KEY_SEMICOLON, "mM"),
+    (OsCode::KEY_APOSTROPHE, "ù%"),
+    (OsCode::KEY_BACKSLASH, "*µ"),
+    (OsCode::KEY_102ND, "<>"),
+    (OsCode::KEY_M, ",?"),
+    (OsCode::KEY_COMMA, ";."),
+    (OsCode::KEY_DOT, ":/"),
+    (OsCode::KEY_SLASH, "!§"),
+];
+
+/// Convert a `char` to the `OsCode` that types it on the layout and the modifiers that must be
+/// held to type it. Returns `None` for characters that have no key on the layout.
+pub fn char_to_oscode(c: char, layout: TextLayout) -> Option<(OsCode, &'static [OsCode])> {
+    const SHIFT: &[OsCode] = &[OsCode::KEY_LEFTSHIFT];
+    const ALTGR: &[OsCode] = &[OsCode::KEY_RIGHTALT];
+    match c {
+        ' ' => return Some((OsCode::KEY_SPACE, &[])),
+        '\n' => return Some((OsCode::KEY_ENTER, &[])),
+        '\t' => return Some((OsCode::KEY_TAB, &[])),
+        '\0' => return None,
+        _ => {}
+    }
+    let (letters, keys) = layout.keys();
+    if c.is_ascii_alphabetic() {
+        let lower = c.to_ascii_lowercase();
+        if let Some(i) = letters.chars().position(|l| l == lower) {
+            let mods = match c.is_ascii_uppercase() {
+                true => SHIFT,
+                false => &[],
+            };
+            return Some((LETTER_KEYS[i], mods));
+        }
+    }
+    keys.iter().find_map(|(key, chars)| {
+        let mods = match chars.chars().position(|k| k == c)? {
+            0 => &[],
+            1 => SHIFT,
+            _ => ALTGR,
+        };
+        Some((*key, mods))
+    })
+}
@@ -1,5 +1,6 @@
 // This file is adapted from the orginal ktrl's `keys.rs` file for Windows.
 
+use crate::custom_action::MWheelDirection;
 use crate::oskbd::*;
 use kanata_keyberon::key_code::*;
 use std::convert::TryFrom;
@@ -753,6 +754,12 @@ pub enum OsCode {
     BTN_TRIGGER_HAPPY39,
     BTN_TRIGGER_HAPPY40,
     BTN_MAX,
+    // The directions of the mouse wheel. They can only be used as outputs, since the keyboard
+    // hook doesn't receive mouse input.
+    MWHEEL_UP,
+    MWHEEL_DOWN,
+    MWHEEL_LEFT,
+    MWHEEL_RIGHT,
 }
 
 impl OsCode {
@@ -1023,6 +1030,29 @@ impl OsCode {
             _ => 0,
         }
     }
+
+    /// Returns the code that stands in for a mouse button in defsrc and the layout. The keyboard
+    /// hook doesn't receive mouse buttons, so this is always the code itself.
+    pub fn to_remappable(self) -> Self {
+        self
+    }
+
+    /// Returns the direction of the mouse wheel that the code stands in for, if any.
+    pub fn mwheel_direction(self) -> Option<MWheelDirection> {
+        match self {
+            OsCode::MWHEEL_UP => Some(MWheelDirection::Up),
+            OsCode::MWHEEL_DOWN => Some(MWheelDirection::Down),
+            OsCode::MWHEEL_LEFT => Some(MWheelDirection::Left),
+            OsCode::MWHEEL_RIGHT => Some(MWheelDirection::Right),
+            _ => None,
+        }
+    }
+
+    /// Returns the mouse button that the code stands in for. This is always the code itself, see
+    /// `to_remappable`.
+    pub fn to_original(self) -> Self {
+        self
+    }
 }
 
 impl TryFrom<usize> for OsCode {
@@ -1205,6 +1235,13 @@ impl From<KeyCode> for OsCode {
             KeyCode::AltErase => OsCode::KEY_ALTERASE,
             KeyCode::Cancel => OsCode::KEY_CANCEL,
             KeyCode::MediaMute => OsCode::KEY_MICMUTE,
+            // Keyberon has no key codes for mouse buttons, so the key codes that keyboards only
+            // use to report errors and obscure key codes that no OS maps stand in for them.
+            KeyCode::ErrorRollOver => OsCode::BTN_LEFT,
+            KeyCode::PostFail => OsCode::BTN_RIGHT,
+            KeyCode::ErrorUndefined => OsCode::BTN_MIDDLE,
+            KeyCode::Out => OsCode::BTN_SIDE,
+            KeyCode::Oper => OsCode::BTN_EXTRA,
             _ => OsCode::KEY_UNKNOWN,
         }
     }
@@ -1368,6 +1405,11 @@ impl From<OsCode> for KeyCode {
             OsCode::KEY_ALTERASE => KeyCode::AltErase,
             OsCode::KEY_CANCEL => KeyCode::Cancel,
             OsCode::KEY_MICMUTE => KeyCode::MediaMute,
+            OsCode::BTN_LEFT => KeyCode::ErrorRollOver,
+            OsCode::BTN_RIGHT => KeyCode::PostFail,
+            OsCode::BTN_MIDDLE => KeyCode::ErrorUndefined,
+            OsCode::BTN_SIDE => KeyCode::Out,
+            OsCode::BTN_EXTRA => KeyCode::Oper,
             _ => KeyCode::No,
         }
     }
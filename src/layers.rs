@@ -5,6484 +5,22 @@ use crate::custom_action::*;
 
 pub const MAX_LAYERS: usize = 25;
 
-pub type KanataLayers = Layers<256, 1, MAX_LAYERS, CustomAction>;
+/// Number of keys in a row of the layout. The column index of a physical key is its `OsCode`.
+pub const KEYS_IN_ROW: usize = 256;
 
+/// Row 0 of the layout contains the physical keys. Row 1 contains virtual keys: actions that are
+/// only ever activated by kanata itself, e.g. the actions of a tap-dance.
+pub const LAYOUT_ROWS: usize = 2;
+
+/// Row index of the virtual keys.
+pub const VIRTUAL_ROW: u8 = 1;
+
+pub type KanataLayers = Layers<KEYS_IN_ROW, LAYOUT_ROWS, MAX_LAYERS, CustomAction>;
+
+/// Returns the layers before the configuration is applied: the keys of the first layer do nothing
+/// and the keys of the other layers are transparent.
 pub fn new_layers() -> KanataLayers {
-    [
-        [[
-            // layer 0
-            Action::NoOp,
-            Action::NoOp,
-            Action::NoOp,
-            Action::NoOp,
-            Action::NoOp,
-            Action::NoOp,
-            Action::NoOp,
-            Action::NoOp,
-            Action::NoOp,
-            Action::NoOp,
-            Action::NoOp,
-            Action::NoOp,
-            Action::NoOp,
-            Action::NoOp,
-            Action::NoOp,
-            Action::NoOp,
-            Action::NoOp,
-            Action::NoOp,
-            Action::NoOp,
-            Action::NoOp,
-            Action::NoOp,
-            Action::NoOp,
-            Action::NoOp,
-            Action::NoOp,
-            Action::NoOp,
-            Action::NoOp,
-            Action::NoOp,
-            Action::NoOp,
-            Action::NoOp,
-            Action::NoOp,
-            Action::NoOp,
-            Action::NoOp,
-            Action::NoOp,
-            Action::NoOp,
-            Action::NoOp,
-            Action::NoOp,
-            Action::NoOp,
-            Action::NoOp,
-            Action::NoOp,
-            Action::NoOp,
-            Action::NoOp,
-            Action::NoOp,
-            Action::NoOp,
-            Action::NoOp,
-            Action::NoOp,
-            Action::NoOp,
-            Action::NoOp,
-            Action::NoOp,
-            Action::NoOp,
-            Action::NoOp,
-            Action::NoOp,
-            Action::NoOp,
-            Action::NoOp,
-            Action::NoOp,
-            Action::NoOp,
-            Action::NoOp,
-            Action::NoOp,
-            Action::NoOp,
-            Action::NoOp,
-            Action::NoOp,
-            Action::NoOp,
-            Action::NoOp,
-            Action::NoOp,
-            Action::NoOp,
-            Action::NoOp,
-            Action::NoOp,
-            Action::NoOp,
-            Action::NoOp,
-            Action::NoOp,
-            Action::NoOp,
-            Action::NoOp,
-            Action::NoOp,
-            Action::NoOp,
-            Action::NoOp,
-            Action::NoOp,
-            Action::NoOp,
-            Action::NoOp,
-            Action::NoOp,
-            Action::NoOp,
-            Action::NoOp,
-            Action::NoOp,
-            Action::NoOp,
-            Action::NoOp,
-            Action::NoOp,
-            Action::NoOp,
-            Action::NoOp,
-            Action::NoOp,
-            Action::NoOp,
-            Action::NoOp,
-            Action::NoOp,
-            Action::NoOp,
-            Action::NoOp,
-            Action::NoOp,
-            Action::NoOp,
-            Action::NoOp,
-            Action::NoOp,
-            Action::NoOp,
-            Action::NoOp,
-            Action::NoOp,
-            Action::NoOp,
-            Action::NoOp,
-            Action::NoOp,
-            Action::NoOp,
-            Action::NoOp,
-            Action::NoOp,
-            Action::NoOp,
-            Action::NoOp,
-            Action::NoOp,
-            Action::NoOp,
-            Action::NoOp,
-            Action::NoOp,
-            Action::NoOp,
-            Action::NoOp,
-            Action::NoOp,
-            Action::NoOp,
-            Action::NoOp,
-            Action::NoOp,
-            Action::NoOp,
-            Action::NoOp,
-            Action::NoOp,
-            Action::NoOp,
-            Action::NoOp,
-            Action::NoOp,
-            Action::NoOp,
-            Action::NoOp,
-            Action::NoOp,
-            Action::NoOp,
-            Action::NoOp,
-            Action::NoOp,
-            Action::NoOp,
-            Action::NoOp,
-            Action::NoOp,
-            Action::NoOp,
-            Action::NoOp,
-            Action::NoOp,
-            Action::NoOp,
-            Action::NoOp,
-            Action::NoOp,
-            Action::NoOp,
-            Action::NoOp,
-            Action::NoOp,
-            Action::NoOp,
-            Action::NoOp,
-            Action::NoOp,
-            Action::NoOp,
-            Action::NoOp,
-            Action::NoOp,
-            Action::NoOp,
-            Action::NoOp,
-            Action::NoOp,
-            Action::NoOp,
-            Action::NoOp,
-            Action::NoOp,
-            Action::NoOp,
-            Action::NoOp,
-            Action::NoOp,
-            Action::NoOp,
-            Action::NoOp,
-            Action::NoOp,
-            Action::NoOp,
-            Action::NoOp,
-            Action::NoOp,
-            Action::NoOp,
-            Action::NoOp,
-            Action::NoOp,
-            Action::NoOp,
-            Action::NoOp,
-            Action::NoOp,
-            Action::NoOp,
-            Action::NoOp,
-            Action::NoOp,
-            Action::NoOp,
-            Action::NoOp,
-            Action::NoOp,
-            Action::NoOp,
-            Action::NoOp,
-            Action::NoOp,
-            Action::NoOp,
-            Action::NoOp,
-            Action::NoOp,
-            Action::NoOp,
-            Action::NoOp,
-            Action::NoOp,
-            Action::NoOp,
-            Action::NoOp,
-            Action::NoOp,
-            Action::NoOp,
-            Action::NoOp,
-            Action::NoOp,
-            Action::NoOp,
-            Action::NoOp,
-            Action::NoOp,
-            Action::NoOp,
-            Action::NoOp,
-            Action::NoOp,
-            Action::NoOp,
-            Action::NoOp,
-            Action::NoOp,
-            Action::NoOp,
-            Action::NoOp,
-            Action::NoOp,
-            Action::NoOp,
-            Action::NoOp,
-            Action::NoOp,
-            Action::NoOp,
-            Action::NoOp,
-            Action::NoOp,
-            Action::NoOp,
-            Action::NoOp,
-            Action::NoOp,
-            Action::NoOp,
-            Action::NoOp,
-            Action::NoOp,
-            Action::NoOp,
-            Action::NoOp,
-            Action::NoOp,
-            Action::NoOp,
-            Action::NoOp,
-            Action::NoOp,
-            Action::NoOp,
-            Action::NoOp,
-            Action::NoOp,
-            Action::NoOp,
-            Action::NoOp,
-            Action::NoOp,
-            Action::NoOp,
-            Action::NoOp,
-            Action::NoOp,
-            Action::NoOp,
-            Action::NoOp,
-            Action::NoOp,
-            Action::NoOp,
-            Action::NoOp,
-            Action::NoOp,
-            Action::NoOp,
-            Action::NoOp,
-            Action::NoOp,
-            Action::NoOp,
-            Action::NoOp,
-            Action::NoOp,
-            Action::NoOp,
-            Action::NoOp,
-            Action::NoOp,
-            Action::NoOp,
-            Action::NoOp,
-            Action::NoOp,
-            Action::NoOp,
-            Action::NoOp,
-            Action::NoOp,
-            Action::NoOp,
-            Action::NoOp,
-            Action::NoOp,
-            Action::NoOp,
-            Action::NoOp,
-            Action::NoOp,
-            Action::NoOp,
-        ]],
-        [[
-            // layer 1
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-        ]],
-        [[
-            // layer 2
-            Action::NoOp,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-        ]],
-        [[
-            // layer 3
-            Action::NoOp,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-        ]],
-        [[
-            // layer 4
-            Action::NoOp,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-        ]],
-        [[
-            // layer 5
-            Action::NoOp,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-        ]],
-        [[
-            // layer 6
-            Action::NoOp,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-        ]],
-        [[
-            // layer 7
-            Action::NoOp,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-        ]],
-        [[
-            // layer 8
-            Action::NoOp,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-        ]],
-        [[
-            // layer 9
-            Action::NoOp,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-        ]],
-        [[
-            // layer 10
-            Action::NoOp,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-        ]],
-        [[
-            // layer 11
-            Action::NoOp,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-        ]],
-        [[
-            // layer 12
-            Action::NoOp,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-        ]],
-        [[
-            // layer 13
-            Action::NoOp,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-        ]],
-        [[
-            // layer 14
-            Action::NoOp,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-        ]],
-        [[
-            // layer 15
-            Action::NoOp,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-        ]],
-        [[
-            // layer 16
-            Action::NoOp,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-        ]],
-        [[
-            // layer 17
-            Action::NoOp,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-        ]],
-        [[
-            // layer 18
-            Action::NoOp,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-        ]],
-        [[
-            // layer 19
-            Action::NoOp,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-        ]],
-        [[
-            // layer 20
-            Action::NoOp,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-        ]],
-        [[
-            // layer 21
-            Action::NoOp,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-        ]],
-        [[
-            // layer 22
-            Action::NoOp,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-        ]],
-        [[
-            // layer 23
-            Action::NoOp,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-        ]],
-        [[
-            // layer 24
-            Action::NoOp,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-            Action::Trans,
-        ]],
-    ]
+    let mut layers = [[[Action::Trans; KEYS_IN_ROW]; LAYOUT_ROWS]; MAX_LAYERS];
+    layers[0] = [[Action::NoOp; KEYS_IN_ROW]; LAYOUT_ROWS];
+    layers
 }
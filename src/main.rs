@@ -1,79 +1,231 @@
-use anyhow::{bail, Result};
+// kanata runs without a console window on Windows, see --console.
+#![cfg_attr(target_os = "windows", windows_subsystem = "windows")]
+
+use anyhow::{anyhow, bail, Result};
 use log::info;
 use simplelog::*;
+use std::fs::File;
 use std::path::{Path, PathBuf};
 
 mod cfg;
+mod chords;
 mod custom_action;
 mod kanata;
 mod keys;
 mod layers;
 mod oskbd;
+mod overrides;
+mod sequences;
+#[cfg(target_os = "windows")]
+mod service;
+mod socd;
+mod tcp_server;
+#[cfg(target_os = "windows")]
+mod tray;
+mod zippy;
 
 use clap::Parser;
 use kanata::Kanata;
+use parking_lot::Mutex;
+use std::sync::Arc;
 
 type CfgPath = PathBuf;
 
 #[derive(Parser, Debug)]
 #[clap(author, version, about, long_about = None)]
 struct Args {
-    /// Configuration file to use with kanata
-    #[clap(short, long, default_value = "kanata.kbd")]
-    cfg: String,
+    /// Configuration file to use with kanata. This can be given multiple times to run one
+    /// configuration per input device, e.g. a different layout for each keyboard. On Windows, this
+    /// requires the Interception driver.
+    #[clap(short, long, default_value = "kanata.kbd", multiple_occurrences(true))]
+    cfg: Vec<String>,
 
     /// Enable debug logging
     #[clap(short, long)]
     debug: bool,
+
+    /// Validate the configuration files and exit without opening any devices. All errors of each
+    /// invalid file are logged, and the exit code is nonzero if any file is invalid.
+    #[clap(long)]
+    check: bool,
+
+    /// Port of the TCP server that other programs control kanata through. This replaces
+    /// tcp-server-port of the first configuration file.
+    #[clap(short, long)]
+    port: Option<u16>,
+
+    /// Write the log to this file instead of the terminal.
+    #[clap(long)]
+    log_file: Option<PathBuf>,
+
+    /// Show the log in a console. kanata runs without a console window on Windows, so this
+    /// attaches to the console that kanata was started from, or opens a new one.
+    #[cfg(target_os = "windows")]
+    #[clap(long)]
+    console: bool,
+
+    #[cfg(target_os = "windows")]
+    #[clap(subcommand)]
+    service: Option<service::ServiceCommand>,
 }
 
-/// Parse CLI arguments and initialize logging.
-fn cli_init() -> Result<CfgPath> {
-    let args = Args::parse();
+struct ValidatedArgs {
+    paths: Vec<CfgPath>,
+    check: bool,
+    port: Option<u16>,
+    #[cfg(target_os = "windows")]
+    debug: bool,
+    #[cfg(target_os = "windows")]
+    service: Option<service::ServiceCommand>,
+}
 
-    let cfg_path = Path::new(&args.cfg);
+/// Parse CLI arguments and initialize logging.
+fn cli_init() -> Result<ValidatedArgs> {
+    let args = match Args::try_parse() {
+        Ok(args) => args,
+        Err(e) => {
+            // Show the help and the errors of the arguments in the terminal that kanata was
+            // started from.
+            #[cfg(target_os = "windows")]
+            attach_console(false);
+            e.exit()
+        }
+    };
+    #[cfg(target_os = "windows")]
+    if args.console || args.check {
+        attach_console(args.console);
+    }
 
     let log_lvl = match args.debug {
         true => LevelFilter::Debug,
         _ => LevelFilter::Info,
     };
 
-    CombinedLogger::init(vec![TermLogger::new(
-        log_lvl,
-        Config::default(),
-        TerminalMode::Mixed,
-    )])
-    .expect("Couldn't initialize the logger");
+    // The service has no console, so it logs to the event log.
+    #[cfg(target_os = "windows")]
+    let logger: Box<dyn SharedLogger> = match (&args.log_file, args.service) {
+        (Some(path), _) => WriteLogger::new(log_lvl, Config::default(), create_log_file(path)?),
+        (None, Some(service::ServiceCommand::ServiceRun)) => service::EventLogger::new(log_lvl)?,
+        (None, _) => TermLogger::new(log_lvl, Config::default(), TerminalMode::Mixed),
+    };
+    #[cfg(target_os = "linux")]
+    let logger: Box<dyn SharedLogger> = match &args.log_file {
+        Some(path) => WriteLogger::new(log_lvl, Config::default(), create_log_file(path)?),
+        None => TermLogger::new(log_lvl, Config::default(), TerminalMode::Mixed),
+    };
+    CombinedLogger::init(vec![logger]).expect("Couldn't initialize the logger");
+
+    for cfg in args.cfg.iter() {
+        if !Path::new(cfg).exists() {
+            bail!("Could not find your config file ({})", cfg)
+        }
+    }
+
+    Ok(ValidatedArgs {
+        paths: args.cfg.iter().map(PathBuf::from).collect(),
+        check: args.check,
+        port: args.port,
+        #[cfg(target_os = "windows")]
+        debug: args.debug,
+        #[cfg(target_os = "windows")]
+        service: args.service,
+    })
+}
+
+fn create_log_file(path: &Path) -> Result<File> {
+    File::create(path)
+        .map_err(|e| anyhow!("could not create the log file {}: {}", path.display(), e))
+}
+
+/// Attach to the console of the parent process, e.g. the terminal that kanata was started from.
+/// If there is none and `allocate` is true, open a new console window instead.
+#[cfg(target_os = "windows")]
+fn attach_console(allocate: bool) {
+    use winapi::um::consoleapi::AllocConsole;
+    use winapi::um::wincon::{AttachConsole, ATTACH_PARENT_PROCESS};
+    unsafe {
+        if AttachConsole(ATTACH_PARENT_PROCESS) == 0 && allocate {
+            AllocConsole();
+        }
+    }
+}
 
-    if !cfg_path.exists() {
+/// Parse the configuration files without starting kanata, and log all errors of every invalid
+/// file.
+fn check_cfg(cfg_paths: &[CfgPath]) -> Result<()> {
+    let mut invalid = 0;
+    for cfg_path in cfg_paths {
+        if let Err(e) = cfg::Cfg::new_from_file(cfg_path) {
+            log::error!("{}", e);
+            invalid += 1;
+        }
+    }
+    if invalid > 0 {
         bail!(
-            "Could not find your config file ({})",
-            cfg_path.to_str().unwrap_or("?")
+            "{} of {} configuration files are invalid",
+            invalid,
+            cfg_paths.len()
         )
     }
+    info!("Kanata: config is valid");
+    Ok(())
+}
 
-    Ok(cfg_path.into())
+/// Start the TCP servers of the configurations that set tcp-server-port. The port from the command
+/// line replaces the port of the first configuration.
+fn start_tcp_servers(kanatas: &[Arc<Mutex<Kanata>>], port: Option<u16>) -> Result<()> {
+    for (i, kanata) in kanatas.iter().enumerate() {
+        let port = match i {
+            0 => port.or(kanata.lock().tcp_server_port),
+            _ => kanata.lock().tcp_server_port,
+        };
+        if let Some(port) = port {
+            tcp_server::start(port, kanata.clone())?;
+        }
+    }
+    Ok(())
 }
 
 #[cfg(target_os = "linux")]
-fn main_impl(cfg: CfgPath) -> Result<()> {
-    let kanata_arc = Kanata::new_arc(cfg)?;
-    info!("Kanata: config parsed");
+fn main_impl(cfgs: Vec<CfgPath>, port: Option<u16>) -> Result<()> {
+    // Every configuration runs as its own instance of kanata with its own input devices, output
+    // device, and layout.
+    let mut kanatas = Vec::new();
+    for cfg in cfgs {
+        kanatas.push(Kanata::new_arc(cfg)?);
+        info!("Kanata: config parsed");
+    }
+    if kanatas.len() > 1
+        && kanatas
+            .iter()
+            .any(|kanata| kanata.lock().kbd_in_selection.is_detected())
+    {
+        bail!("every configuration needs linux-dev or linux-dev-names when multiple configurations are used")
+    }
+    start_tcp_servers(&kanatas, port)?;
 
-    // Start a processing loop in another thread and run the event loop in this thread.
+    // Start a processing loop and an event loop in other threads for every configuration.
     //
     // The reason for two different event loops is that the "event loop" only listens for keyboard
     // events, which it sends to the "processing loop". The processing loop handles keyboard events
     // while also maintaining `tick()` calls to keyberon.
-    let (tx, rx) = crossbeam_channel::bounded(10);
-    Kanata::start_processing_loop(kanata_arc.clone(), rx);
-    Kanata::event_loop(kanata_arc, tx)?;
+    let (done_tx, done_rx) = crossbeam_channel::unbounded();
+    for kanata_arc in kanatas {
+        let (tx, rx) = crossbeam_channel::bounded(10);
+        Kanata::start_processing_loop(kanata_arc.clone(), rx);
+        let done_tx = done_tx.clone();
+        std::thread::spawn(move || {
+            let _ = done_tx.send(Kanata::event_loop(kanata_arc, tx));
+        });
+    }
+    drop(done_tx);
 
-    Ok(())
+    // Kanata ends when any of the event loops ends, e.g. when it is asked to quit.
+    done_rx.recv()?
 }
 
 #[cfg(target_os = "windows")]
-fn main_impl(cfg: CfgPath) -> Result<()> {
+fn main_impl(cfgs: Vec<CfgPath>, port: Option<u16>) -> Result<()> {
     // Need to use a thread with a larger stack size because Windows appears to have a lower
     // default stack size than Linux, which causes a stack overflow from generating the keyberon
     // Layout struct.
@@ -81,21 +233,40 @@ fn main_impl(cfg: CfgPath) -> Result<()> {
         .name("kanata".into())
         .stack_size(8 * 1024 * 1024); // 8MB of stack space, same as Linux default max
     let handler = builder
-        .spawn(|| {
-            let kanata_arc = Kanata::new_arc(cfg).expect("Could not parse cfg");
-            info!("Kanata: config parsed");
-
-            let (tx, rx) = crossbeam_channel::bounded(10);
-            Kanata::start_processing_loop(kanata_arc.clone(), rx);
-            Kanata::event_loop(kanata_arc, tx).expect("Could not parse cfg");
+        .spawn(move || {
+            // Every configuration has its own processing loop, and the keys of every keyboard
+            // are sent to the processing loop of one configuration by the event loop.
+            let mut kanatas = Vec::new();
+            for cfg in cfgs {
+                let kanata_arc = Kanata::new_arc(cfg)?;
+                info!("Kanata: config parsed");
+                let (tx, rx) = crossbeam_channel::bounded(10);
+                Kanata::start_processing_loop(kanata_arc.clone(), rx);
+                kanatas.push((kanata_arc, tx));
+            }
+            let kanata_arcs: Vec<_> = kanatas.iter().map(|(k, _)| k.clone()).collect();
+            start_tcp_servers(&kanata_arcs, port)?;
+            Kanata::event_loop(kanatas)
         })
         .unwrap();
 
-    handler.join().unwrap();
-    Ok(())
+    handler.join().unwrap()
 }
 
 fn main() -> Result<()> {
     let args = cli_init()?;
-    main_impl(args)
+    if args.check {
+        return check_cfg(&args.paths);
+    }
+    #[cfg(target_os = "windows")]
+    match args.service {
+        Some(service::ServiceCommand::ServiceInstall) => {
+            check_cfg(&args.paths)?;
+            return service::install(&args.paths, args.debug, args.port);
+        }
+        Some(service::ServiceCommand::ServiceUninstall) => return service::uninstall(),
+        Some(service::ServiceCommand::ServiceRun) => return service::run(args.paths, args.port),
+        None => {}
+    }
+    main_impl(args.paths, args.port)
 }
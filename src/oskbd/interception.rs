@@ -0,0 +1,216 @@
+//! Keyboard input and output through the Interception driver.
+//!
+//! The driver receives the keys of the keyboards before Windows does, so unlike the low level
+//! keyboard hook it also intercepts keys for elevated windows and secure desktops and doesn't
+//! delay the input of other applications. interception.dll is loaded at runtime, so kanata runs
+//! without it when the driver is not used.
+//! https://github.com/oblitum/Interception
+
+use std::io;
+use std::mem;
+use std::sync::Arc;
+
+use once_cell::sync::OnceCell;
+
+use winapi::ctypes::*;
+use winapi::shared::minwindef::*;
+use winapi::um::libloaderapi::*;
+
+use super::{scancode_of_vk, vk_of_scancode, InputEvent};
+
+/// Number of a device of the driver. Keyboards are 1 to 10 and mice are 11 to 20.
+pub type Device = c_int;
+
+const FILTER_KEY_ALL: c_ushort = 0xFFFF;
+const KEY_UP: c_ushort = 0x01;
+const KEY_E0: c_ushort = 0x02;
+
+/// InterceptionKeyStroke of the driver.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct KeyStroke {
+    pub code: c_ushort,
+    pub state: c_ushort,
+    pub information: c_uint,
+}
+
+/// InterceptionStroke of the driver, which has the size of the larger mouse stroke.
+#[repr(C)]
+#[derive(Clone, Copy, Default)]
+struct Stroke {
+    key: KeyStroke,
+    padding: [c_int; 3],
+}
+
+type Context = *mut c_void;
+type Predicate = unsafe extern "C" fn(Device) -> c_int;
+
+/// Functions of interception.dll.
+struct Fns {
+    create_context: unsafe extern "C" fn() -> Context,
+    destroy_context: unsafe extern "C" fn(Context),
+    set_filter: unsafe extern "C" fn(Context, Predicate, c_ushort),
+    wait_with_timeout: unsafe extern "C" fn(Context, c_ulong) -> Device,
+    send: unsafe extern "C" fn(Context, Device, *const Stroke, c_uint) -> c_int,
+    receive: unsafe extern "C" fn(Context, Device, *mut Stroke, c_uint) -> c_int,
+    get_hardware_id: unsafe extern "C" fn(Context, Device, *mut c_void, c_uint) -> c_uint,
+    is_keyboard: Predicate,
+}
+
+/// Context of the driver that receives the strokes of all keyboards.
+pub struct Interception {
+    lib: HMODULE,
+    ctx: Context,
+    fns: Fns,
+}
+
+/// The context that is shared by all configurations, since only one context can receive the
+/// strokes of a keyboard.
+static SHARED: OnceCell<Arc<Interception>> = OnceCell::new();
+
+// The functions of the driver can be called from any thread.
+unsafe impl Send for Interception {}
+unsafe impl Sync for Interception {}
+
+impl Interception {
+    /// Load interception.dll and create a context that intercepts all keyboards.
+    pub fn new() -> Result<Self, io::Error> {
+        let dll_name: Vec<u16> = "interception.dll\0".encode_utf16().collect();
+        let lib = unsafe { LoadLibraryW(dll_name.as_ptr()) };
+        if lib.is_null() {
+            return Err(io::Error::new(
+                io::ErrorKind::NotFound,
+                "could not load interception.dll, is the Interception driver installed?",
+            ));
+        }
+        let load = |name: &str| -> Result<FARPROC, io::Error> {
+            let cname = format!("{}\0", name);
+            let f = unsafe { GetProcAddress(lib, cname.as_ptr() as *const c_char) };
+            if f.is_null() {
+                unsafe { FreeLibrary(lib) };
+                return Err(io::Error::new(
+                    io::ErrorKind::NotFound,
+                    format!("{} is missing from interception.dll", name),
+                ));
+            }
+            Ok(f)
+        };
+        // Safety: the function pointers have the signatures of interception.h.
+        let fns = unsafe {
+            Fns {
+                create_context: mem::transmute(load("interception_create_context")?),
+                destroy_context: mem::transmute(load("interception_destroy_context")?),
+                set_filter: mem::transmute(load("interception_set_filter")?),
+                wait_with_timeout: mem::transmute(load("interception_wait_with_timeout")?),
+                send: mem::transmute(load("interception_send")?),
+                receive: mem::transmute(load("interception_receive")?),
+                get_hardware_id: mem::transmute(load("interception_get_hardware_id")?),
+                is_keyboard: mem::transmute(load("interception_is_keyboard")?),
+            }
+        };
+        let ctx = unsafe { (fns.create_context)() };
+        if ctx.is_null() {
+            unsafe { FreeLibrary(lib) };
+            return Err(io::Error::new(
+                io::ErrorKind::Other,
+                "could not create the context of the Interception driver",
+            ));
+        }
+        unsafe { (fns.set_filter)(ctx, fns.is_keyboard, FILTER_KEY_ALL) };
+        Ok(Self { lib, ctx, fns })
+    }
+
+    /// Returns the context of the driver that is shared by all configurations, which is created
+    /// on first use.
+    pub fn shared() -> Result<Arc<Self>, io::Error> {
+        SHARED
+            .get_or_try_init(|| Ok(Arc::new(Self::new()?)))
+            .cloned()
+    }
+
+    /// Wait for a stroke of a keyboard for up to `timeout_ms` and return the keyboard.
+    pub fn wait(&self, timeout_ms: u32) -> Option<Device> {
+        match unsafe { (self.fns.wait_with_timeout)(self.ctx, timeout_ms) } {
+            0 => None,
+            device => Some(device),
+        }
+    }
+
+    /// Receive the stroke of the keyboard that [`Interception::wait`] returned. The stroke is
+    /// dropped unless it is sent again.
+    pub fn receive(&self, device: Device) -> Option<KeyStroke> {
+        let mut stroke = Stroke::default();
+        match unsafe { (self.fns.receive)(self.ctx, device, &mut stroke, 1) } {
+            n if n > 0 => Some(stroke.key),
+            _ => None,
+        }
+    }
+
+    /// Send a stroke as if the keyboard had sent it.
+    pub fn send(&self, device: Device, key: KeyStroke) {
+        let stroke = Stroke {
+            key,
+            ..Default::default()
+        };
+        unsafe { (self.fns.send)(self.ctx, device, &stroke, 1) };
+    }
+
+    /// Send a key event as if the keyboard had sent it.
+    pub fn send_event(&self, device: Device, event: InputEvent) {
+        self.send(device, stroke_from_event(event));
+    }
+
+    /// Returns the hardware IDs of the keyboard, e.g. `HID\VID_046D&PID_C52B&MI_00`.
+    pub fn hardware_ids(&self, device: Device) -> Vec<String> {
+        let mut buf = [0u16; 512];
+        let len = unsafe {
+            (self.fns.get_hardware_id)(
+                self.ctx,
+                device,
+                buf.as_mut_ptr() as *mut c_void,
+                mem::size_of_val(&buf) as c_uint,
+            )
+        };
+        // The IDs are a list of strings that each end with a null character.
+        let len = (len as usize / 2).min(buf.len());
+        String::from_utf16_lossy(&buf[..len])
+            .split('\0')
+            .filter(|id| !id.is_empty())
+            .map(String::from)
+            .collect()
+    }
+}
+
+impl Drop for Interception {
+    fn drop(&mut self) {
+        unsafe {
+            (self.fns.destroy_context)(self.ctx);
+            FreeLibrary(self.lib);
+        }
+    }
+}
+
+/// Returns the event of the virtual key of a stroke, if the scancode has a virtual key.
+pub fn event_from_stroke(stroke: KeyStroke) -> Option<InputEvent> {
+    vk_of_scancode(stroke.code, stroke.state & KEY_E0 != 0).map(|vk| InputEvent {
+        code: vk,
+        up: stroke.state & KEY_UP != 0,
+        time: 0,
+    })
+}
+
+fn stroke_from_event(event: InputEvent) -> KeyStroke {
+    let (scancode, extended) = scancode_of_vk(event.code).unwrap_or_default();
+    let mut state = 0;
+    if event.up {
+        state |= KEY_UP;
+    }
+    if extended {
+        state |= KEY_E0;
+    }
+    KeyStroke {
+        code: scancode,
+        state,
+        information: 0,
+    }
+}
@@ -1,6 +1,11 @@
 // This file contains the original ktrl project's `kbd_in.rs` and `kbd_out.rs` files.
 
 use evdev_rs::enums::EventCode;
+use evdev_rs::enums::EventType;
+use evdev_rs::enums::EV_ABS;
+use evdev_rs::enums::EV_KEY;
+use evdev_rs::enums::EV_REL;
+use evdev_rs::enums::EV_REP;
 use evdev_rs::enums::EV_SYN;
 use evdev_rs::Device;
 use evdev_rs::GrabMode;
@@ -14,30 +19,52 @@ use uinput_sys::uinput_user_dev;
 use crate::custom_action::*;
 use crate::keys::*;
 use libc::c_char;
+use libc::c_int;
 use libc::input_event as raw_event;
 
 // file i/o
 use io::Write;
+use std::collections::HashMap;
 use std::fs::File;
 use std::fs::OpenOptions;
 use std::io;
+use std::os::unix::fs::OpenOptionsExt;
 use std::os::unix::io::AsRawFd;
+use std::os::unix::io::FromRawFd;
+use std::os::unix::io::RawFd;
 use std::path::Path;
+use std::path::PathBuf;
 
 // unsafe
 use std::mem;
 use std::slice;
+use std::sync::atomic::AtomicI32;
+use std::sync::atomic::Ordering;
+
+use once_cell::sync::OnceCell;
 
 // kanata
 use crate::keys::KeyEvent;
 
 pub struct KbdIn {
     device: Device,
+    /// File descriptor of the device, which is owned by `device`.
+    fd: RawFd,
 }
 
-impl KbdIn {
+/// An input device that is open but not grabbed yet, because some of its keys are held, e.g. the
+/// enter key that started kanata from a terminal. Otherwise the release of a held key would go to
+/// kanata instead of the OS, which would see the key as stuck. The device is waited for with epoll
+/// like a grabbed one, so that the release of its keys is noticed without blocking.
+pub struct PendingKbdIn {
+    file: File,
+    path: PathBuf,
+    opened: std::time::Instant,
+}
+
+impl PendingKbdIn {
     pub fn new(dev_path: &Path) -> Result<Self, std::io::Error> {
-        match KbdIn::new_linux(dev_path) {
+        match PendingKbdIn::open(dev_path) {
             Ok(s) => Ok(s),
             Err(e) => {
                 log::error!("Failed to open the input keyboard device. Make sure you've added kanata to the `input` group. E: {}", e);
@@ -46,9 +73,45 @@ impl KbdIn {
         }
     }
 
-    fn new_linux(dev_path: &Path) -> Result<Self, std::io::Error> {
-        let kbd_in_file = File::open(dev_path)?;
-        let mut kbd_in_dev = Device::new_from_fd(kbd_in_file)?;
+    /// Open the device like `new` but without logging a failure, e.g. to retry opening a device
+    /// that was unplugged.
+    pub fn open(dev_path: &Path) -> Result<Self, std::io::Error> {
+        // The device is read without blocking since multiple devices are waited for with epoll.
+        // The device is also written to for setting its LEDs.
+        let file = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .custom_flags(libc::O_NONBLOCK)
+            .open(dev_path)?;
+        if any_key_held(file.as_raw_fd()) {
+            log::info!(
+                "waiting for the keys of {} to be released before grabbing it",
+                dev_path.display()
+            );
+        }
+        Ok(Self {
+            file,
+            path: dev_path.to_owned(),
+            opened: std::time::Instant::now(),
+        })
+    }
+
+    /// Grab the device if none of its keys are held, or if they have been held for longer than
+    /// `GRAB_IDLE_TIMEOUT`. Returns the device back if it is still pending. The events until the
+    /// grab are discarded since the OS has received them already.
+    pub fn try_grab(self) -> Result<Result<KbdIn, Self>, std::io::Error> {
+        discard_pending_events(&self.file);
+        if any_key_held(self.file.as_raw_fd()) {
+            if self.opened.elapsed() < GRAB_IDLE_TIMEOUT {
+                return Ok(Err(self));
+            }
+            log::warn!(
+                "keys of {} are still held, grabbing it anyway",
+                self.path.display()
+            );
+        }
+        let fd = self.file.as_raw_fd();
+        let mut kbd_in_dev = Device::new_from_fd(self.file)?;
 
         // NOTE: This grab-ungrab-grab sequence magically
         // fix an issue I had with my Lenovo Yoga trackpad not working.
@@ -57,27 +120,431 @@ impl KbdIn {
         kbd_in_dev.grab(GrabMode::Ungrab)?;
         kbd_in_dev.grab(GrabMode::Grab)?;
 
-        Ok(KbdIn { device: kbd_in_dev })
+        Ok(Ok(KbdIn {
+            device: kbd_in_dev,
+            fd,
+        }))
+    }
+}
+
+impl KbdIn {
+    /// Turn an LED of the device on or off, e.g. to show the caps lock state of the virtual
+    /// keyboard on the physical keyboard.
+    pub fn set_led(&self, code: u16, value: i32) -> Result<(), io::Error> {
+        let mut led: raw_event = unsafe { mem::zeroed() };
+        led.type_ = uinput_sys::EV_LED as u16;
+        led.code = code;
+        led.value = value;
+        let mut sync: raw_event = unsafe { mem::zeroed() };
+        sync.type_ = uinput_sys::EV_SYN as u16;
+        for ev in [led, sync] {
+            let n = unsafe {
+                libc::write(
+                    self.fd,
+                    (&ev as *const raw_event).cast(),
+                    mem::size_of::<raw_event>(),
+                )
+            };
+            if n < 0 {
+                return Err(io::Error::last_os_error());
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns the name of the device.
+    pub fn name(&self) -> &str {
+        self.device.name().unwrap_or("")
+    }
+
+    /// Returns the next event of the device, or `None` if there are no pending events.
+    pub fn read(&self) -> Result<Option<InputEvent>, std::io::Error> {
+        match self.device.next_event(ReadFlag::NORMAL) {
+            Ok((status, event)) => {
+                std::assert!(status == ReadStatus::Success);
+                Ok(Some(event))
+            }
+            Err(e) if e.kind() == io::ErrorKind::WouldBlock => Ok(None),
+            Err(e) => Err(e),
+        }
+    }
+}
+
+/// How long a device stays pending while its keys are held before it is grabbed anyway.
+const GRAB_IDLE_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(5);
+
+/// Returns whether any key of the device is held, using the EVIOCGKEY ioctl.
+fn any_key_held(fd: RawFd) -> bool {
+    let mut bits = [0u8; uinput_sys::KEY_MAX as usize / 8 + 1];
+    let res = unsafe { libc::ioctl(fd, evdev_ioc_read(0x18, bits.len()) as _, bits.as_mut_ptr()) };
+    res >= 0 && bits.iter().any(|b| *b != 0)
+}
+
+/// Reads and discards the events of a device that is opened without blocking.
+fn discard_pending_events(file: &File) {
+    let mut buf = [0u8; 64 * mem::size_of::<raw_event>()];
+    loop {
+        let n = unsafe { libc::read(file.as_raw_fd(), buf.as_mut_ptr().cast(), buf.len()) };
+        if n <= 0 {
+            break;
+        }
     }
+}
 
-    pub fn read(&self) -> Result<InputEvent, std::io::Error> {
-        let (status, event) = self
-            .device
-            .next_event(ReadFlag::NORMAL | ReadFlag::BLOCKING)?;
-        std::assert!(status == ReadStatus::Success);
-        Ok(event)
+impl AsRawFd for KbdOut {
+    /// Returns the file descriptor of the virtual keyboard, which is readable when the OS changes
+    /// its LEDs.
+    fn as_raw_fd(&self) -> RawFd {
+        self.device.as_raw_fd()
     }
 }
 
+impl AsRawFd for KbdIn {
+    fn as_raw_fd(&self) -> RawFd {
+        self.fd
+    }
+}
+
+impl AsRawFd for PendingKbdIn {
+    fn as_raw_fd(&self) -> RawFd {
+        self.file.as_raw_fd()
+    }
+}
+
+/// Detects that the system was suspended, since CLOCK_BOOTTIME advances during a suspend and
+/// CLOCK_MONOTONIC does not.
+pub struct SuspendDetector {
+    /// Time spent in suspend so far.
+    suspended: std::time::Duration,
+}
+
+impl SuspendDetector {
+    pub fn new() -> Self {
+        Self {
+            suspended: suspended_time(),
+        }
+    }
+
+    /// Returns whether the system was suspended since the previous call.
+    pub fn resumed(&mut self) -> bool {
+        let suspended = suspended_time();
+        // Both clocks are read one after another, so small differences are not a suspend.
+        let resumed = suspended > self.suspended + std::time::Duration::from_secs(1);
+        self.suspended = suspended;
+        resumed
+    }
+}
+
+/// Returns the time that the system spent in suspend since it booted.
+fn suspended_time() -> std::time::Duration {
+    let now = |clock| {
+        let mut ts: libc::timespec = unsafe { mem::zeroed() };
+        unsafe { libc::clock_gettime(clock, &mut ts) };
+        std::time::Duration::new(ts.tv_sec as u64, ts.tv_nsec as u32)
+    };
+    let monotonic = now(libc::CLOCK_MONOTONIC);
+    now(libc::CLOCK_BOOTTIME).saturating_sub(monotonic)
+}
+
+/// Waits for the events of multiple input devices with epoll.
+pub struct InputPoll {
+    epoll: File,
+}
+
+impl InputPoll {
+    pub fn new() -> Result<Self, io::Error> {
+        let fd = unsafe { libc::epoll_create1(libc::EPOLL_CLOEXEC) };
+        if fd < 0 {
+            return Err(io::Error::last_os_error());
+        }
+        Ok(Self {
+            epoll: unsafe { File::from_raw_fd(fd) },
+        })
+    }
+
+    /// Wait for events of `source`, which are reported with `token`.
+    pub fn add(&self, source: &impl AsRawFd, token: u64) -> Result<(), io::Error> {
+        let mut event = libc::epoll_event {
+            events: libc::EPOLLIN as u32,
+            u64: token,
+        };
+        let res = unsafe {
+            libc::epoll_ctl(
+                self.epoll.as_raw_fd(),
+                libc::EPOLL_CTL_ADD,
+                source.as_raw_fd(),
+                &mut event,
+            )
+        };
+        if res < 0 {
+            return Err(io::Error::last_os_error());
+        }
+        Ok(())
+    }
+
+    /// Wait until at least one of the sources has events or the timeout expires. Returns the
+    /// tokens of the sources that have events.
+    pub fn wait(&self, timeout: std::time::Duration) -> Result<Vec<u64>, io::Error> {
+        let mut events = [libc::epoll_event { events: 0, u64: 0 }; 16];
+        let n = unsafe {
+            libc::epoll_wait(
+                self.epoll.as_raw_fd(),
+                events.as_mut_ptr(),
+                events.len() as c_int,
+                timeout.as_millis().min(c_int::MAX as u128) as c_int,
+            )
+        };
+        if n < 0 {
+            let e = io::Error::last_os_error();
+            return match e.kind() {
+                io::ErrorKind::Interrupted => Ok(Vec::new()),
+                _ => Err(e),
+            };
+        }
+        Ok(events[..n as usize].iter().map(|event| event.u64).collect())
+    }
+}
+
+/// Write end of the pipe that the signal handler of `quit_signal` writes to.
+static QUIT_PIPE: AtomicI32 = AtomicI32::new(-1);
+
+/// Read end of the pipe of `quit_signal`.
+static QUIT_SIGNAL: OnceCell<File> = OnceCell::new();
+
+extern "C" fn on_quit_signal(_: c_int) {
+    let fd = QUIT_PIPE.load(Ordering::Relaxed);
+    if fd >= 0 {
+        unsafe { libc::write(fd, [1u8].as_ptr() as *const libc::c_void, 1) };
+    }
+}
+
+/// Returns a file that becomes readable once kanata is asked to quit by SIGINT or SIGTERM, so that
+/// an event loop can wait for it together with the input devices. The file is never read, so it
+/// stays readable for the event loops of all configurations.
+pub fn quit_signal() -> Result<&'static File, io::Error> {
+    QUIT_SIGNAL.get_or_try_init(|| {
+        let mut fds = [0; 2];
+        if unsafe { libc::pipe2(fds.as_mut_ptr(), libc::O_CLOEXEC | libc::O_NONBLOCK) } < 0 {
+            return Err(io::Error::last_os_error());
+        }
+        QUIT_PIPE.store(fds[1], Ordering::Relaxed);
+        let handler = on_quit_signal as extern "C" fn(c_int) as libc::sighandler_t;
+        unsafe {
+            libc::signal(libc::SIGINT, handler);
+            libc::signal(libc::SIGTERM, handler);
+            Ok(File::from_raw_fd(fds[0]))
+        }
+    })
+}
+
+/// Event codes besides keys that the input devices can send. The virtual devices advertise them, so
+/// that the events that pass through kanata are not dropped.
+#[derive(Default)]
+pub struct DeviceCapabilities {
+    rel: Vec<c_int>,
+    abs: Vec<(c_int, libc::input_absinfo)>,
+    msc: Vec<c_int>,
+    sw: Vec<c_int>,
+    led: Vec<c_int>,
+    snd: Vec<c_int>,
+}
+
+impl DeviceCapabilities {
+    /// Returns the combined capabilities of the input devices. Devices that can't be opened are
+    /// left out.
+    pub fn of_devices(paths: &[PathBuf]) -> Self {
+        let mut caps = Self::default();
+        for path in paths {
+            let file = match File::open(path) {
+                Ok(file) => file,
+                Err(e) => {
+                    log::debug!("failed to open {}: {}", path.display(), e);
+                    continue;
+                }
+            };
+            let fd = file.as_raw_fd();
+            let add = |codes: &mut Vec<c_int>, new_codes: Vec<c_int>| {
+                for code in new_codes {
+                    if !codes.contains(&code) {
+                        codes.push(code);
+                    }
+                }
+            };
+            add(
+                &mut caps.rel,
+                event_codes(fd, uinput_sys::EV_REL, uinput_sys::REL_MAX),
+            );
+            add(
+                &mut caps.msc,
+                event_codes(fd, uinput_sys::EV_MSC, uinput_sys::MSC_MAX),
+            );
+            add(
+                &mut caps.sw,
+                event_codes(fd, uinput_sys::EV_SW, uinput_sys::SW_MAX),
+            );
+            add(
+                &mut caps.led,
+                event_codes(fd, uinput_sys::EV_LED, uinput_sys::LED_MAX),
+            );
+            add(&mut caps.snd, event_codes(fd, uinput_sys::EV_SND, SND_MAX));
+            for code in event_codes(fd, uinput_sys::EV_ABS, uinput_sys::ABS_MAX) {
+                if caps.abs.iter().any(|(c, _)| *c == code) {
+                    continue;
+                }
+                if let Some(info) = abs_info(fd, code) {
+                    caps.abs.push((code, info));
+                }
+            }
+        }
+        caps
+    }
+}
+
+const SND_MAX: c_int = 0x07;
+
+/// Returns the number of a reading evdev ioctl, like the `_IOR('E', nr, size)` C macro.
+fn evdev_ioc_read(nr: c_int, size: usize) -> u64 {
+    const IOC_READ: u64 = 2;
+    (IOC_READ << 30) | ((size as u64) << 16) | (u64::from(b'E') << 8) | nr as u64
+}
+
+/// Returns the event codes of the type that the device supports, using the EVIOCGBIT ioctl.
+fn event_codes(fd: RawFd, ev_type: c_int, max: c_int) -> Vec<c_int> {
+    let mut bits = [0u8; 96];
+    let len = max as usize / 8 + 1;
+    let res = unsafe {
+        libc::ioctl(
+            fd,
+            evdev_ioc_read(0x20 + ev_type, len) as _,
+            bits.as_mut_ptr(),
+        )
+    };
+    if res < 0 {
+        return Vec::new();
+    }
+    (0..=max)
+        .filter(|code| bits[*code as usize / 8] & (1 << (code % 8)) != 0)
+        .collect()
+}
+
+/// Returns the range of an absolute axis of the device, using the EVIOCGABS ioctl.
+fn abs_info(fd: RawFd, code: c_int) -> Option<libc::input_absinfo> {
+    let mut info: libc::input_absinfo = unsafe { mem::zeroed() };
+    let size = mem::size_of::<libc::input_absinfo>();
+    let res = unsafe { libc::ioctl(fd, evdev_ioc_read(0x40 + code, size) as _, &mut info) };
+    match res {
+        0.. => Some(info),
+        _ => None,
+    }
+}
+
+/// Name and IDs of the virtual devices of kanata. The mouse and pointer devices get the name with
+/// a suffix.
+#[derive(Debug, Clone)]
+pub struct UinputDevice {
+    pub name: String,
+    pub vendor: u16,
+    pub product: u16,
+    /// Milliseconds between the press of a key and its first repeat by the kernel. The virtual
+    /// keyboard has no repeat settings of its own unless this or `repeat_rate` is set.
+    pub repeat_delay: Option<u16>,
+    /// Repeats per second by the kernel.
+    pub repeat_rate: Option<u16>,
+}
+
+impl Default for UinputDevice {
+    fn default() -> Self {
+        Self {
+            name: "kanata".into(),
+            vendor: 0x1,
+            product: 0x1,
+            repeat_delay: None,
+            repeat_rate: None,
+        }
+    }
+}
+
+impl UinputDevice {
+    /// Returns whether the kernel repeats the held keys of the virtual keyboard.
+    pub fn repeats_keys(&self) -> bool {
+        self.repeat_delay.is_some() || self.repeat_rate.is_some()
+    }
+}
+
+/// How a unicode character is entered. With the keys backend, the key combos of the trigger are
+/// tapped, then the hex digits of the character, then the key combos of the terminator. Each combo
+/// is a list of keys that are held together.
+#[derive(Debug, Clone)]
+pub struct UnicodeMethod {
+    pub backend: UnicodeBackend,
+    pub trigger: Vec<Vec<OsCode>>,
+    pub terminator: Vec<Vec<OsCode>>,
+    /// The keys of the compose key of the OS, which are held together.
+    pub compose_key: Vec<OsCode>,
+    /// Characters that are entered with the compose key followed by the characters of their
+    /// compose sequence, regardless of the backend.
+    pub compose: HashMap<char, Vec<char>>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UnicodeBackend {
+    /// Type the hex code of the character with the virtual keyboard.
+    Keys,
+    /// Run wtype, which types the character with the virtual keyboard protocol of Wayland and a
+    /// keymap that contains the character. It works in applications that don't understand the
+    /// hex code, but only on compositors with the protocol, e.g. wlroots based ones.
+    Wtype,
+}
+
+impl Default for UnicodeMethod {
+    /// Ctrl+Shift+U and enter, which IBus and GTK understand.
+    fn default() -> Self {
+        Self {
+            backend: UnicodeBackend::Keys,
+            trigger: vec![vec![
+                OsCode::KEY_LEFTCTRL,
+                OsCode::KEY_LEFTSHIFT,
+                OsCode::KEY_U,
+            ]],
+            terminator: vec![vec![OsCode::KEY_ENTER]],
+            compose_key: Vec::new(),
+            compose: HashMap::new(),
+        }
+    }
+}
+
+/// LEDs of num lock, caps lock, scroll lock, compose, and kana.
+const LOCK_LEDS: [c_int; 5] = [0, 1, 2, 3, 4];
+
+/// Names of the virtual devices that this process created, which are never read as input devices.
+static OUTPUT_DEVICE_NAMES: parking_lot::Mutex<Vec<String>> = parking_lot::const_mutex(Vec::new());
+
 pub struct KbdOut {
     device: File,
+    /// The key combos that enter a unicode character.
+    pub unicode_method: UnicodeMethod,
+    /// Name and IDs of the virtual devices.
+    uinput: UinputDevice,
+    /// Device for mouse buttons, movement, and scrolling. Some desktops handle pointer events of a
+    /// keyboard device oddly, so they are sent by a separate mouse device.
+    mouse_device: File,
+    /// Absolute pointer device for the setmouse action, which is created when it is first used.
+    abs_device: Option<File>,
+    /// Device for the gamepad buttons and axes, which is created when it is first used so that
+    /// games don't see a gamepad otherwise.
+    gamepad_device: Option<File>,
+    /// Vertical and horizontal high resolution scroll distances that don't add up to a notch yet.
+    wheel_remainder: (i32, i32),
 }
 
 impl KbdOut {
-    pub fn new() -> Result<Self, io::Error> {
+    pub fn new(uinput: &UinputDevice, caps: &DeviceCapabilities) -> Result<Self, io::Error> {
+        // The LED changes of the virtual keyboard are read without blocking, like the input
+        // devices.
         let mut uinput_out_file = OpenOptions::new()
             .read(true)
             .write(true)
+            .custom_flags(libc::O_NONBLOCK)
             .open("/dev/uinput")?;
 
         unsafe {
@@ -85,22 +552,51 @@ impl KbdOut {
             uinput_sys::ui_set_evbit(uinput_out_file.as_raw_fd(), uinput_sys::EV_KEY);
 
             for key in 0..uinput_sys::KEY_MAX {
-                uinput_sys::ui_set_keybit(uinput_out_file.as_raw_fd(), key);
+                if !is_mouse_btn(key as u32) {
+                    uinput_sys::ui_set_keybit(uinput_out_file.as_raw_fd(), key);
+                }
             }
 
-            let mut uidev: uinput_user_dev = mem::zeroed();
-
-            const PROG_NAME: &[u8] = "kanata".as_bytes();
-            let copy_len = std::cmp::min(PROG_NAME.len(), uidev.name.len());
-            assert!(copy_len <= uidev.name.len());
-            for (i, c) in PROG_NAME.iter().copied().enumerate().take(copy_len) {
-                uidev.name[i] = c as c_char;
+            let fd = uinput_out_file.as_raw_fd();
+            let mut uidev = uinput_device(&uinput.name, uinput.vendor, uinput.product);
+            if !caps.abs.is_empty() {
+                uinput_sys::ui_set_evbit(fd, uinput_sys::EV_ABS);
+            }
+            for (code, info) in caps.abs.iter() {
+                uinput_sys::ui_set_absbit(fd, *code);
+                let code = *code as usize;
+                uidev.absmin[code] = info.minimum;
+                uidev.absmax[code] = info.maximum;
+                uidev.absfuzz[code] = info.fuzz;
+                uidev.absflat[code] = info.flat;
+            }
+            if !caps.msc.is_empty() {
+                uinput_sys::ui_set_evbit(fd, uinput_sys::EV_MSC);
+            }
+            for code in caps.msc.iter() {
+                uinput_sys::ui_set_mscbit(fd, *code);
+            }
+            if !caps.sw.is_empty() {
+                uinput_sys::ui_set_evbit(fd, uinput_sys::EV_SW);
+            }
+            for code in caps.sw.iter() {
+                uinput_sys::ui_set_swbit(fd, *code);
+            }
+            // The lock key LEDs are always supported, so that the OS reports their state to
+            // kanata, which sets them on the input devices.
+            uinput_sys::ui_set_evbit(fd, uinput_sys::EV_LED);
+            for code in LOCK_LEDS.iter().chain(caps.led.iter()) {
+                uinput_sys::ui_set_ledbit(fd, *code);
+            }
+            if !caps.snd.is_empty() {
+                uinput_sys::ui_set_evbit(fd, uinput_sys::EV_SND);
+            }
+            for code in caps.snd.iter() {
+                uinput_sys::ui_set_sndbit(fd, *code);
+            }
+            if uinput.repeats_keys() {
+                uinput_sys::ui_set_evbit(fd, uinput_sys::EV_REP);
             }
-
-            uidev.id.bustype = 0x3; // BUS_USB
-            uidev.id.vendor = 0x1;
-            uidev.id.product = 0x1;
-            uidev.id.version = 1;
 
             let uidev_bytes =
                 slice::from_raw_parts(mem::transmute(&uidev), mem::size_of::<uinput_user_dev>());
@@ -108,23 +604,94 @@ impl KbdOut {
             uinput_sys::ui_dev_create(uinput_out_file.as_raw_fd());
         }
 
+        // The kernel uses its default repeat settings for a new device, which are changed by
+        // writing repeat events to the device.
+        let time = TimeVal {
+            tv_sec: 0,
+            tv_usec: 0,
+        };
+        if let Some(delay) = uinput.repeat_delay {
+            write_event(
+                &mut uinput_out_file,
+                InputEvent::new(&time, &EventCode::EV_REP(EV_REP::REP_DELAY), delay.into()),
+            )?;
+        }
+        if let Some(rate) = uinput.repeat_rate {
+            write_event(
+                &mut uinput_out_file,
+                InputEvent::new(
+                    &time,
+                    &EventCode::EV_REP(EV_REP::REP_PERIOD),
+                    (1000 / rate).into(),
+                ),
+            )?;
+        }
+
         Ok(KbdOut {
             device: uinput_out_file,
+            unicode_method: UnicodeMethod::default(),
+            mouse_device: new_mouse(uinput, &caps.rel)?,
+            uinput: uinput.clone(),
+            abs_device: None,
+            gamepad_device: None,
+            wheel_remainder: (0, 0),
         })
     }
 
-    pub fn write(&mut self, event: InputEvent) -> Result<(), io::Error> {
-        let ev = event.as_raw();
+    /// Returns whether the kernel repeats the held keys of the virtual keyboard.
+    pub fn repeats_keys(&self) -> bool {
+        self.uinput.repeats_keys()
+    }
 
-        unsafe {
-            let ev_bytes = slice::from_raw_parts(
-                mem::transmute(&ev as *const raw_event),
-                mem::size_of::<raw_event>(),
-            );
-            self.device.write_all(ev_bytes)?;
-        };
+    /// Returns the LED changes that the OS requested for the virtual keyboard, e.g. when caps lock
+    /// is toggled, as pairs of the LED code and its value.
+    pub fn read_leds(&mut self) -> Result<Vec<(u16, i32)>, io::Error> {
+        let mut leds = Vec::new();
+        loop {
+            let mut ev: raw_event = unsafe { mem::zeroed() };
+            let n = unsafe {
+                libc::read(
+                    self.device.as_raw_fd(),
+                    (&mut ev as *mut raw_event).cast(),
+                    mem::size_of::<raw_event>(),
+                )
+            };
+            if n < 0 {
+                let e = io::Error::last_os_error();
+                return match e.kind() {
+                    io::ErrorKind::WouldBlock => Ok(leds),
+                    _ => Err(e),
+                };
+            }
+            if n as usize != mem::size_of::<raw_event>() {
+                return Ok(leds);
+            }
+            if c_int::from(ev.type_) == uinput_sys::EV_LED {
+                leds.push((ev.code, ev.value));
+            }
+        }
+    }
 
-        Ok(())
+    pub fn write(&mut self, event: InputEvent) -> Result<(), io::Error> {
+        match event.event_type {
+            // The sync event ends the events of both devices. The kernel drops a sync event of a
+            // device that has no events to report.
+            EventType::EV_SYN => {
+                write_event(&mut self.mouse_device, event.clone())?;
+                write_event(&mut self.device, event)
+            }
+            EventType::EV_REL => write_event(&mut self.mouse_device, event),
+            EventType::EV_KEY if is_mouse_btn(event.as_raw().code.into()) => {
+                write_event(&mut self.mouse_device, event)
+            }
+            // The gamepad has no events of other devices, so its events are synced right away.
+            EventType::EV_KEY if GAMEPAD_BUTTONS.contains(&event.as_raw().code.into()) => {
+                let device = self.gamepad()?;
+                write_event(device, event)?;
+                write_event(device, sync_event())
+            }
+            _ => write_event(&mut self.device, event),
+        }
     }
 
     pub fn write_key(&mut self, key: OsCode, value: KeyValue) -> Result<(), io::Error> {
@@ -132,18 +699,11 @@ impl KbdOut {
         let input_ev = key_ev.into();
         log::debug!("input ev: {:?}", input_ev);
         self.write(input_ev)?;
+        self.write_sync()
+    }
 
-        let sync = InputEvent::new(
-            &TimeVal {
-                tv_sec: 0,
-                tv_usec: 0,
-            },
-            &EventCode::EV_SYN(EV_SYN::SYN_REPORT),
-            0,
-        );
-        self.write(sync)?;
-
-        Ok(())
+    fn write_sync(&mut self) -> Result<(), io::Error> {
+        self.write(sync_event())
     }
 
     pub fn press_key(&mut self, key: OsCode) -> Result<(), io::Error> {
@@ -154,43 +714,593 @@ impl KbdOut {
         self.write_key(key, KeyValue::Release)
     }
 
-    /// Send using C-S-u + <unicode hex number> + ret
-    pub fn send_unicode(&mut self, c: char) -> Result<(), io::Error> {
+    /// Send using the trigger of the unicode method + <unicode hex number> + the terminator. The
+    /// keys of the hex digits are looked up in the text layout.
+    pub fn send_unicode(&mut self, c: char, layout: TextLayout) -> Result<(), io::Error> {
+        if let Some(seq) = self.unicode_method.compose.get(&c).cloned() {
+            let compose_key = self.unicode_method.compose_key.clone();
+            self.tap_combo(&compose_key)?;
+            for c in seq {
+                self.tap_char(c, layout)?;
+            }
+            return Ok(());
+        }
+        if self.unicode_method.backend == UnicodeBackend::Wtype {
+            return send_unicode_wtype(c.encode_utf8(&mut [0; 4]));
+        }
         let hex = format!("{:x}", c as u32);
-        self.press_key(OsCode::KEY_LEFTCTRL)?;
-        self.press_key(OsCode::KEY_LEFTSHIFT)?;
-        self.press_key(OsCode::KEY_U)?;
-        self.release_key(OsCode::KEY_U)?;
-        self.release_key(OsCode::KEY_LEFTSHIFT)?;
-        self.release_key(OsCode::KEY_LEFTCTRL)?;
-        let mut s = String::new();
+        for combo in self.unicode_method.trigger.clone() {
+            self.tap_combo(&combo)?;
+        }
         for c in hex.chars() {
-            s.push(c);
-            let osc = str_to_oscode(&s).expect("invalid char in unicode output");
-            s.clear();
-            self.press_key(osc)?;
-            self.release_key(osc)?;
-        }
-        self.press_key(OsCode::KEY_ENTER)?;
-        self.release_key(OsCode::KEY_ENTER)?;
+            self.tap_char(c, layout)?;
+        }
+        for combo in self.unicode_method.terminator.clone() {
+            self.tap_combo(&combo)?;
+        }
+        Ok(())
+    }
+
+    /// Send the characters like `send_unicode`. With the wtype backend, the characters between
+    /// those entered with the compose key are typed by one run of wtype instead of one run each.
+    pub fn send_unicode_str(&mut self, s: &str, layout: TextLayout) -> Result<(), io::Error> {
+        if self.unicode_method.backend != UnicodeBackend::Wtype {
+            for c in s.chars() {
+                self.send_unicode(c, layout)?;
+            }
+            return Ok(());
+        }
+        let mut batch = String::new();
+        for c in s.chars() {
+            if self.unicode_method.compose.contains_key(&c) {
+                send_unicode_wtype(&std::mem::take(&mut batch))?;
+                self.send_unicode(c, layout)?;
+            } else {
+                batch.push(c);
+            }
+        }
+        send_unicode_wtype(&batch)
+    }
+
+    /// Type the character with its key in the layout, which must have one.
+    fn tap_char(&mut self, c: char, layout: TextLayout) -> Result<(), io::Error> {
+        let (osc, mods) = char_to_oscode(c, layout).expect("no key for char in unicode output");
+        for m in mods {
+            self.press_key(*m)?;
+        }
+        self.press_key(osc)?;
+        self.release_key(osc)?;
+        for m in mods {
+            self.release_key(*m)?;
+        }
+        Ok(())
+    }
+
+    /// Press the keys in order and release them in reverse order.
+    fn tap_combo(&mut self, keys: &[OsCode]) -> Result<(), io::Error> {
+        for key in keys {
+            self.press_key(*key)?;
+        }
+        for key in keys.iter().rev() {
+            self.release_key(*key)?;
+        }
         Ok(())
     }
 
-    pub fn click_btn(&mut self, btn: Btn) -> Result<(), io::Error> {
-        self.press_key(btn.into())
+    /// Scroll the mouse wheel by a distance in units of 1/120 of a notch. Applications that don't
+    /// support high resolution scrolling receive a notch whenever the distance adds up to one.
+    pub fn scroll(&mut self, direction: MWheelDirection, distance: u16) -> Result<(), io::Error> {
+        let distance = i32::from(distance);
+        let (hi_res_code, code, remainder, value) = match direction {
+            MWheelDirection::Up => (
+                EV_REL::REL_WHEEL_HI_RES,
+                EV_REL::REL_WHEEL,
+                &mut self.wheel_remainder.0,
+                distance,
+            ),
+            MWheelDirection::Down => (
+                EV_REL::REL_WHEEL_HI_RES,
+                EV_REL::REL_WHEEL,
+                &mut self.wheel_remainder.0,
+                -distance,
+            ),
+            MWheelDirection::Left => (
+                EV_REL::REL_HWHEEL_HI_RES,
+                EV_REL::REL_HWHEEL,
+                &mut self.wheel_remainder.1,
+                -distance,
+            ),
+            MWheelDirection::Right => (
+                EV_REL::REL_HWHEEL_HI_RES,
+                EV_REL::REL_HWHEEL,
+                &mut self.wheel_remainder.1,
+                distance,
+            ),
+        };
+        *remainder += value;
+        let notches = *remainder / 120;
+        *remainder -= notches * 120;
+        let time = TimeVal {
+            tv_sec: 0,
+            tv_usec: 0,
+        };
+        self.write(InputEvent::new(
+            &time,
+            &EventCode::EV_REL(hi_res_code),
+            value,
+        ))?;
+        if notches != 0 {
+            self.write(InputEvent::new(&time, &EventCode::EV_REL(code), notches))?;
+        }
+        self.write_sync()
+    }
+
+    /// Move the mouse pointer by a distance in pixels.
+    pub fn move_mouse(&mut self, dx: i32, dy: i32) -> Result<(), io::Error> {
+        let time = TimeVal {
+            tv_sec: 0,
+            tv_usec: 0,
+        };
+        if dx != 0 {
+            self.write(InputEvent::new(
+                &time,
+                &EventCode::EV_REL(EV_REL::REL_X),
+                dx,
+            ))?;
+        }
+        if dy != 0 {
+            self.write(InputEvent::new(
+                &time,
+                &EventCode::EV_REL(EV_REL::REL_Y),
+                dy,
+            ))?;
+        }
+        self.write_sync()
+    }
+
+    /// Move an axis of the virtual gamepad to the value.
+    pub fn set_gamepad_axis(&mut self, axis: GamepadAxis, value: i32) -> Result<(), io::Error> {
+        let code = match axis {
+            GamepadAxis::LeftX => EV_ABS::ABS_X,
+            GamepadAxis::LeftY => EV_ABS::ABS_Y,
+            GamepadAxis::RightX => EV_ABS::ABS_RX,
+            GamepadAxis::RightY => EV_ABS::ABS_RY,
+            GamepadAxis::LeftTrigger => EV_ABS::ABS_Z,
+            GamepadAxis::RightTrigger => EV_ABS::ABS_RZ,
+        };
+        let time = TimeVal {
+            tv_sec: 0,
+            tv_usec: 0,
+        };
+        let device = self.gamepad()?;
+        write_event(
+            device,
+            InputEvent::new(&time, &EventCode::EV_ABS(code), value),
+        )?;
+        write_event(device, sync_event())
+    }
+
+    fn gamepad(&mut self) -> Result<&mut File, io::Error> {
+        match self.gamepad_device {
+            Some(ref mut device) => Ok(device),
+            None => Ok(self.gamepad_device.insert(new_gamepad(&self.uinput)?)),
+        }
+    }
+
+    /// Move the mouse pointer to a position on the screen. The coordinates range from 0 at the
+    /// top or left edge to 65535 at the bottom or right edge.
+    pub fn set_mouse(&mut self, x: u16, y: u16) -> Result<(), io::Error> {
+        let device = match self.abs_device.as_mut() {
+            Some(device) => device,
+            None => self.abs_device.insert(new_abs_pointer(&self.uinput)?),
+        };
+        let time = TimeVal {
+            tv_sec: 0,
+            tv_usec: 0,
+        };
+        write_event(
+            device,
+            InputEvent::new(&time, &EventCode::EV_ABS(EV_ABS::ABS_X), x.into()),
+        )?;
+        write_event(
+            device,
+            InputEvent::new(&time, &EventCode::EV_ABS(EV_ABS::ABS_Y), y.into()),
+        )?;
+        write_event(device, sync_event())
+    }
+}
+
+/// Type the text with one run of wtype. It is waited for so that the text comes before the
+/// following keys.
+fn send_unicode_wtype(text: &str) -> Result<(), io::Error> {
+    if text.is_empty() {
+        return Ok(());
+    }
+    let status = std::process::Command::new("wtype")
+        .arg("--")
+        .arg(text)
+        .status()?;
+    if !status.success() {
+        log::error!("wtype failed to type {:?}: {}", text, status);
+    }
+    Ok(())
+}
+
+/// Returns whether the wtype program is in one of the directories of PATH.
+pub fn wtype_is_installed() -> bool {
+    std::env::var_os("PATH")
+        .map(|paths| std::env::split_paths(&paths).any(|dir| dir.join("wtype").is_file()))
+        .unwrap_or(false)
+}
+
+/// Returns the size of the screen in pixels, if it is known. Kanata doesn't talk to the display
+/// server on Linux, so the size is never known.
+pub fn screen_size() -> Option<(u16, u16)> {
+    None
+}
+
+/// Returns the event with its direction reversed if it is a mouse wheel event, otherwise the event
+/// unchanged.
+pub fn reverse_wheel(mut event: InputEvent) -> InputEvent {
+    if let EventCode::EV_REL(
+        EV_REL::REL_WHEEL
+        | EV_REL::REL_HWHEEL
+        | EV_REL::REL_WHEEL_HI_RES
+        | EV_REL::REL_HWHEEL_HI_RES,
+    ) = event.event_code
+    {
+        event.value = -event.value;
+    }
+    event
+}
+
+/// Returns the relative pointer movement of an absolute X or Y position event since the previous
+/// position in `position`, which is updated. Other events and the first position after a reset of
+/// `position` have no movement.
+pub fn abs_to_rel(event: &InputEvent, position: &mut [Option<i32>; 2]) -> Option<InputEvent> {
+    let (axis, code) = match event.event_code {
+        EventCode::EV_ABS(EV_ABS::ABS_X) => (0, EV_REL::REL_X),
+        EventCode::EV_ABS(EV_ABS::ABS_Y) => (1, EV_REL::REL_Y),
+        _ => return None,
+    };
+    let delta = position[axis].map(|prev| event.value - prev);
+    position[axis] = Some(event.value);
+    match delta {
+        Some(delta) if delta != 0 => Some(InputEvent::new(
+            &event.time,
+            &EventCode::EV_REL(code),
+            delta,
+        )),
+        _ => None,
+    }
+}
+
+/// The input devices that kanata reads, which are either given by path or name in defcfg, or
+/// detected if neither is given.
+#[derive(Clone)]
+pub struct DeviceSelection {
+    pub paths: Vec<PathBuf>,
+    /// Patterns that the names of the devices are matched against. `*` matches any text and `?`
+    /// matches any character.
+    pub names: Vec<String>,
+    /// Paths or name patterns of devices that are not read even if they are detected or match
+    /// `names`.
+    pub exclude: Vec<String>,
+}
+
+impl DeviceSelection {
+    /// Returns the paths of the selected devices. The devices are looked up again on every call,
+    /// since their paths or the targets of their symlinks can change when they are plugged in.
+    pub fn devices(&self) -> Vec<PathBuf> {
+        let detect = self.is_detected();
+        // Symlinks such as the paths in /dev/input/by-id are resolved, so that a device is not
+        // read twice when it is selected by both a symlink and another path or its name. A
+        // device that does not exist keeps its path until it is plugged in.
+        let mut paths: Vec<PathBuf> = Vec::new();
+        for path in self.paths.iter() {
+            let path = std::fs::canonicalize(path).unwrap_or_else(|_| path.clone());
+            if !paths.contains(&path) {
+                paths.push(path);
+            }
+        }
+        if !detect && self.names.is_empty() {
+            return paths;
+        }
+        let selected = |path: &Path, device: &Device| {
+            let name = device.name().unwrap_or("");
+            let selected = match detect {
+                true => is_keyboard(device),
+                false => self.names.iter().any(|pattern| glob_match(pattern, name)),
+            };
+            selected && !self.is_excluded(path, name)
+        };
+        for path in find_devices(selected) {
+            if !paths.contains(&path) {
+                paths.push(path);
+            }
+        }
+        paths
+    }
+
+    /// Returns whether the devices are detected because neither paths nor names are given.
+    pub fn is_detected(&self) -> bool {
+        self.paths.is_empty() && self.names.is_empty()
+    }
+
+    /// Returns whether the device is excluded by its path or name.
+    fn is_excluded(&self, path: &Path, name: &str) -> bool {
+        self.exclude
+            .iter()
+            .any(|item| device_matches(item, path, name))
     }
+}
+
+/// Returns whether the device with the resolved path and name is the device given by `item`. Items
+/// that start with `/` are paths, which may be symlinks, e.g. in /dev/input/by-id. Other items are
+/// name patterns.
+pub fn device_matches(item: &str, path: &Path, name: &str) -> bool {
+    match item.starts_with('/') {
+        true => std::fs::canonicalize(item)
+            .map(|item| item == path)
+            .unwrap_or(false),
+        false => glob_match(item, name),
+    }
+}
+
+/// Returns the paths of the input devices in /dev/input for which `pred` returns true. The virtual
+/// devices of this process are left out.
+fn find_devices(pred: impl Fn(&Path, &Device) -> bool) -> Vec<PathBuf> {
+    let entries = match std::fs::read_dir("/dev/input") {
+        Ok(entries) => entries,
+        Err(e) => {
+            log::error!("Failed to read /dev/input: {}", e);
+            return Vec::new();
+        }
+    };
+    let mut paths: Vec<PathBuf> = entries
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| {
+            path.file_name()
+                .and_then(|name| name.to_str())
+                .map(|name| name.starts_with("event"))
+                .unwrap_or(false)
+        })
+        .filter(|path| match Device::new_from_path(path) {
+            Ok(device) => {
+                let name = device.name().unwrap_or("");
+                !OUTPUT_DEVICE_NAMES.lock().iter().any(|n| n == name) && pred(path, &device)
+            }
+            Err(e) => {
+                log::debug!("failed to open {}: {}", path.display(), e);
+                false
+            }
+        })
+        .collect();
+    paths.sort();
+    paths
+}
+
+/// Returns whether the device looks like a keyboard, i.e. has the letter keys.
+fn is_keyboard(device: &Device) -> bool {
+    [
+        EV_KEY::KEY_Q,
+        EV_KEY::KEY_A,
+        EV_KEY::KEY_Z,
+        EV_KEY::KEY_SPACE,
+    ]
+    .into_iter()
+    .all(|key| device.has_event_code(&EventCode::EV_KEY(key)))
+}
+
+/// Returns whether the text matches the pattern, where `*` matches any text and `?` matches any
+/// character.
+fn glob_match(pattern: &str, text: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let text: Vec<char> = text.chars().collect();
+    let (mut p, mut t) = (0, 0);
+    // Position of the last `*` in the pattern and of the text that it matched up to.
+    let mut star: Option<(usize, usize)> = None;
+    while t < text.len() {
+        match pattern.get(p) {
+            Some('*') => {
+                star = Some((p, t));
+                p += 1;
+            }
+            Some(c) if *c == '?' || *c == text[t] => {
+                p += 1;
+                t += 1;
+            }
+            _ => match star {
+                // Let the `*` match one more character and try again.
+                Some((sp, st)) => {
+                    star = Some((sp, st + 1));
+                    p = sp + 1;
+                    t = st + 1;
+                }
+                None => return false,
+            },
+        }
+    }
+    pattern[p..].iter().all(|c| *c == '*')
+}
 
-    pub fn release_btn(&mut self, btn: Btn) -> Result<(), io::Error> {
-        self.release_key(btn.into())
+#[test]
+fn glob_match_wildcards() {
+    assert!(glob_match("*Keyboard*", "AT Translated Keyboard"));
+    assert!(glob_match("event?", "event3"));
+    assert!(!glob_match("event?", "event10"));
+    assert!(glob_match("a*b*c", "aXbYbZc"));
+    assert!(!glob_match("a*b*c", "aXbYbZ"));
+    assert!(glob_match("*", ""));
+    assert!(!glob_match("", "a"));
+    assert!(glob_match("Logitech K120", "Logitech K120"));
+}
+
+fn write_event(device: &mut File, event: InputEvent) -> Result<(), io::Error> {
+    let ev = event.as_raw();
+
+    unsafe {
+        let ev_bytes = slice::from_raw_parts(
+            mem::transmute(&ev as *const raw_event),
+            mem::size_of::<raw_event>(),
+        );
+        device.write_all(ev_bytes)?;
+    };
+
+    Ok(())
+}
+
+fn sync_event() -> InputEvent {
+    InputEvent::new(
+        &TimeVal {
+            tv_sec: 0,
+            tv_usec: 0,
+        },
+        &EventCode::EV_SYN(EV_SYN::SYN_REPORT),
+        0,
+    )
+}
+
+/// Returns whether the key code is a button of a mouse, from BTN_LEFT to BTN_TASK.
+fn is_mouse_btn(code: u32) -> bool {
+    (0x110..=0x117).contains(&code)
+}
+
+/// Codes of the buttons of the virtual gamepad: the face buttons, shoulder buttons, triggers,
+/// select, start, mode, stick buttons, and the d-pad.
+const GAMEPAD_BUTTONS: [u32; 19] = [
+    304, 305, 306, 307, 308, 309, 310, 311, 312, 313, 314, 315, 316, 317, 318, 544, 545, 546, 547,
+];
+
+/// Create a uinput device for the gamepad buttons, sticks, and triggers.
+fn new_gamepad(uinput: &UinputDevice) -> Result<File, io::Error> {
+    let mut gamepad_file = OpenOptions::new()
+        .read(true)
+        .write(true)
+        .open("/dev/uinput")?;
+
+    unsafe {
+        let fd = gamepad_file.as_raw_fd();
+        uinput_sys::ui_set_evbit(fd, uinput_sys::EV_SYN);
+        uinput_sys::ui_set_evbit(fd, uinput_sys::EV_KEY);
+        uinput_sys::ui_set_evbit(fd, uinput_sys::EV_ABS);
+        for button in GAMEPAD_BUTTONS {
+            uinput_sys::ui_set_keybit(fd, button as c_int);
+        }
+
+        let mut uidev = uinput_device(&format!("{} gamepad", uinput.name), uinput.vendor, 0x4);
+        for (axis, code) in [
+            (GamepadAxis::LeftX, uinput_sys::ABS_X),
+            (GamepadAxis::LeftY, uinput_sys::ABS_Y),
+            (GamepadAxis::RightX, uinput_sys::ABS_RX),
+            (GamepadAxis::RightY, uinput_sys::ABS_RY),
+            (GamepadAxis::LeftTrigger, uinput_sys::ABS_Z),
+            (GamepadAxis::RightTrigger, uinput_sys::ABS_RZ),
+        ] {
+            uinput_sys::ui_set_absbit(fd, code);
+            let (min, max) = axis.range();
+            uidev.absmin[code as usize] = min;
+            uidev.absmax[code as usize] = max;
+        }
+
+        let uidev_bytes = slice::from_raw_parts(
+            &uidev as *const uinput_user_dev as *const u8,
+            mem::size_of::<uinput_user_dev>(),
+        );
+        gamepad_file.write_all(uidev_bytes)?;
+        uinput_sys::ui_dev_create(fd);
     }
+    Ok(gamepad_file)
 }
 
-impl From<Btn> for OsCode {
-    fn from(btn: Btn) -> Self {
-        match btn {
-            Btn::Left => OsCode::BTN_LEFT,
-            Btn::Right => OsCode::BTN_RIGHT,
-            Btn::Mid => OsCode::BTN_MIDDLE,
+/// Create a uinput device for the mouse buttons and relative pointer and wheel movement.
+fn new_mouse(uinput: &UinputDevice, rel_codes: &[c_int]) -> Result<File, io::Error> {
+    let mut mouse_file = OpenOptions::new()
+        .read(true)
+        .write(true)
+        .open("/dev/uinput")?;
+
+    unsafe {
+        let fd = mouse_file.as_raw_fd();
+        uinput_sys::ui_set_evbit(fd, uinput_sys::EV_SYN);
+        uinput_sys::ui_set_evbit(fd, uinput_sys::EV_KEY);
+        uinput_sys::ui_set_evbit(fd, uinput_sys::EV_REL);
+        for key in 0..uinput_sys::KEY_MAX {
+            if is_mouse_btn(key as u32) {
+                uinput_sys::ui_set_keybit(fd, key);
+            }
+        }
+        uinput_sys::ui_set_relbit(fd, uinput_sys::REL_X);
+        uinput_sys::ui_set_relbit(fd, uinput_sys::REL_Y);
+        uinput_sys::ui_set_relbit(fd, uinput_sys::REL_WHEEL);
+        uinput_sys::ui_set_relbit(fd, uinput_sys::REL_HWHEEL);
+        uinput_sys::ui_set_relbit(fd, EV_REL::REL_WHEEL_HI_RES as c_int);
+        uinput_sys::ui_set_relbit(fd, EV_REL::REL_HWHEEL_HI_RES as c_int);
+        // Other axes of the input devices, e.g. a dial, so that their events pass through.
+        for code in rel_codes {
+            uinput_sys::ui_set_relbit(fd, *code);
         }
+
+        let uidev = uinput_device(&format!("{} mouse", uinput.name), uinput.vendor, 0x3);
+
+        let uidev_bytes = slice::from_raw_parts(
+            &uidev as *const uinput_user_dev as *const u8,
+            mem::size_of::<uinput_user_dev>(),
+        );
+        mouse_file.write_all(uidev_bytes)?;
+        uinput_sys::ui_dev_create(fd);
+    }
+    Ok(mouse_file)
+}
+
+/// Returns the description of a uinput device with the name and IDs. The name is remembered so that
+/// the device is never read as an input device.
+fn uinput_device(name: &str, vendor: u16, product: u16) -> uinput_user_dev {
+    let mut uidev: uinput_user_dev = unsafe { mem::zeroed() };
+    // The name is cut off if needed to leave room for the terminating zero.
+    let len = std::cmp::min(name.len(), uidev.name.len() - 1);
+    for (i, c) in name.bytes().enumerate().take(len) {
+        uidev.name[i] = c as c_char;
+    }
+    OUTPUT_DEVICE_NAMES
+        .lock()
+        .push(String::from_utf8_lossy(&name.as_bytes()[..len]).into_owned());
+
+    uidev.id.bustype = 0x3; // BUS_USB
+    uidev.id.vendor = vendor;
+    uidev.id.product = product;
+    uidev.id.version = 1;
+    uidev
+}
+
+/// Create a uinput device that moves the mouse pointer to absolute positions. Its axes range from
+/// 0 to 65535, which the desktop maps to the whole screen.
+fn new_abs_pointer(uinput: &UinputDevice) -> Result<File, io::Error> {
+    let mut abs_file = OpenOptions::new()
+        .read(true)
+        .write(true)
+        .open("/dev/uinput")?;
+
+    unsafe {
+        let fd = abs_file.as_raw_fd();
+        uinput_sys::ui_set_evbit(fd, uinput_sys::EV_SYN);
+        uinput_sys::ui_set_evbit(fd, uinput_sys::EV_KEY);
+        uinput_sys::ui_set_evbit(fd, uinput_sys::EV_ABS);
+        // The device is only treated as a mouse if it has a button.
+        uinput_sys::ui_set_keybit(fd, u32::from(OsCode::BTN_LEFT) as c_int);
+        uinput_sys::ui_set_absbit(fd, uinput_sys::ABS_X);
+        uinput_sys::ui_set_absbit(fd, uinput_sys::ABS_Y);
+
+        let mut uidev = uinput_device(&format!("{} pointer", uinput.name), uinput.vendor, 0x2);
+        uidev.absmax[uinput_sys::ABS_X as usize] = u16::MAX.into();
+        uidev.absmax[uinput_sys::ABS_Y as usize] = u16::MAX.into();
+
+        let uidev_bytes = slice::from_raw_parts(
+            &uidev as *const uinput_user_dev as *const u8,
+            mem::size_of::<uinput_user_dev>(),
+        );
+        abs_file.write_all(uidev_bytes)?;
+        uinput_sys::ui_dev_create(fd);
     }
+    Ok(abs_file)
 }
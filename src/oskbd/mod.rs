@@ -9,3 +9,8 @@ pub use linux::*;
 mod windows;
 #[cfg(target_os = "windows")]
 pub use windows::*;
+
+#[cfg(target_os = "windows")]
+mod interception;
+#[cfg(target_os = "windows")]
+pub use interception::*;
@@ -6,9 +6,12 @@
 use std::cell::RefCell;
 use std::io;
 use std::marker::PhantomData;
+use std::sync::atomic::{AtomicBool, AtomicU32, AtomicU8, Ordering};
+use std::sync::Arc;
 use std::{mem, ptr};
 
 use winapi::ctypes::*;
+use winapi::shared::basetsd::*;
 use winapi::shared::minwindef::*;
 use winapi::shared::windef::*;
 use winapi::um::winuser::*;
@@ -25,18 +28,142 @@ thread_local! {
     static HOOK_STATE: RefCell<HookState> = RefCell::default();
 }
 
+/// Whether the keyboard hook and SendInput identify keys by their scancodes instead of their
+/// virtual key codes.
+static USE_SCANCODES: AtomicBool = AtomicBool::new(false);
+
+/// Read and write keys by their scancodes, which games and remote desktop clients read, instead of
+/// their virtual key codes. The keys are still mapped by the virtual key codes that the scancodes
+/// have in the keyboard layout of the OS.
+pub fn set_scancode_mode(enabled: bool) {
+    USE_SCANCODES.store(enabled, Ordering::SeqCst);
+}
+
+/// Which injected key events, i.e. events that software sends instead of a keyboard, the
+/// keyboard hook hands to kanata.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InjectedEvents {
+    /// No injected events are handled by kanata.
+    Ignore = 0,
+    /// Injected events of other software are handled like the keys of a keyboard. The output of
+    /// kanata itself is never handled again.
+    Process = 1,
+    /// Only the output of other kanata processes is handled, e.g. to chain kanata instances.
+    ProcessOnlyOwn = 2,
+}
+
+static INJECTED_EVENTS: AtomicU8 = AtomicU8::new(InjectedEvents::Ignore as u8);
+
+/// Set which injected events the keyboard hook hands to kanata.
+pub fn set_injected_events(policy: InjectedEvents) {
+    INJECTED_EVENTS.store(policy as u8, Ordering::SeqCst);
+}
+
+/// Marker in the extra info of the events that kanata sends, which is combined with the process
+/// ID to tell its own output from the output of other kanata processes.
+const EXTRA_INFO_MARKER: ULONG_PTR = 0x4B00_0000;
+const EXTRA_INFO_MARKER_MASK: ULONG_PTR = 0xFF00_0000;
+const EXTRA_INFO_PID_MASK: ULONG_PTR = 0x00FF_FFFF;
+
+/// Returns the extra info of the events that this process sends.
+fn own_extra_info() -> ULONG_PTR {
+    EXTRA_INFO_MARKER | (std::process::id() as ULONG_PTR & EXTRA_INFO_PID_MASK)
+}
+
+/// Returns whether an injected event with the extra info is handed to kanata.
+fn process_injected(extra_info: ULONG_PTR) -> bool {
+    let from_kanata = extra_info & EXTRA_INFO_MARKER_MASK == EXTRA_INFO_MARKER;
+    let own = extra_info == own_extra_info();
+    match INJECTED_EVENTS.load(Ordering::SeqCst) {
+        x if x == InjectedEvents::Process as u8 => !own,
+        x if x == InjectedEvents::ProcessOnlyOwn as u8 => from_kanata && !own,
+        _ => false,
+    }
+}
+
+const MAPVK_VK_TO_VSC_EX: UINT = 4;
+
+/// Returns the virtual key code of a scancode, where `extended` is the E0 prefix of the scancode.
+pub fn vk_of_scancode(scancode: u16, extended: bool) -> Option<u32> {
+    let mut scancode = u32::from(scancode);
+    if extended {
+        scancode |= 0xE000;
+    }
+    match unsafe { MapVirtualKeyW(scancode, MAPVK_VSC_TO_VK_EX) } {
+        0 => None,
+        vk => Some(vk),
+    }
+}
+
+/// Returns the scancode of a virtual key code and whether it has the E0 prefix.
+pub fn scancode_of_vk(vk: u32) -> Option<(u16, bool)> {
+    match unsafe { MapVirtualKeyW(vk, MAPVK_VK_TO_VSC_EX) } {
+        0 => None,
+        scancode => Some(((scancode & 0xFF) as u16, scancode & 0xFF00 == 0xE000)),
+    }
+}
+
 #[derive(Default)]
 struct HookState {
     hook: Option<Box<HookFn<'static>>>,
+    /// Handle of the installed hook, which changes when the watchdog reinstalls the hook.
+    handle: Option<HHOOK>,
+    /// ID of the thread timer of the watchdog.
+    watchdog_timer: UINT_PTR,
+    /// Time of the last probe that the watchdog sent.
+    last_probe: DWORD,
 }
 
+/// Time of the last event that the hook received, in milliseconds since boot like the time of the
+/// events.
+static LAST_HOOK_EVENT: AtomicU32 = AtomicU32::new(0);
+
+/// Whether the watchdog sent a probe that the hook hasn't received yet.
+static PROBE_PENDING: AtomicBool = AtomicBool::new(false);
+
+/// Unassigned virtual key code of the probes of the watchdog, which the hook drops.
+const PROBE_VK: u16 = 0xFF;
+
+const WATCHDOG_INTERVAL_MS: UINT = 1000;
+const PROBE_INTERVAL_MS: DWORD = 5000;
+
 /// Wrapper for the low-level keyboard hook API.
 /// Automatically unregisters the hook when dropped.
+///
+/// Windows silently removes the hook when the callback exceeds the hook timeout. A watchdog
+/// notices when there was input that the hook didn't receive and then sends a probe key event. If
+/// the hook doesn't receive the probe either, it is reinstalled. The watchdog is a timer of the
+/// thread, so the thread must dispatch its messages.
 pub struct KeyboardHook<'a> {
-    handle: HHOOK,
     lifetime: PhantomData<&'a ()>,
 }
 
+fn install_hook() -> HHOOK {
+    unsafe {
+        SetWindowsHookExW(WH_KEYBOARD_LL, Some(hook_proc), ptr::null_mut(), 0)
+            .as_mut()
+            .expect("Failed to install low-level keyboard hook.")
+    }
+}
+
+fn reinstall_hook(state: &mut HookState) {
+    if let Some(handle) = state.handle.take() {
+        unsafe { UnhookWindowsHookEx(handle) };
+    }
+    state.handle = Some(install_hook());
+}
+
+/// Install the keyboard hook of this thread again, e.g. after the session was locked, during which
+/// Windows may remove it. Does nothing if the thread has no hook.
+pub fn reinstall_keyboard_hook() {
+    HOOK_STATE.with(|state| {
+        let mut state = state.borrow_mut();
+        if state.hook.is_some() {
+            reinstall_hook(&mut state);
+        }
+    });
+}
+
 impl<'a> KeyboardHook<'a> {
     /// Sets the low-level keyboard hook for this thread.
     ///
@@ -58,13 +185,17 @@ impl<'a> KeyboardHook<'a> {
             // local storage. It is safe to do so because we properly unregister the hook on drop
             // after which the global (thread local) variable `HOOK` will not be acccesed anymore.
             state.hook = Some(unsafe { mem::transmute(boxed_cb) });
+            state.handle = Some(install_hook());
+            state.watchdog_timer = unsafe {
+                SetTimer(
+                    ptr::null_mut(),
+                    0,
+                    WATCHDOG_INTERVAL_MS,
+                    Some(watchdog_proc),
+                )
+            };
 
             KeyboardHook {
-                handle: unsafe {
-                    SetWindowsHookExW(WH_KEYBOARD_LL, Some(hook_proc), ptr::null_mut(), 0)
-                        .as_mut()
-                        .expect("Failed to install low-level keyboard hook.")
-                },
                 lifetime: PhantomData,
             }
         })
@@ -73,8 +204,57 @@ impl<'a> KeyboardHook<'a> {
 
 impl<'a> Drop for KeyboardHook<'a> {
     fn drop(&mut self) {
-        unsafe { UnhookWindowsHookEx(self.handle) };
-        HOOK_STATE.with(|state| state.take());
+        let state = HOOK_STATE.with(|state| state.take());
+        unsafe {
+            KillTimer(ptr::null_mut(), state.watchdog_timer);
+            if let Some(handle) = state.handle {
+                UnhookWindowsHookEx(handle);
+            }
+        }
+    }
+}
+
+/// Check that the hook still receives events, see [`KeyboardHook`].
+unsafe extern "system" fn watchdog_proc(_hwnd: HWND, _msg: UINT, _id: UINT_PTR, now: DWORD) {
+    let probe = HOOK_STATE.with(|state| {
+        let mut state = state.borrow_mut();
+        if PROBE_PENDING.swap(false, Ordering::SeqCst) {
+            log::warn!("The keyboard hook was removed by Windows, reinstalling it");
+            reinstall_hook(&mut state);
+            return false;
+        }
+        let mut info = LASTINPUTINFO {
+            cbSize: mem::size_of::<LASTINPUTINFO>() as UINT,
+            dwTime: 0,
+        };
+        if GetLastInputInfo(&mut info) == 0 {
+            return false;
+        }
+        // Input that the hook didn't receive may also be mouse input, so the probe tells whether
+        // the hook still works.
+        let unseen_input = info
+            .dwTime
+            .wrapping_sub(LAST_HOOK_EVENT.load(Ordering::SeqCst)) as i32
+            > 0;
+        if unseen_input && now.wrapping_sub(state.last_probe) >= PROBE_INTERVAL_MS {
+            state.last_probe = now;
+            return true;
+        }
+        false
+    });
+    if probe {
+        PROBE_PENDING.store(true, Ordering::SeqCst);
+        let mut inputs: [INPUT; 2] = mem::zeroed();
+        for (i, input) in inputs.iter_mut().enumerate() {
+            let mut kb_input: KEYBDINPUT = mem::zeroed();
+            kb_input.wVk = PROBE_VK;
+            if i == 1 {
+                kb_input.dwFlags |= KEYEVENTF_KEYUP;
+            }
+            input.type_ = INPUT_KEYBOARD;
+            *input.u.ki_mut() = kb_input;
+        }
+        SendInput(2, inputs.as_mut_ptr(), mem::size_of::<INPUT>() as _);
     }
 }
 
@@ -92,8 +272,12 @@ pub struct InputEvent {
 
 impl InputEvent {
     fn from_hook_lparam(lparam: &KBDLLHOOKSTRUCT) -> Self {
+        let scancode_vk = match USE_SCANCODES.load(Ordering::SeqCst) {
+            true => vk_of_scancode(lparam.scanCode as u16, lparam.flags & LLKHF_EXTENDED != 0),
+            false => None,
+        };
         Self {
-            code: lparam.vkCode,
+            code: scancode_vk.unwrap_or(lparam.vkCode),
             up: lparam.flags & LLKHF_UP != 0,
             time: lparam.time,
         }
@@ -117,11 +301,18 @@ unsafe extern "system" fn hook_proc(code: c_int, wparam: WPARAM, lparam: LPARAM)
     let hook_lparam = &*(lparam as *const KBDLLHOOKSTRUCT);
     let key_event = InputEvent::from_hook_lparam(hook_lparam);
     let injected = hook_lparam.flags & LLKHF_INJECTED != 0;
+    LAST_HOOK_EVENT.store(hook_lparam.time, Ordering::SeqCst);
+
+    // The probes of the watchdog are only meant for the hook.
+    if injected && hook_lparam.vkCode == u32::from(PROBE_VK) {
+        PROBE_PENDING.store(false, Ordering::SeqCst);
+        return 1;
+    }
 
     // `SendInput()` internally calls the hook function. Filter out injected events
     // to prevent recursion and potential stack overflows if our remapping logic
-    // sent the injected event.
-    if injected {
+    // sent the injected event. The output of kanata is always filtered out.
+    if injected && !process_injected(hook_lparam.dwExtraInfo) {
         return CallNextHookEx(ptr::null_mut(), code, wparam, lparam);
     }
 
@@ -147,7 +338,20 @@ pub fn send_key(key: InputEvent) {
         let mut inputs: [INPUT; 1] = mem::zeroed();
 
         let mut kb_input = key_input_from_event(key);
-        kb_input.wVk = key.code as u16;
+        let scancode = match USE_SCANCODES.load(Ordering::SeqCst) {
+            true => scancode_of_vk(key.code),
+            false => None,
+        };
+        match scancode {
+            Some((scancode, extended)) => {
+                kb_input.wScan = scancode;
+                kb_input.dwFlags |= KEYEVENTF_SCANCODE;
+                if extended {
+                    kb_input.dwFlags |= KEYEVENTF_EXTENDEDKEY;
+                }
+            }
+            None => kb_input.wVk = key.code as u16,
+        }
 
         inputs[0].type_ = INPUT_KEYBOARD;
         *inputs[0].u.ki_mut() = kb_input;
@@ -166,6 +370,7 @@ fn send_uc(c: char, up: bool) {
             let mut kb_input: KEYBDINPUT = unsafe { mem::zeroed() };
             kb_input.wScan = c;
             kb_input.dwFlags |= KEYEVENTF_UNICODE;
+            kb_input.dwExtraInfo = own_extra_info();
             if up {
                 kb_input.dwFlags |= KEYEVENTF_KEYUP;
             }
@@ -183,20 +388,87 @@ fn send_uc(c: char, up: bool) {
     }
 }
 
+/// How unicode characters are entered.
+#[derive(Debug, Clone, Default)]
+pub struct UnicodeMethod {
+    pub backend: UnicodeBackend,
+    /// Lowercase executable names of the applications that alt-numpad is used for regardless of
+    /// the backend, e.g. `cmd.exe`.
+    pub alt_numpad_apps: Vec<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum UnicodeBackend {
+    /// The character is sent as is with SendInput.
+    #[default]
+    SendInput,
+    /// Alt is held while the decimal code of the character is typed on the numpad, which legacy
+    /// applications and consoles understand.
+    AltNumpad,
+}
+
 /// Handle for writing keys to the OS.
-pub struct KbdOut {}
+pub struct KbdOut {
+    /// The driver that keys are written to instead of SendInput, if it is used.
+    pub interception: Option<Arc<super::Interception>>,
+    /// The keyboard of the driver that the keys are written as, which is the one that was read
+    /// last.
+    pub interception_device: super::Device,
+    pub unicode_method: UnicodeMethod,
+}
 
 impl KbdOut {
     pub fn new() -> Result<Self, io::Error> {
-        Ok(Self {})
+        Ok(Self {
+            interception: None,
+            interception_device: 1,
+            unicode_method: UnicodeMethod::default(),
+        })
+    }
+
+    /// Open the Interception driver, which reads the keyboards and receives the output keys.
+    pub fn new_interception() -> Result<Self, io::Error> {
+        Ok(Self {
+            interception: Some(super::Interception::shared()?),
+            interception_device: 1,
+            unicode_method: UnicodeMethod::default(),
+        })
     }
 
     pub fn write(&mut self, event: InputEvent) -> Result<(), io::Error> {
-        send_key(event);
+        match &self.interception {
+            Some(interception) => interception.send_event(self.interception_device, event),
+            None => send_key(event),
+        }
         Ok(())
     }
 
     pub fn write_key(&mut self, key: OsCode, value: KeyValue) -> Result<(), io::Error> {
+        // Mouse buttons are sent as mouse input instead of keyboard input. They are not repeated.
+        let btn = match key {
+            OsCode::BTN_LEFT => Some((MOUSEEVENTF_LEFTDOWN, MOUSEEVENTF_LEFTUP, 0)),
+            OsCode::BTN_RIGHT => Some((MOUSEEVENTF_RIGHTDOWN, MOUSEEVENTF_RIGHTUP, 0)),
+            OsCode::BTN_MIDDLE => Some((MOUSEEVENTF_MIDDLEDOWN, MOUSEEVENTF_MIDDLEUP, 0)),
+            // Windows only has two more buttons, which are back and forward like the side and
+            // extra buttons on Linux.
+            OsCode::BTN_SIDE | OsCode::BTN_BACK => {
+                Some((MOUSEEVENTF_XDOWN, MOUSEEVENTF_XUP, XBUTTON1))
+            }
+            OsCode::BTN_EXTRA | OsCode::BTN_FORWARD => {
+                Some((MOUSEEVENTF_XDOWN, MOUSEEVENTF_XUP, XBUTTON2))
+            }
+            _ => None,
+        };
+        if let Some((down_flag, up_flag, data)) = btn {
+            let flag = match value {
+                KeyValue::Press => down_flag,
+                KeyValue::Release => up_flag,
+                KeyValue::Repeat => return Ok(()),
+            };
+            log::debug!("mouse btn: {:?} {:?}", key, value);
+            send_btn(flag, data.into());
+            return Ok(());
+        }
         let event = InputEvent::from_oscode(key, value);
         self.write(event)
     }
@@ -209,35 +481,179 @@ impl KbdOut {
         self.write_key(key, KeyValue::Release)
     }
 
-    /// Send using VK_PACKET
-    pub fn send_unicode(&mut self, c: char) -> Result<(), io::Error> {
+    /// Send using VK_PACKET, or with alt-numpad if the unicode method selects it for the
+    /// foreground application. The character is sent as is, so the text layout doesn't matter.
+    pub fn send_unicode(&mut self, c: char, _layout: TextLayout) -> Result<(), io::Error> {
+        let alt_numpad = self.unicode_method.backend == UnicodeBackend::AltNumpad
+            || (!self.unicode_method.alt_numpad_apps.is_empty()
+                && foreground_exe()
+                    .map(|exe| self.unicode_method.alt_numpad_apps.contains(&exe))
+                    .unwrap_or(false));
+        if alt_numpad {
+            if (c as u32) <= 0xFFFF {
+                return self.send_alt_numpad(c);
+            }
+            log::warn!("alt-numpad can't enter {}, sending it with SendInput", c);
+        }
         send_uc(c, false);
         send_uc(c, true);
         Ok(())
     }
 
-    pub fn click_btn(&mut self, btn: Btn) -> Result<(), io::Error> {
-        log::debug!("click btn: {:?}", btn);
-        match btn {
-            Btn::Left => send_btn(MOUSEEVENTF_LEFTDOWN),
-            Btn::Right => send_btn(MOUSEEVENTF_RIGHTDOWN),
-            Btn::Mid => send_btn(MOUSEEVENTF_MIDDLEDOWN),
-        };
+    /// Send the characters one by one like `send_unicode`.
+    pub fn send_unicode_str(&mut self, s: &str, layout: TextLayout) -> Result<(), io::Error> {
+        for c in s.chars() {
+            self.send_unicode(c, layout)?;
+        }
         Ok(())
     }
 
-    pub fn release_btn(&mut self, btn: Btn) -> Result<(), io::Error> {
-        log::debug!("reles btn: {:?}", btn);
-        match btn {
-            Btn::Left => send_btn(MOUSEEVENTF_LEFTUP),
-            Btn::Right => send_btn(MOUSEEVENTF_RIGHTUP),
-            Btn::Mid => send_btn(MOUSEEVENTF_MIDDLEUP),
+    /// Hold alt and type the decimal code of the character on the numpad. Codes below 256 get a
+    /// leading zero, which selects the ANSI code page, whose characters match unicode for most of
+    /// these codes. Larger codes are unicode in most applications.
+    fn send_alt_numpad(&mut self, c: char) -> Result<(), io::Error> {
+        let code = c as u32;
+        let digits = match code {
+            0..=255 => format!("0{}", code),
+            _ => code.to_string(),
         };
+        self.press_key(OsCode::KEY_LEFTALT)?;
+        for digit in digits.chars() {
+            let key = NUMPAD_DIGITS[digit.to_digit(10).expect("decimal digit") as usize];
+            self.press_key(key)?;
+            self.release_key(key)?;
+        }
+        self.release_key(OsCode::KEY_LEFTALT)
+    }
+
+    /// Scroll the mouse wheel by a distance in units of 1/120 of a notch, which is the unit of
+    /// WHEEL_DELTA.
+    pub fn scroll(&mut self, direction: MWheelDirection, distance: u16) -> Result<(), io::Error> {
+        log::debug!("scroll: {:?} {}", direction, distance);
+        let distance = i32::from(distance);
+        let (flag, delta) = match direction {
+            MWheelDirection::Up => (MOUSEEVENTF_WHEEL, distance),
+            MWheelDirection::Down => (MOUSEEVENTF_WHEEL, -distance),
+            MWheelDirection::Left => (MOUSEEVENTF_HWHEEL, -distance),
+            MWheelDirection::Right => (MOUSEEVENTF_HWHEEL, distance),
+        };
+        unsafe {
+            let mut inputs: [INPUT; 1] = mem::zeroed();
+            inputs[0].type_ = INPUT_MOUSE;
+
+            let mut m_input: MOUSEINPUT = mem::zeroed();
+            m_input.dwFlags |= flag;
+            // The distance is signed but the field is unsigned.
+            m_input.mouseData = delta as u32;
+
+            *inputs[0].u.mi_mut() = m_input;
+            SendInput(1, inputs.as_mut_ptr(), mem::size_of::<INPUT>() as _);
+        }
+        Ok(())
+    }
+
+    /// Move the mouse pointer by a distance in pixels.
+    pub fn move_mouse(&mut self, dx: i32, dy: i32) -> Result<(), io::Error> {
+        unsafe {
+            let mut inputs: [INPUT; 1] = mem::zeroed();
+            inputs[0].type_ = INPUT_MOUSE;
+
+            let mut m_input: MOUSEINPUT = mem::zeroed();
+            m_input.dwFlags |= MOUSEEVENTF_MOVE;
+            m_input.dx = dx;
+            m_input.dy = dy;
+
+            *inputs[0].u.mi_mut() = m_input;
+            SendInput(1, inputs.as_mut_ptr(), mem::size_of::<INPUT>() as _);
+        }
         Ok(())
     }
+
+    /// Move the mouse pointer to a position on the screen. The coordinates range from 0 at the
+    /// top or left edge to 65535 at the bottom or right edge of the area of all monitors.
+    /// There is no virtual gamepad on Windows, where gamepad-axis is rejected by the parser.
+    pub fn set_gamepad_axis(&mut self, _axis: GamepadAxis, _value: i32) -> Result<(), io::Error> {
+        Ok(())
+    }
+
+    pub fn set_mouse(&mut self, x: u16, y: u16) -> Result<(), io::Error> {
+        unsafe {
+            let mut inputs: [INPUT; 1] = mem::zeroed();
+            inputs[0].type_ = INPUT_MOUSE;
+
+            let mut m_input: MOUSEINPUT = mem::zeroed();
+            m_input.dwFlags |= MOUSEEVENTF_MOVE | MOUSEEVENTF_ABSOLUTE | MOUSEEVENTF_VIRTUALDESK;
+            m_input.dx = x.into();
+            m_input.dy = y.into();
+
+            *inputs[0].u.mi_mut() = m_input;
+            SendInput(1, inputs.as_mut_ptr(), mem::size_of::<INPUT>() as _);
+        }
+        Ok(())
+    }
+}
+
+const NUMPAD_DIGITS: [OsCode; 10] = [
+    OsCode::KEY_KP0,
+    OsCode::KEY_KP1,
+    OsCode::KEY_KP2,
+    OsCode::KEY_KP3,
+    OsCode::KEY_KP4,
+    OsCode::KEY_KP5,
+    OsCode::KEY_KP6,
+    OsCode::KEY_KP7,
+    OsCode::KEY_KP8,
+    OsCode::KEY_KP9,
+];
+
+/// Returns the lowercase executable name of the application of the foreground window, e.g.
+/// `cmd.exe`.
+fn foreground_exe() -> Option<String> {
+    use winapi::um::handleapi::CloseHandle;
+    use winapi::um::processthreadsapi::OpenProcess;
+    use winapi::um::winbase::QueryFullProcessImageNameW;
+    use winapi::um::winnt::PROCESS_QUERY_LIMITED_INFORMATION;
+
+    unsafe {
+        let hwnd = GetForegroundWindow();
+        if hwnd.is_null() {
+            return None;
+        }
+        let mut pid = 0;
+        GetWindowThreadProcessId(hwnd, &mut pid);
+        let process = OpenProcess(PROCESS_QUERY_LIMITED_INFORMATION, FALSE, pid);
+        if process.is_null() {
+            return None;
+        }
+        let mut buf = [0u16; MAX_PATH];
+        let mut len = buf.len() as DWORD;
+        let ok = QueryFullProcessImageNameW(process, 0, buf.as_mut_ptr(), &mut len);
+        CloseHandle(process);
+        if ok == 0 {
+            return None;
+        }
+        let path = String::from_utf16_lossy(&buf[..len as usize]);
+        path.rsplit('\\').next().map(|exe| exe.to_lowercase())
+    }
+}
+
+/// Returns the size in pixels of the area of all monitors, if it is known.
+pub fn screen_size() -> Option<(u16, u16)> {
+    let (width, height) = unsafe {
+        (
+            GetSystemMetrics(SM_CXVIRTUALSCREEN),
+            GetSystemMetrics(SM_CYVIRTUALSCREEN),
+        )
+    };
+    match (u16::try_from(width), u16::try_from(height)) {
+        (Ok(width), Ok(height)) if width > 0 && height > 0 => Some((width, height)),
+        _ => None,
+    }
 }
 
-fn send_btn(flag: u32) {
+/// Send a mouse button event. `data` is the number of the button for the X buttons and zero
+/// otherwise.
+fn send_btn(flag: u32, data: u32) {
     unsafe {
         let mut inputs: [INPUT; 1] = mem::zeroed();
         inputs[0].type_ = INPUT_MOUSE;
@@ -245,6 +661,7 @@ fn send_btn(flag: u32) {
         // set button
         let mut m_input: MOUSEINPUT = mem::zeroed();
         m_input.dwFlags |= flag;
+        m_input.mouseData = data;
 
         *inputs[0].u.mi_mut() = m_input;
         SendInput(1, inputs.as_mut_ptr(), mem::size_of::<INPUT>() as _);
@@ -257,5 +674,6 @@ fn key_input_from_event(key: InputEvent) -> KEYBDINPUT {
         kb_input.dwFlags |= KEYEVENTF_KEYUP;
     }
     kb_input.time = key.time;
+    kb_input.dwExtraInfo = own_extra_info();
     kb_input
 }
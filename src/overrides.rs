@@ -0,0 +1,81 @@
+//! Overrides: combinations of output keys that are replaced by other keys right before they are
+//! sent to the OS, e.g. to send `lalt+f4` instead of `lsft+2`.
+
+use kanata_keyberon::key_code::KeyCode;
+
+/// A combination of keys and the keys that replace it.
+#[derive(Debug)]
+pub struct Override {
+    pub in_keys: Vec<KeyCode>,
+    pub out_keys: Vec<KeyCode>,
+}
+
+/// The overrides of the configuration.
+#[derive(Debug, Default)]
+pub struct Overrides {
+    overrides: Vec<Override>,
+}
+
+impl Overrides {
+    pub fn new(overrides: Vec<Override>) -> Self {
+        Self { overrides }
+    }
+
+    /// Replace the keys of the first override whose keys are all in `keys` with its output keys.
+    /// The output keys are added in the order that they are declared.
+    pub fn override_keys(&self, keys: &mut Vec<KeyCode>) {
+        let ov = match self
+            .overrides
+            .iter()
+            .find(|ov| ov.in_keys.iter().all(|k| keys.contains(k)))
+        {
+            Some(ov) => ov,
+            None => return,
+        };
+        keys.retain(|k| !ov.in_keys.contains(k));
+        for k in ov.out_keys.iter() {
+            if !keys.contains(k) {
+                keys.push(*k);
+            }
+        }
+    }
+}
+
+#[test]
+fn override_replaces_its_keys() {
+    use KeyCode::*;
+    let overrides = Overrides::new(vec![
+        Override {
+            in_keys: vec![LShift, Kb2],
+            out_keys: vec![LAlt, F4],
+        },
+        Override {
+            in_keys: vec![LShift],
+            out_keys: vec![RShift],
+        },
+    ]);
+    let mut keys = vec![A, Kb2, LShift];
+    overrides.override_keys(&mut keys);
+    assert_eq!(keys, [A, LAlt, F4]);
+
+    // Only the first matching override is applied.
+    let mut keys = vec![LShift, B];
+    overrides.override_keys(&mut keys);
+    assert_eq!(keys, [B, RShift]);
+
+    let mut keys = vec![Kb2];
+    overrides.override_keys(&mut keys);
+    assert_eq!(keys, [Kb2]);
+}
+
+#[test]
+fn override_does_not_duplicate_held_output_keys() {
+    use KeyCode::*;
+    let overrides = Overrides::new(vec![Override {
+        in_keys: vec![CapsLock],
+        out_keys: vec![LCtrl, Escape],
+    }]);
+    let mut keys = vec![LCtrl, CapsLock];
+    overrides.override_keys(&mut keys);
+    assert_eq!(keys, [LCtrl, Escape]);
+}
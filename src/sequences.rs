@@ -0,0 +1,316 @@
+//! Sequences: keys typed one after another after activating the sequence leader action, which are
+//! mapped to actions.
+
+use kanata_keyberon::key_code::KeyCode;
+
+use crate::custom_action::Macro;
+use crate::keys::OsCode;
+
+/// What happens to the keys that are typed while a sequence is being entered.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SequenceInputMode {
+    /// The keys are not typed.
+    Hidden,
+    /// The keys are typed as usual.
+    Visible,
+    /// The keys are typed as usual and erased with backspaces when a sequence matches.
+    VisibleBackspaced,
+}
+
+/// An item of a sequence that matches one typed key.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SequenceToken {
+    /// Matches the key with this coordinate.
+    Key(u8),
+    /// Matches any key.
+    Any,
+    /// Matches any of the keys with these coordinates, e.g. any digit.
+    OneOf(Vec<u8>),
+}
+
+impl SequenceToken {
+    fn matches(&self, coord: u8) -> bool {
+        match self {
+            SequenceToken::Key(c) => *c == coord,
+            SequenceToken::Any => true,
+            SequenceToken::OneOf(coords) => coords.contains(&coord),
+        }
+    }
+}
+
+/// A sequence of keys mapped to an action.
+#[derive(Debug)]
+pub struct SequenceDef {
+    pub keys: Vec<SequenceToken>,
+    /// Virtual key of the action of the sequence.
+    pub vkey: u8,
+    /// Macro that erases the typed keys of the sequence in the `VisibleBackspaced` input mode.
+    pub backspaces: &'static Macro,
+}
+
+/// Result of a key press while a sequence is being entered.
+pub enum SequenceEvent {
+    /// The key completed a sequence. The press should be hidden if `hidden` is true.
+    /// `backspaces` is the macro that must be played back before the action of the sequence, if
+    /// any.
+    /// `matched_keys` are the keys typed for the wildcards and ranges of the sequence.
+    Matched {
+        vkey: u8,
+        hidden: bool,
+        backspaces: Option<&'static Macro>,
+        matched_keys: Vec<KeyCode>,
+    },
+    /// The key is part of a sequence. The press should be hidden if `hidden` is true.
+    Pending { hidden: bool },
+    /// The key is not part of any sequence and should be handled as usual.
+    NoMatch,
+}
+
+/// The sequences of the configuration and the state of the sequence that is being entered.
+pub struct Sequences {
+    defs: Vec<SequenceDef>,
+    timeout: u16,
+    input_mode: SequenceInputMode,
+    /// Keys typed since the leader was activated, or `None` if no sequence is being entered.
+    typed: Option<Vec<u8>>,
+    ticks_remaining: u16,
+}
+
+impl Sequences {
+    pub fn new(defs: Vec<SequenceDef>, timeout: u16, input_mode: SequenceInputMode) -> Self {
+        Self {
+            defs,
+            timeout,
+            input_mode,
+            typed: None,
+            ticks_remaining: 0,
+        }
+    }
+
+    /// Start entering a sequence.
+    pub fn start(&mut self) {
+        log::debug!("sequence started");
+        self.typed = Some(Vec::new());
+        self.ticks_remaining = self.timeout;
+    }
+
+    /// Handle a key press. Returns `None` if no sequence is being entered.
+    pub fn press(&mut self, coord: u8) -> Option<SequenceEvent> {
+        let typed = self.typed.as_mut()?;
+        typed.push(coord);
+        self.ticks_remaining = self.timeout;
+        let typed = &*typed;
+        let candidates = self.defs.iter().filter(|d| {
+            d.keys.len() >= typed.len() && d.keys.iter().zip(typed).all(|(t, c)| t.matches(*c))
+        });
+        // The first sequence that is complete wins, otherwise keep waiting for more keys.
+        let mut pending = false;
+        let mut completed = None;
+        for def in candidates {
+            if def.keys.len() == typed.len() {
+                completed = Some(def);
+                break;
+            }
+            pending = true;
+        }
+        let event = match completed {
+            Some(def) => SequenceEvent::Matched {
+                vkey: def.vkey,
+                hidden: self.input_mode == SequenceInputMode::Hidden,
+                backspaces: match self.input_mode {
+                    SequenceInputMode::VisibleBackspaced => Some(def.backspaces),
+                    _ => None,
+                },
+                matched_keys: def
+                    .keys
+                    .iter()
+                    .zip(typed)
+                    .filter(|(t, _)| !matches!(t, SequenceToken::Key(_)))
+                    .map(|(_, c)| OsCode::from(u32::from(*c)).into())
+                    .collect(),
+            },
+            None if pending => SequenceEvent::Pending {
+                hidden: self.input_mode == SequenceInputMode::Hidden,
+            },
+            None => SequenceEvent::NoMatch,
+        };
+        if !matches!(event, SequenceEvent::Pending { .. }) {
+            log::debug!("sequence ended");
+            self.typed = None;
+        }
+        Some(event)
+    }
+
+    /// Advance the state by one millisecond.
+    pub fn tick(&mut self) {
+        if self.typed.is_none() {
+            return;
+        }
+        self.ticks_remaining = self.ticks_remaining.saturating_sub(1);
+        if self.ticks_remaining == 0 {
+            log::debug!("sequence timed out");
+            self.typed = None;
+        }
+    }
+}
+
+#[cfg(test)]
+static TEST_BACKSPACES: Macro = Macro {
+    events: &[],
+    cancel_on_press: false,
+    repeat: crate::custom_action::MacroRepeat::Times(1),
+};
+
+#[cfg(test)]
+fn test_sequences(defs: Vec<Vec<SequenceToken>>, input_mode: SequenceInputMode) -> Sequences {
+    let defs = defs
+        .into_iter()
+        .enumerate()
+        .map(|(i, keys)| SequenceDef {
+            keys,
+            vkey: i as u8,
+            backspaces: &TEST_BACKSPACES,
+        })
+        .collect();
+    Sequences::new(defs, 100, input_mode)
+}
+
+#[cfg(test)]
+fn key(osc: OsCode) -> SequenceToken {
+    SequenceToken::Key(osc as u8)
+}
+
+#[test]
+fn keys_are_only_handled_while_a_sequence_is_entered() {
+    let mut seqs = test_sequences(vec![vec![key(OsCode::KEY_A)]], SequenceInputMode::Hidden);
+    assert!(seqs.press(OsCode::KEY_A as u8).is_none());
+    seqs.start();
+    assert!(matches!(
+        seqs.press(OsCode::KEY_A as u8),
+        Some(SequenceEvent::Matched { vkey: 0, .. })
+    ));
+    assert!(seqs.press(OsCode::KEY_A as u8).is_none());
+}
+
+#[test]
+fn input_mode_hides_or_erases_the_typed_keys() {
+    let defs = || vec![vec![key(OsCode::KEY_A), key(OsCode::KEY_B)]];
+    for (mode, hide, erase) in [
+        (SequenceInputMode::Hidden, true, false),
+        (SequenceInputMode::Visible, false, false),
+        (SequenceInputMode::VisibleBackspaced, false, true),
+    ] {
+        let mut seqs = test_sequences(defs(), mode);
+        seqs.start();
+        match seqs.press(OsCode::KEY_A as u8) {
+            Some(SequenceEvent::Pending { hidden }) => assert_eq!(hidden, hide),
+            _ => panic!("expected a pending sequence in {:?}", mode),
+        }
+        match seqs.press(OsCode::KEY_B as u8) {
+            Some(SequenceEvent::Matched {
+                hidden, backspaces, ..
+            }) => {
+                assert_eq!(hidden, hide);
+                assert_eq!(backspaces.is_some(), erase);
+            }
+            _ => panic!("expected a matched sequence in {:?}", mode),
+        }
+    }
+}
+
+#[test]
+fn wrong_key_ends_the_sequence() {
+    let mut seqs = test_sequences(
+        vec![vec![key(OsCode::KEY_A), key(OsCode::KEY_B)]],
+        SequenceInputMode::Hidden,
+    );
+    seqs.start();
+    seqs.press(OsCode::KEY_A as u8);
+    assert!(matches!(
+        seqs.press(OsCode::KEY_C as u8),
+        Some(SequenceEvent::NoMatch)
+    ));
+    assert!(seqs.press(OsCode::KEY_B as u8).is_none());
+}
+
+#[test]
+fn sequence_times_out_after_the_last_key() {
+    let mut seqs = test_sequences(
+        vec![vec![key(OsCode::KEY_A), key(OsCode::KEY_B)]],
+        SequenceInputMode::Hidden,
+    );
+    seqs.start();
+    for _ in 0..50 {
+        seqs.tick();
+    }
+    seqs.press(OsCode::KEY_A as u8);
+    for _ in 0..99 {
+        seqs.tick();
+    }
+    assert!(seqs.press(OsCode::KEY_B as u8).is_some());
+    seqs.start();
+    seqs.press(OsCode::KEY_A as u8);
+    for _ in 0..100 {
+        seqs.tick();
+    }
+    assert!(seqs.press(OsCode::KEY_B as u8).is_none());
+}
+
+#[test]
+fn wildcard_matches_any_key() {
+    let mut seqs = test_sequences(
+        vec![vec![key(OsCode::KEY_W), SequenceToken::Any]],
+        SequenceInputMode::Hidden,
+    );
+    for osc in [OsCode::KEY_A, OsCode::KEY_1, OsCode::KEY_W] {
+        seqs.start();
+        seqs.press(OsCode::KEY_W as u8);
+        match seqs.press(osc as u8) {
+            Some(SequenceEvent::Matched { matched_keys, .. }) => {
+                assert_eq!(matched_keys, vec![KeyCode::from(osc)])
+            }
+            _ => panic!("expected {:?} to match the wildcard", osc),
+        }
+    }
+}
+
+#[test]
+fn range_matches_only_its_keys() {
+    let digits = SequenceToken::OneOf(vec![OsCode::KEY_1 as u8, OsCode::KEY_2 as u8]);
+    let mut seqs = test_sequences(
+        vec![vec![digits.clone(), key(OsCode::KEY_W), digits]],
+        SequenceInputMode::Hidden,
+    );
+    seqs.start();
+    assert!(matches!(
+        seqs.press(OsCode::KEY_A as u8),
+        Some(SequenceEvent::NoMatch)
+    ));
+    seqs.start();
+    seqs.press(OsCode::KEY_2 as u8);
+    seqs.press(OsCode::KEY_W as u8);
+    match seqs.press(OsCode::KEY_1 as u8) {
+        Some(SequenceEvent::Matched { matched_keys, .. }) => {
+            assert_eq!(matched_keys, vec![KeyCode::Kb2, KeyCode::Kb1])
+        }
+        _ => panic!("expected the range to match"),
+    }
+}
+
+#[test]
+fn first_complete_sequence_wins() {
+    let mut seqs = test_sequences(
+        vec![
+            vec![key(OsCode::KEY_A), key(OsCode::KEY_B), key(OsCode::KEY_C)],
+            vec![key(OsCode::KEY_A), SequenceToken::Any],
+            vec![key(OsCode::KEY_A), key(OsCode::KEY_B)],
+        ],
+        SequenceInputMode::Hidden,
+    );
+    seqs.start();
+    seqs.press(OsCode::KEY_A as u8);
+    assert!(matches!(
+        seqs.press(OsCode::KEY_B as u8),
+        Some(SequenceEvent::Matched { vkey: 1, .. })
+    ));
+}
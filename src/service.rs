@@ -0,0 +1,314 @@
+//! Running kanata as a Windows service, which keeps remapping keys on the login screen and after
+//! logoff. Services run in session 0 without a desktop, where the low level keyboard hook receives
+//! no keys, so the service reads the keyboards with the Interception driver. The log goes to the
+//! Windows event log.
+
+use std::io;
+use std::path::PathBuf;
+use std::ptr;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+
+use anyhow::{anyhow, bail, Result};
+use log::{Level, LevelFilter, Log, Metadata, Record};
+use once_cell::sync::OnceCell;
+use simplelog::{Config, SharedLogger};
+use winapi::shared::minwindef::*;
+use winapi::um::winbase::*;
+use winapi::um::winnt::*;
+use winapi::um::winsvc::*;
+
+use crate::CfgPath;
+
+const SERVICE_NAME: &str = "kanata";
+const SERVICE_DISPLAY_NAME: &str = "kanata keyboard remapper";
+
+const WTS_SESSION_LOGON: DWORD = 5;
+const WTS_SESSION_LOGOFF: DWORD = 6;
+const WTS_SESSION_LOCK: DWORD = 7;
+const WTS_SESSION_UNLOCK: DWORD = 8;
+
+/// Subcommands that manage the Windows service.
+#[derive(clap::Subcommand, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ServiceCommand {
+    /// Install and start the Windows service, which runs kanata with the given configuration files
+    /// and the given options when Windows starts. Requires administrator rights.
+    ServiceInstall,
+    /// Stop and uninstall the Windows service. Requires administrator rights.
+    ServiceUninstall,
+    /// Run as the Windows service. This is used by the service control manager.
+    ServiceRun,
+}
+
+static RUNNING_AS_SERVICE: AtomicBool = AtomicBool::new(false);
+static STATUS_HANDLE: AtomicUsize = AtomicUsize::new(0);
+static CFG_PATHS: OnceCell<Vec<CfgPath>> = OnceCell::new();
+static PORT: OnceCell<Option<u16>> = OnceCell::new();
+
+/// Whether kanata runs as the Windows service.
+pub fn is_running() -> bool {
+    RUNNING_AS_SERVICE.load(Ordering::SeqCst)
+}
+
+fn wide(s: &str) -> Vec<u16> {
+    s.encode_utf16().chain(Some(0)).collect()
+}
+
+/// Install the service that runs this executable with the configuration files and the other
+/// arguments of the current invocation, and start it.
+pub fn install(cfgs: &[CfgPath], debug: bool, port: Option<u16>) -> Result<()> {
+    let exe = std::env::current_exe()?;
+    let mut cmd = format!("\"{}\"", exe.display());
+    for cfg in cfgs {
+        // The service doesn't run in the current directory.
+        let cfg: PathBuf = std::fs::canonicalize(cfg)?;
+        cmd.push_str(&format!(" --cfg \"{}\"", cfg.display()));
+    }
+    if debug {
+        cmd.push_str(" --debug");
+    }
+    if let Some(port) = port {
+        cmd.push_str(&format!(" --port {}", port));
+    }
+    cmd.push_str(" service-run");
+    unsafe {
+        let scm = OpenSCManagerW(ptr::null(), ptr::null(), SC_MANAGER_CREATE_SERVICE);
+        if scm.is_null() {
+            bail!(
+                "could not open the service control manager, run as administrator: {}",
+                io::Error::last_os_error()
+            )
+        }
+        let svc = CreateServiceW(
+            scm,
+            wide(SERVICE_NAME).as_ptr(),
+            wide(SERVICE_DISPLAY_NAME).as_ptr(),
+            SERVICE_ALL_ACCESS,
+            SERVICE_WIN32_OWN_PROCESS,
+            SERVICE_AUTO_START,
+            SERVICE_ERROR_NORMAL,
+            wide(&cmd).as_ptr(),
+            ptr::null(),
+            ptr::null_mut(),
+            ptr::null(),
+            ptr::null(),
+            ptr::null(),
+        );
+        if svc.is_null() {
+            let err = io::Error::last_os_error();
+            CloseServiceHandle(scm);
+            bail!("could not install the service: {}", err)
+        }
+        let started = StartServiceW(svc, 0, ptr::null_mut()) != 0;
+        let err = io::Error::last_os_error();
+        CloseServiceHandle(svc);
+        CloseServiceHandle(scm);
+        if !started {
+            bail!("installed the service, but could not start it: {}", err)
+        }
+    }
+    log::info!("Installed and started the service: {}", cmd);
+    Ok(())
+}
+
+/// Stop the service if it runs and uninstall it.
+pub fn uninstall() -> Result<()> {
+    unsafe {
+        let scm = OpenSCManagerW(ptr::null(), ptr::null(), SC_MANAGER_CONNECT);
+        if scm.is_null() {
+            bail!(
+                "could not open the service control manager, run as administrator: {}",
+                io::Error::last_os_error()
+            )
+        }
+        let svc = OpenServiceW(
+            scm,
+            wide(SERVICE_NAME).as_ptr(),
+            SERVICE_STOP | SERVICE_QUERY_STATUS | DELETE,
+        );
+        if svc.is_null() {
+            let err = io::Error::last_os_error();
+            CloseServiceHandle(scm);
+            bail!("could not open the service: {}", err)
+        }
+        let mut status: SERVICE_STATUS = std::mem::zeroed();
+        // Stopping fails if the service isn't running, which is fine.
+        ControlService(svc, SERVICE_CONTROL_STOP, &mut status);
+        let deleted = DeleteService(svc) != 0;
+        let err = io::Error::last_os_error();
+        CloseServiceHandle(svc);
+        CloseServiceHandle(scm);
+        if !deleted {
+            bail!("could not uninstall the service: {}", err)
+        }
+    }
+    log::info!("Uninstalled the service");
+    Ok(())
+}
+
+/// Run as the service until it is stopped. This blocks while the service runs and must be called
+/// early, since the service control manager waits only briefly for it.
+pub fn run(cfgs: Vec<CfgPath>, port: Option<u16>) -> Result<()> {
+    RUNNING_AS_SERVICE.store(true, Ordering::SeqCst);
+    let _ = CFG_PATHS.set(cfgs);
+    let _ = PORT.set(port);
+    let mut name = wide(SERVICE_NAME);
+    let table = [
+        SERVICE_TABLE_ENTRYW {
+            lpServiceName: name.as_mut_ptr(),
+            lpServiceProc: Some(service_main),
+        },
+        SERVICE_TABLE_ENTRYW {
+            lpServiceName: ptr::null_mut(),
+            lpServiceProc: None,
+        },
+    ];
+    if unsafe { StartServiceCtrlDispatcherW(table.as_ptr()) } == 0 {
+        bail!(
+            "could not connect to the service control manager, service-run is only used by the service: {}",
+            io::Error::last_os_error()
+        )
+    }
+    Ok(())
+}
+
+unsafe extern "system" fn service_main(_argc: DWORD, _argv: *mut LPWSTR) {
+    let handle =
+        RegisterServiceCtrlHandlerExW(wide(SERVICE_NAME).as_ptr(), Some(handler), ptr::null_mut());
+    if handle.is_null() {
+        log::error!(
+            "could not register the service handler: {}",
+            io::Error::last_os_error()
+        );
+        return;
+    }
+    STATUS_HANDLE.store(handle as usize, Ordering::SeqCst);
+    set_status(SERVICE_RUNNING, 0);
+    log::info!("Service: started");
+    let cfgs = CFG_PATHS.get().cloned().unwrap_or_default();
+    let port = PORT.get().copied().flatten();
+    let result = crate::main_impl(cfgs, port);
+    if let Err(e) = &result {
+        log::error!("Service: kanata failed: {}", e);
+    }
+    set_status(SERVICE_STOPPED, if result.is_ok() { 0 } else { 1 });
+}
+
+/// Handle the controls of the service control manager. Changes of the sessions are only logged,
+/// because the Interception driver keeps reading the keyboards regardless of the session.
+unsafe extern "system" fn handler(
+    control: DWORD,
+    event_type: DWORD,
+    _event_data: LPVOID,
+    _context: LPVOID,
+) -> DWORD {
+    match control {
+        SERVICE_CONTROL_STOP | SERVICE_CONTROL_SHUTDOWN => {
+            log::info!("Service: stopping");
+            set_status(SERVICE_STOPPED, 0);
+            std::process::exit(0);
+        }
+        SERVICE_CONTROL_SESSIONCHANGE => {
+            let change = match event_type {
+                WTS_SESSION_LOGON => "logon",
+                WTS_SESSION_LOGOFF => "logoff",
+                WTS_SESSION_LOCK => "lock",
+                WTS_SESSION_UNLOCK => "unlock",
+                _ => "other",
+            };
+            log::info!("Service: session change: {}", change);
+            0
+        }
+        SERVICE_CONTROL_INTERROGATE => 0,
+        _ => 120, // ERROR_CALL_NOT_IMPLEMENTED
+    }
+}
+
+fn set_status(state: DWORD, exit_code: DWORD) {
+    let handle = STATUS_HANDLE.load(Ordering::SeqCst) as SERVICE_STATUS_HANDLE;
+    let mut status = SERVICE_STATUS {
+        dwServiceType: SERVICE_WIN32_OWN_PROCESS,
+        dwCurrentState: state,
+        dwControlsAccepted: match state {
+            SERVICE_RUNNING => {
+                SERVICE_ACCEPT_STOP | SERVICE_ACCEPT_SHUTDOWN | SERVICE_ACCEPT_SESSIONCHANGE
+            }
+            _ => 0,
+        },
+        dwWin32ExitCode: exit_code,
+        dwServiceSpecificExitCode: 0,
+        dwCheckPoint: 0,
+        dwWaitHint: 0,
+    };
+    unsafe { SetServiceStatus(handle, &mut status) };
+}
+
+/// Logger that writes to the application log of the Windows event log, since the service has no
+/// console.
+pub struct EventLogger {
+    level: LevelFilter,
+    source: usize,
+}
+
+impl EventLogger {
+    pub fn new(level: LevelFilter) -> Result<Box<Self>> {
+        let source = unsafe { RegisterEventSourceW(ptr::null(), wide(SERVICE_NAME).as_ptr()) };
+        if source.is_null() {
+            return Err(anyhow!(
+                "could not open the event log: {}",
+                io::Error::last_os_error()
+            ));
+        }
+        Ok(Box::new(Self {
+            level,
+            source: source as usize,
+        }))
+    }
+}
+
+impl Log for EventLogger {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        metadata.level() <= self.level
+    }
+
+    fn log(&self, record: &Record) {
+        if !self.enabled(record.metadata()) {
+            return;
+        }
+        let event_type = match record.level() {
+            Level::Error => EVENTLOG_ERROR_TYPE,
+            Level::Warn => EVENTLOG_WARNING_TYPE,
+            _ => EVENTLOG_INFORMATION_TYPE,
+        };
+        let msg = wide(&record.args().to_string());
+        let mut strings = [msg.as_ptr()];
+        unsafe {
+            ReportEventW(
+                self.source as HANDLE,
+                event_type,
+                0,
+                0,
+                ptr::null_mut(),
+                1,
+                0,
+                strings.as_mut_ptr(),
+                ptr::null_mut(),
+            );
+        }
+    }
+
+    fn flush(&self) {}
+}
+
+impl SharedLogger for EventLogger {
+    fn level(&self) -> LevelFilter {
+        self.level
+    }
+
+    fn config(&self) -> Option<&Config> {
+        None
+    }
+
+    fn as_log(self: Box<Self>) -> Box<dyn Log> {
+        Box::new(*self)
+    }
+}
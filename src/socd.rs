@@ -0,0 +1,113 @@
+//! SOCD cleaning (simultaneous opposing cardinal directions): when both keys of an opposing pair,
+//! e.g. `a` and `d` for moving left and right in a game, are held in the output, only one of them
+//! or neither is sent to the OS.
+
+use kanata_keyberon::key_code::KeyCode;
+
+/// Which key of an opposing pair is sent while both are held.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SocdPolicy {
+    /// The key that was pressed last.
+    LastInputPriority,
+    /// Neither key.
+    Neutral,
+    /// The key that was pressed first.
+    FirstWins,
+}
+
+/// Two opposing keys.
+#[derive(Debug)]
+pub struct SocdPair {
+    pub keys: [KeyCode; 2],
+    pub policy: SocdPolicy,
+}
+
+/// The opposing pairs of the configuration and the order in which their keys were pressed.
+#[derive(Debug, Default)]
+pub struct Socd {
+    pairs: Vec<SocdPair>,
+    /// Held keys of the pairs, in the order that they were pressed.
+    held: Vec<KeyCode>,
+}
+
+impl Socd {
+    pub fn new(pairs: Vec<SocdPair>) -> Self {
+        Self {
+            pairs,
+            held: Vec::new(),
+        }
+    }
+
+    /// Remove the keys of the opposing pairs that are both in `keys` according to the policies of
+    /// the pairs. `keys` are the output keys of the current tick.
+    pub fn clean(&mut self, keys: &mut Vec<KeyCode>) {
+        if self.pairs.is_empty() {
+            return;
+        }
+        self.held.retain(|k| keys.contains(k));
+        for pair in self.pairs.iter() {
+            for k in pair.keys.iter() {
+                if keys.contains(k) && !self.held.contains(k) {
+                    self.held.push(*k);
+                }
+            }
+        }
+        for pair in self.pairs.iter() {
+            let (first, last) = match (
+                self.held.iter().position(|k| *k == pair.keys[0]),
+                self.held.iter().position(|k| *k == pair.keys[1]),
+            ) {
+                (Some(i), Some(j)) if i < j => (pair.keys[0], pair.keys[1]),
+                (Some(_), Some(_)) => (pair.keys[1], pair.keys[0]),
+                _ => continue,
+            };
+            match pair.policy {
+                SocdPolicy::LastInputPriority => keys.retain(|k| *k != first),
+                SocdPolicy::FirstWins => keys.retain(|k| *k != last),
+                SocdPolicy::Neutral => keys.retain(|k| *k != first && *k != last),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+fn clean_ticks(policy: SocdPolicy, ticks: &[&[KeyCode]]) -> Vec<KeyCode> {
+    let mut socd = Socd::new(vec![SocdPair {
+        keys: [KeyCode::A, KeyCode::D],
+        policy,
+    }]);
+    let mut keys = Vec::new();
+    for tick in ticks {
+        keys = tick.to_vec();
+        socd.clean(&mut keys);
+    }
+    keys
+}
+
+#[test]
+fn socd_policies_use_the_press_order() {
+    use KeyCode::*;
+    let a_then_d: &[&[KeyCode]] = &[&[W, A], &[W, A, D]];
+    let d_then_a: &[&[KeyCode]] = &[&[D], &[A, D]];
+    assert_eq!(clean_ticks(SocdPolicy::LastInputPriority, a_then_d), [W, D]);
+    assert_eq!(clean_ticks(SocdPolicy::LastInputPriority, d_then_a), [A]);
+    assert_eq!(clean_ticks(SocdPolicy::FirstWins, a_then_d), [W, A]);
+    assert_eq!(clean_ticks(SocdPolicy::FirstWins, d_then_a), [D]);
+    assert_eq!(clean_ticks(SocdPolicy::Neutral, a_then_d), [W]);
+    assert_eq!(clean_ticks(SocdPolicy::Neutral, d_then_a), []);
+}
+
+#[test]
+fn socd_forgets_released_keys() {
+    use KeyCode::*;
+    // a is released and pressed again after d, so it is now the last key.
+    let ticks: &[&[KeyCode]] = &[&[A], &[A, D], &[D], &[A, D]];
+    assert_eq!(clean_ticks(SocdPolicy::LastInputPriority, ticks), [A]);
+    assert_eq!(clean_ticks(SocdPolicy::FirstWins, ticks), [D]);
+}
+
+#[test]
+fn socd_keeps_a_lone_key() {
+    use KeyCode::*;
+    assert_eq!(clean_ticks(SocdPolicy::Neutral, &[&[A, D], &[A]]), [A]);
+}
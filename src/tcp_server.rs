@@ -0,0 +1,182 @@
+//! TCP server that lets other programs control kanata, e.g. to change the layer from a window
+//! manager script. Clients send one JSON message per line:
+//!
+//! ```text
+//! {"ChangeLayer":{"new":"<layer name>"}}
+//! ```
+//!
+//! The server sends the active layer to every client when it connects and whenever the layer
+//! changes, also one JSON message per line:
+//!
+//! ```text
+//! {"LayerChange":{"new":"<layer name>"}}
+//! ```
+//!
+//! A client that stops reading its messages is disconnected once a write to it times out.
+//!
+//! The server only listens on localhost.
+
+use std::io::{BufRead, BufReader, Write};
+use std::net::{Ipv4Addr, Shutdown, TcpListener, TcpStream};
+use std::sync::Arc;
+use std::time::Duration;
+
+use anyhow::{anyhow, Result};
+use crossbeam_channel::{Receiver, Sender};
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+
+use crate::kanata::Kanata;
+
+/// A message from a client.
+#[derive(Debug, Deserialize)]
+enum ClientMessage {
+    /// Switch the base layer like layer-switch.
+    ChangeLayer { new: String },
+}
+
+/// A message to the clients.
+#[derive(Debug, Serialize)]
+enum ServerMessage {
+    /// The active layer, sent when a client connects and whenever it changes.
+    LayerChange { new: String },
+}
+
+/// Channels to the writer threads of the clients, which write the messages to the connections.
+/// The connections themselves are never written to while the list is locked.
+type Clients = Arc<Mutex<Vec<Sender<String>>>>;
+
+/// Time after which a client that doesn't read its messages is disconnected.
+const WRITE_TIMEOUT: Duration = Duration::from_secs(1);
+
+/// Start listening on the port and handle the messages of the clients in other threads.
+pub fn start(port: u16, kanata: Arc<Mutex<Kanata>>) -> Result<()> {
+    let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, port))
+        .map_err(|e| anyhow!("could not start the TCP server on port {}: {}", port, e))?;
+    log::info!("TCP server: listening on port {}", port);
+    let clients = Clients::default();
+
+    // The layer changes are written to the clients by another thread, so that a slow client
+    // doesn't delay the processing loop.
+    let (layer_change_tx, layer_change_rx) = crossbeam_channel::unbounded();
+    kanata.lock().layer_change_tx = Some(layer_change_tx);
+    let broadcast_clients = clients.clone();
+    std::thread::spawn(move || broadcast_layer_changes(layer_change_rx, broadcast_clients));
+
+    std::thread::spawn(move || {
+        for stream in listener.incoming() {
+            let stream = match stream {
+                Ok(stream) => stream,
+                Err(e) => {
+                    log::warn!("TCP server: could not accept a client: {}", e);
+                    continue;
+                }
+            };
+            let addr = match stream.peer_addr() {
+                Ok(addr) => addr.to_string(),
+                Err(_) => "unknown client".into(),
+            };
+            log::info!("TCP server: {} connected", addr);
+            let writer = match stream
+                .set_write_timeout(Some(WRITE_TIMEOUT))
+                .and_then(|_| stream.try_clone())
+            {
+                Ok(writer) => writer,
+                Err(e) => {
+                    log::warn!(
+                        "TCP server: could not use the connection of {}: {}",
+                        addr,
+                        e
+                    );
+                    continue;
+                }
+            };
+            let (tx, rx) = crossbeam_channel::unbounded();
+            // The layer is looked up while holding the list of clients, so that a layer change
+            // is either sent after this message or included in it.
+            let mut clients = clients.lock();
+            let _ = tx.send(layer_change_msg(kanata.lock().current_layer_name()));
+            clients.push(tx);
+            drop(clients);
+            let writer_addr = addr.clone();
+            std::thread::spawn(move || write_messages(rx, writer, writer_addr));
+            let kanata = kanata.clone();
+            std::thread::spawn(move || handle_client(stream, addr, kanata));
+        }
+    });
+    Ok(())
+}
+
+/// Send every layer change to the writer threads of all clients, and forget the clients whose
+/// writer thread has stopped.
+fn broadcast_layer_changes(rx: Receiver<String>, clients: Clients) {
+    for layer in rx {
+        let msg = layer_change_msg(&layer);
+        clients.lock().retain(|tx| tx.send(msg.clone()).is_ok());
+    }
+}
+
+/// Write the messages to the connection of a client until writing fails or times out. The
+/// connection is then closed, which also ends the thread that reads from it.
+fn write_messages(rx: Receiver<String>, mut stream: TcpStream, addr: String) {
+    for msg in rx {
+        if let Err(e) = stream.write_all(msg.as_bytes()) {
+            log::debug!("TCP server: could not send the layer to {}: {}", addr, e);
+            let _ = stream.shutdown(Shutdown::Both);
+            return;
+        }
+    }
+}
+
+fn layer_change_msg(layer: &str) -> String {
+    let msg = ServerMessage::LayerChange { new: layer.into() };
+    let mut line = serde_json::to_string(&msg).expect("the message can be serialized");
+    line.push('\n');
+    line
+}
+
+fn handle_client(stream: TcpStream, addr: String, kanata: Arc<Mutex<Kanata>>) {
+    for line in BufReader::new(stream).lines() {
+        let line = match line {
+            Ok(line) => line,
+            Err(_) => break,
+        };
+        if line.trim().is_empty() {
+            continue;
+        }
+        if let Err(e) = handle_message(&line, &kanata) {
+            log::warn!("TCP server: invalid message from {}: {}: {}", addr, e, line);
+        }
+    }
+    log::info!("TCP server: {} disconnected", addr);
+}
+
+fn handle_message(msg: &str, kanata: &Mutex<Kanata>) -> Result<()> {
+    let msg = serde_json::from_str(msg).map_err(|e| {
+        anyhow!(
+            "{}, expected {{\"ChangeLayer\":{{\"new\":\"<layer name>\"}}}}",
+            e
+        )
+    })?;
+    match msg {
+        ClientMessage::ChangeLayer { new } => kanata.lock().change_layer(&new),
+    }
+}
+
+#[test]
+fn messages_are_one_json_object_per_line() {
+    assert_eq!(
+        layer_change_msg("a\"b"),
+        "{\"LayerChange\":{\"new\":\"a\\\"b\"}}\n"
+    );
+    let msg: ClientMessage = serde_json::from_str(r#"{"ChangeLayer":{"new":"qwerty"}}"#).unwrap();
+    assert!(matches!(msg, ClientMessage::ChangeLayer { new } if new == "qwerty"));
+    // Deeply nested unknown fields are skipped without recursion.
+    let nested = format!(
+        r#"{{"ChangeLayer":{{"new":"qwerty","x":{}{}}}}}"#,
+        "[".repeat(100_000),
+        "]".repeat(100_000)
+    );
+    let msg: ClientMessage = serde_json::from_str(&nested).unwrap();
+    assert!(matches!(msg, ClientMessage::ChangeLayer { new } if new == "qwerty"));
+}
@@ -0,0 +1,123 @@
+//! System tray icon on Windows that shows the active layer in its tooltip and has a menu to reload
+//! the configuration, pause kanata, and exit.
+
+use std::rc::Rc;
+use std::sync::atomic::Ordering;
+use std::sync::Arc;
+
+use anyhow::Result;
+use native_windows_gui as nwg;
+use parking_lot::Mutex;
+
+use crate::kanata::{Kanata, PAUSED};
+
+#[derive(Default)]
+struct Controls {
+    window: nwg::MessageWindow,
+    icon: nwg::Icon,
+    tray: nwg::TrayNotification,
+    menu: nwg::Menu,
+    reload: nwg::MenuItem,
+    pause: nwg::MenuItem,
+    exit: nwg::MenuItem,
+    layer_change: nwg::Notice,
+}
+
+/// The tray icon, which is removed when it is dropped.
+pub struct Tray {
+    // The controls are only used by the event handler, but must live as long as the icon.
+    _controls: Rc<Controls>,
+    handler: nwg::EventHandler,
+}
+
+impl Tray {
+    /// Create the tray icon. Its events are handled by native_windows_gui::dispatch_thread_events
+    /// of the same thread.
+    pub fn build(kanata: Arc<Mutex<Kanata>>) -> Result<Self> {
+        let mut c = Controls::default();
+        nwg::MessageWindow::builder().build(&mut c.window)?;
+        nwg::Icon::builder()
+            .source_system(Some(nwg::OemIcon::Information))
+            .build(&mut c.icon)?;
+        nwg::TrayNotification::builder()
+            .parent(&c.window)
+            .icon(Some(&c.icon))
+            .tip(Some(&tip(&kanata.lock())))
+            .build(&mut c.tray)?;
+        nwg::Menu::builder()
+            .popup(true)
+            .parent(&c.window)
+            .build(&mut c.menu)?;
+        nwg::MenuItem::builder()
+            .text("Reload configuration")
+            .parent(&c.menu)
+            .build(&mut c.reload)?;
+        nwg::MenuItem::builder()
+            .text("Pause")
+            .check(false)
+            .parent(&c.menu)
+            .build(&mut c.pause)?;
+        nwg::MenuItem::builder()
+            .text("Exit")
+            .parent(&c.menu)
+            .build(&mut c.exit)?;
+        nwg::Notice::builder()
+            .parent(&c.window)
+            .build(&mut c.layer_change)?;
+        kanata.lock().layer_change_notice = Some(c.layer_change.sender());
+
+        let c = Rc::new(c);
+        let controls = Rc::downgrade(&c);
+        let handler = nwg::full_bind_event_handler(&c.window.handle, move |evt, _, handle| {
+            let c = match controls.upgrade() {
+                Some(c) => c,
+                None => return,
+            };
+            match evt {
+                nwg::Event::OnContextMenu if handle == c.tray.handle => {
+                    let (x, y) = nwg::GlobalCursor::position();
+                    c.menu.popup(x, y);
+                }
+                nwg::Event::OnMenuItemSelected if handle == c.reload.handle => {
+                    log::info!("Tray: reloading the configuration");
+                    kanata.lock().request_live_reload();
+                }
+                nwg::Event::OnMenuItemSelected if handle == c.pause.handle => {
+                    let paused = !c.pause.checked();
+                    c.pause.set_checked(paused);
+                    PAUSED.store(paused, Ordering::SeqCst);
+                    log::info!("Tray: paused: {}", paused);
+                    c.tray.set_tip(&tip(&kanata.lock()));
+                }
+                nwg::Event::OnMenuItemSelected if handle == c.exit.handle => {
+                    log::info!("Tray: exiting");
+                    nwg::stop_thread_dispatch();
+                }
+                nwg::Event::OnNotice if handle == c.layer_change.handle => {
+                    c.tray.set_tip(&tip(&kanata.lock()));
+                }
+                _ => {}
+            }
+        });
+        Ok(Self {
+            _controls: c,
+            handler,
+        })
+    }
+}
+
+impl Drop for Tray {
+    fn drop(&mut self) {
+        nwg::unbind_event_handler(&self.handler);
+    }
+}
+
+/// Returns the tooltip of the tray icon, which shows the active layer.
+fn tip(kanata: &Kanata) -> String {
+    let paused = if PAUSED.load(Ordering::SeqCst) {
+        " (paused)"
+    } else {
+        ""
+    };
+    format!("kanata: {}{}", kanata.current_layer_name(), paused)
+}
@@ -0,0 +1,200 @@
+//! Zippy: chorded text expansion. Pressing two or more keys of a dictionary entry at nearly the
+//! same time replaces the characters that the keys typed with the text of the entry.
+//!
+//! Unlike chords, the key presses are not buffered. The keys type their characters as usual and
+//! the chord is only resolved when the first of its keys is released, at which point the typed
+//! characters are erased with backspaces. A suffix chord pressed right after an expansion appends
+//! its text to the expanded word, e.g. to turn "do " into "doing ".
+
+use std::collections::{HashMap, HashSet};
+
+/// Text to type in place of a chord.
+#[derive(Debug, PartialEq, Eq)]
+pub struct ZippyOutput {
+    /// Number of characters to erase before typing the text.
+    pub backspaces: usize,
+    pub text: String,
+}
+
+/// The dictionary of the configuration and the state of the chord that is being typed.
+#[derive(Default)]
+pub struct Zippy {
+    /// Expansions of the chords, keyed by the sorted coordinates of their keys.
+    words: HashMap<Vec<u8>, String>,
+    /// Texts that suffix chords append to the previous expansion.
+    suffixes: HashMap<Vec<u8>, String>,
+    /// All keys that participate in at least one chord.
+    participants: HashSet<u8>,
+    /// Maximum number of milliseconds between two key presses of a chord.
+    timeout: u16,
+    /// Keys of the chord that is being typed, in the order that they were pressed.
+    pressed: Vec<u8>,
+    /// Whether the keys in `pressed` were already handled as a chord.
+    resolved: bool,
+    ticks_since_press: u16,
+    /// Whether the last output was an expansion that a suffix chord can extend.
+    expanded: bool,
+}
+
+impl Zippy {
+    pub fn new(
+        words: HashMap<Vec<u8>, String>,
+        suffixes: HashMap<Vec<u8>, String>,
+        timeout: u16,
+    ) -> Self {
+        let participants = words
+            .keys()
+            .chain(suffixes.keys())
+            .flat_map(|keys| keys.iter().copied())
+            .collect();
+        Self {
+            words,
+            suffixes,
+            participants,
+            timeout,
+            ..Default::default()
+        }
+    }
+
+    /// Handle a key press.
+    pub fn press(&mut self, coord: u8) {
+        if !self.participants.contains(&coord) {
+            self.pressed.clear();
+            self.expanded = false;
+            return;
+        }
+        if self.resolved || self.ticks_since_press > self.timeout {
+            self.pressed.clear();
+            self.resolved = false;
+        }
+        self.pressed.push(coord);
+        self.ticks_since_press = 0;
+    }
+
+    /// Handle a key release. Returns the text that replaces the typed characters if the release
+    /// completes a chord of the dictionary.
+    pub fn release(&mut self, coord: u8) -> Option<ZippyOutput> {
+        if self.resolved || !self.pressed.contains(&coord) {
+            return None;
+        }
+        self.resolved = true;
+        if self.pressed.len() < 2 {
+            self.expanded = false;
+            return None;
+        }
+        let mut keys = self.pressed.clone();
+        keys.sort_unstable();
+        let suffix = match self.expanded {
+            true => self.suffixes.get(&keys),
+            false => None,
+        };
+        // Every chord key typed one character. A suffix also erases the space that was typed
+        // after the expanded word.
+        let output = match (suffix, self.words.get(&keys)) {
+            (Some(suffix), _) => ZippyOutput {
+                backspaces: keys.len() + 1,
+                text: format!("{} ", suffix),
+            },
+            (None, Some(word)) => ZippyOutput {
+                backspaces: keys.len(),
+                text: format!("{} ", word),
+            },
+            (None, None) => {
+                self.expanded = false;
+                return None;
+            }
+        };
+        log::debug!("zippy     {:?} -> {:?}", keys, output.text);
+        self.expanded = true;
+        Some(output)
+    }
+
+    /// Advance the state by one millisecond.
+    pub fn tick(&mut self) {
+        self.ticks_since_press = self.ticks_since_press.saturating_add(1);
+    }
+}
+
+#[cfg(test)]
+fn test_zippy() -> Zippy {
+    Zippy::new(
+        [
+            (vec![1, 2], "do".to_string()),
+            (vec![1, 2, 3], "does".to_string()),
+        ]
+        .into(),
+        [(vec![4, 5], "ing".to_string())].into(),
+        20,
+    )
+}
+
+#[test]
+fn chord_is_expanded_on_the_first_release() {
+    let mut zippy = test_zippy();
+    zippy.press(2);
+    zippy.tick();
+    zippy.press(1);
+    assert_eq!(
+        zippy.release(1),
+        Some(ZippyOutput {
+            backspaces: 2,
+            text: "do ".into()
+        })
+    );
+    assert_eq!(zippy.release(2), None);
+}
+
+#[test]
+fn single_key_or_unknown_chord_is_not_expanded() {
+    let mut zippy = test_zippy();
+    zippy.press(1);
+    assert_eq!(zippy.release(1), None);
+    zippy.press(2);
+    zippy.press(4);
+    assert_eq!(zippy.release(4), None);
+}
+
+#[test]
+fn slow_presses_are_not_a_chord() {
+    let mut zippy = test_zippy();
+    zippy.press(1);
+    for _ in 0..21 {
+        zippy.tick();
+    }
+    zippy.press(2);
+    assert_eq!(zippy.release(2), None);
+}
+
+#[test]
+fn other_key_interrupts_the_chord() {
+    let mut zippy = test_zippy();
+    zippy.press(1);
+    zippy.press(9);
+    zippy.press(2);
+    assert_eq!(zippy.release(2), None);
+}
+
+#[test]
+fn suffix_extends_only_an_expansion() {
+    let mut zippy = test_zippy();
+    zippy.press(4);
+    zippy.press(5);
+    assert_eq!(zippy.release(5), None);
+    zippy.release(4);
+
+    zippy.press(1);
+    zippy.press(2);
+    zippy.press(3);
+    assert_eq!(zippy.release(3).map(|o| o.text), Some("does ".to_string()));
+    zippy.release(1);
+    zippy.release(2);
+    zippy.press(4);
+    zippy.press(5);
+    assert_eq!(
+        zippy.release(4),
+        Some(ZippyOutput {
+            backspaces: 3,
+            text: "ing ".into()
+        })
+    );
+}